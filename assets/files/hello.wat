@@ -0,0 +1,30 @@
+;; Source of `hello.wasm`, a minimal plugin used by `tests/typ/compute/plugin.typ`.
+;;
+;; Rebuild it with `wat2wasm` from the WebAssembly Binary Toolkit (wabt):
+;;
+;;     wat2wasm assets/files/hello.wat -o assets/files/hello.wasm
+(module
+  (import "typst_env" "wasm_minimal_protocol_write_args_to_buffer"
+    (func $write_args_to_buffer (param i32)))
+  (import "typst_env" "wasm_minimal_protocol_send_result_to_host"
+    (func $send_result_to_host (param i32 i32)))
+
+  (memory (export "memory") 1)
+  (data (i32.const 0) "Hello")
+  (data (i32.const 16) "oops")
+
+  ;; Returns its single argument unchanged.
+  (func (export "echo") (param $len i32) (result i32)
+    (call $write_args_to_buffer (i32.const 0))
+    (call $send_result_to_host (i32.const 0) (local.get $len))
+    (i32.const 0))
+
+  ;; Returns the bytes of "Hello".
+  (func (export "hello") (result i32)
+    (call $send_result_to_host (i32.const 0) (i32.const 5))
+    (i32.const 0))
+
+  ;; Fails with the message "oops".
+  (func (export "fail") (result i32)
+    (call $send_result_to_host (i32.const 16) (i32.const 4))
+    (i32.const 1)))
@@ -0,0 +1,11 @@
+;; Source of `loop.wasm`, a plugin that never terminates, used by
+;; `tests/typ/compute/plugin.typ`.
+;;
+;; Rebuild it with `wat2wasm` from the WebAssembly Binary Toolkit (wabt):
+;;
+;;     wat2wasm assets/files/loop.wat -o assets/files/loop.wasm
+(module
+  ;; Loops forever.
+  (func (export "loop") (result i32)
+    (loop (br 0))
+    (i32.const 0)))
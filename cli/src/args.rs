@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::{ArgAction, Parser, Subcommand};
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
 
 /// typst creates PDF files from .typ files
 #[derive(Debug, Clone, Parser)]
@@ -14,6 +14,14 @@ pub struct CliArguments {
     #[clap(long = "root", env = "TYPST_ROOT", value_name = "DIR")]
     pub root: Option<PathBuf>,
 
+    /// Configure the directory in which local packages are looked up
+    #[clap(long = "package-path", env = "TYPST_PACKAGE_PATH", value_name = "DIR")]
+    pub package_path: Option<PathBuf>,
+
+    /// The format to emit diagnostics in
+    #[clap(long = "diagnostic-format", default_value = "human", value_name = "FORMAT")]
+    pub diagnostic_format: DiagnosticFormat,
+
     /// The typst command to run
     #[command(subcommand)]
     pub command: Command,
@@ -35,6 +43,15 @@ pub enum Command {
     #[command(visible_alias = "w")]
     Watch(CompileCommand),
 
+    /// Processes an input file to extract provided metadata
+    Query(QueryCommand),
+
+    /// Formats Typst source files
+    Fmt(FmtCommand),
+
+    /// Runs a language server that communicates over standard input and output
+    Lsp(LspCommand),
+
     /// List all discovered fonts in system and custom font paths
     Fonts(FontsCommand),
 }
@@ -45,7 +62,9 @@ impl Command {
         match self {
             Command::Compile(cmd) => Some(cmd),
             Command::Watch(cmd) => Some(cmd),
-            Command::Fonts(_) => None,
+            Command::Query(_) | Command::Fmt(_) | Command::Lsp(_) | Command::Fonts(_) => {
+                None
+            }
         }
     }
 
@@ -61,19 +80,105 @@ pub struct CompileCommand {
     /// Path to input Typst file
     pub input: PathBuf,
 
-    /// Path to output PDF file
+    /// Path to output file (PDF, PNG, or SVG). For PNG and SVG output of
+    /// multi-page documents, the path must contain `{n}` (page number) or
+    /// `{p}` (zero-padded page number) to write one file per page
     pub output: Option<PathBuf>,
 
     /// Opens the output file after compilation using the default PDF viewer
     #[arg(long = "open")]
     pub open: Option<Option<String>>,
 
+    /// The PPI (pixels per inch) to use for PNG export
+    #[arg(long = "ppi", default_value_t = 144.0, value_parser = parse_ppi)]
+    pub ppi: f32,
+
     /// Produces a flamegraph of the compilation process and saves it to the
     /// given file or to `flamegraph.svg` in the current working directory.
     #[arg(long = "flamegraph", value_name = "OUTPUT_SVG")]
     pub flamegraph: Option<Option<PathBuf>>,
 }
 
+/// The maximum PPI accepted for PNG export. Higher values quickly lead to
+/// pixmaps that are too large to allocate.
+const MAX_PPI: f32 = 4800.0;
+
+/// Parse and validate the `--ppi` argument.
+fn parse_ppi(value: &str) -> Result<f32, String> {
+    let ppi: f32 = value.parse().map_err(|_| format!("`{value}` is not a number"))?;
+    if !(ppi > 0.0 && ppi <= MAX_PPI) {
+        return Err(format!("must be larger than 0 and at most {MAX_PPI}"));
+    }
+    Ok(ppi)
+}
+
+/// Processes an input file to extract provided metadata
+#[derive(Debug, Clone, Parser)]
+pub struct QueryCommand {
+    /// Path to input Typst file
+    pub input: PathBuf,
+
+    /// Defines which elements to retrieve, e.g. `"<label>"` or
+    /// `"heading.where(level: 1)"`
+    pub selector: String,
+
+    /// Extracts just one field from all retrieved elements. Elements that
+    /// don't have the field yield `none`
+    #[arg(long = "field")]
+    pub field: Option<String>,
+
+    /// Expects and retrieves exactly one element
+    #[arg(long = "one")]
+    pub one: bool,
+
+    /// The format to serialize in
+    #[arg(long = "format", default_value = "json")]
+    pub format: SerializationFormat,
+}
+
+/// Formats Typst source files
+#[derive(Debug, Clone, Parser)]
+pub struct FmtCommand {
+    /// Paths to the Typst files to format
+    #[arg(required = true)]
+    pub files: Vec<PathBuf>,
+
+    /// Only checks whether the files are formatted instead of rewriting them
+    #[arg(long = "check")]
+    pub check: bool,
+
+    /// The maximum line width
+    #[arg(long = "width", default_value_t = 80)]
+    pub width: usize,
+}
+
+/// Runs a language server that communicates over standard input and output
+#[derive(Debug, Clone, Parser)]
+pub struct LspCommand {
+    /// Path to the Typst file to compile, relative to the root. Defaults to
+    /// the most recently saved file
+    #[arg(long = "main")]
+    pub main: Option<PathBuf>,
+}
+
+/// A format in which diagnostics are emitted.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum DiagnosticFormat {
+    /// Rich output with annotated source snippets
+    Human,
+    /// One line per diagnostic: `path:line:column: severity: message`
+    Short,
+    /// One JSON object per line and diagnostic
+    Json,
+}
+
+/// A format in which query results can be serialized.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, ValueEnum)]
+pub enum SerializationFormat {
+    Json,
+    Yaml,
+}
+
 /// List all discovered fonts in system and custom font paths
 #[derive(Debug, Clone, Parser)]
 pub struct FontsCommand {
@@ -81,3 +186,30 @@ pub struct FontsCommand {
     #[arg(long)]
     pub variants: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse the `--ppi` argument of a compile command.
+    fn ppi(value: &str) -> Result<f32, clap::Error> {
+        let args = CliArguments::try_parse_from([
+            "typst", "compile", "in.typ", "out.png", "--ppi", value,
+        ])?;
+        Ok(args.command.as_compile().unwrap().ppi)
+    }
+
+    #[test]
+    fn test_ppi_validation() {
+        assert_eq!(ppi("144").unwrap(), 144.0);
+        assert_eq!(ppi("72.5").unwrap(), 72.5);
+        assert_eq!(ppi("4800").unwrap(), 4800.0);
+        assert!(ppi("0").is_err());
+        assert!(ppi("-10").is_err());
+        assert!(ppi("4801").is_err());
+        assert!(ppi("1e30").is_err());
+        assert!(ppi("NaN").is_err());
+        assert!(ppi("inf").is_err());
+        assert!(ppi("many").is_err());
+    }
+}
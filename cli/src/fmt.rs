@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+use ecow::eco_format;
+use typst::diag::StrResult;
+use typst::syntax::{format, parse};
+
+use crate::args::{CliArguments, Command, FmtCommand};
+
+/// A summary of the input arguments relevant to formatting.
+pub struct FmtSettings {
+    /// The paths to the files to format.
+    files: Vec<PathBuf>,
+
+    /// Whether to only check if the files are formatted.
+    check: bool,
+
+    /// The maximum line width.
+    width: usize,
+}
+
+impl FmtSettings {
+    /// Create new formatting settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a formatting command.
+    pub fn with_arguments(args: CliArguments) -> Self {
+        let FmtCommand { files, check, width } = match args.command {
+            Command::Fmt(command) => command,
+            _ => unreachable!(),
+        };
+
+        Self { files, check, width }
+    }
+}
+
+/// Execute a formatting command.
+pub fn fmt(command: FmtSettings) -> StrResult<()> {
+    tracing::info!("Starting formatting");
+
+    let mut unformatted = false;
+    for path in &command.files {
+        let text = fs::read_to_string(path)
+            .map_err(|err| eco_format!("failed to read {} ({err})", path.display()))?;
+
+        let Some(formatted) = format(&parse(&text), command.width) else {
+            return Err(eco_format!(
+                "failed to format {}: file contains syntax errors",
+                path.display()
+            ));
+        };
+
+        if formatted == text {
+            continue;
+        }
+
+        if command.check {
+            println!("{}", path.display());
+            unformatted = true;
+        } else {
+            fs::write(path, formatted).map_err(|err| {
+                eco_format!("failed to write {} ({err})", path.display())
+            })?;
+        }
+    }
+
+    if unformatted {
+        process::exit(1);
+    }
+
+    Ok(())
+}
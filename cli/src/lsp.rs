@@ -0,0 +1,918 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use ecow::{eco_format, EcoString};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    DidSaveTextDocument, Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{
+    Completion, GotoDefinition, HoverRequest, References, Rename, Request as _,
+    SemanticTokensFullRequest,
+};
+use lsp_types::{self as lsp, SemanticTokenType, Url};
+use serde::{Deserialize, Serialize};
+use typst::diag::{Severity, SourceError, StrResult};
+use typst::doc::Frame;
+use typst::eval::Tracer;
+use typst::ide::{
+    autocomplete, definition, highlight, jump_from_cursor, references, rename, tooltip,
+    CompletionKind, Tag, Tooltip,
+};
+use typst::syntax::{is_newline, LinkedNode, Source};
+use typst::World;
+
+use crate::args::{CliArguments, Command, LspCommand};
+use crate::{create_world, SystemWorld};
+
+/// A summary of the input arguments relevant to the language server.
+pub struct LspSettings {
+    /// The file to compile instead of the saved one, if any.
+    main: Option<PathBuf>,
+
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+
+    /// The directory in which local packages are looked up.
+    package_path: Option<PathBuf>,
+}
+
+impl LspSettings {
+    /// Create new language server settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a language server command.
+    pub fn with_arguments(args: CliArguments) -> Self {
+        let LspCommand { main } = match args.command {
+            Command::Lsp(command) => command,
+            _ => unreachable!(),
+        };
+
+        Self {
+            main,
+            root: args.root,
+            font_paths: args.font_paths,
+            package_path: args.package_path,
+        }
+    }
+}
+
+/// Execute a language server command.
+///
+/// Communicates with the client over standard input and output.
+pub fn lsp(command: LspSettings) -> StrResult<()> {
+    tracing::info!("Starting language server");
+
+    let (connection, threads) = Connection::stdio();
+    let (id, params) = connection
+        .initialize_start()
+        .map_err(|err| eco_format!("failed to initialize language server ({err})"))?;
+    let params: lsp::InitializeParams = serde_json::from_value(params)
+        .map_err(|err| eco_format!("failed to parse initialization ({err})"))?;
+
+    let result = lsp::InitializeResult {
+        capabilities: capabilities(),
+        server_info: Some(lsp::ServerInfo {
+            name: "typst".into(),
+            version: Some(crate::typst_version().into()),
+        }),
+    };
+
+    let result = serde_json::to_value(result).map_err(|err| err.to_string())?;
+    connection
+        .initialize_finish(id, result)
+        .map_err(|err| eco_format!("failed to initialize language server ({err})"))?;
+
+    // Prefer the configured root, then the client's workspace, then the
+    // working directory.
+    let root = command
+        .root
+        .clone()
+        .or_else(|| params.root_uri.and_then(|uri| uri.to_file_path().ok()))
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+
+    let world = create_world(
+        &root,
+        Some(&root),
+        &command.font_paths,
+        command.package_path.as_deref(),
+    );
+
+    let mut server = Server {
+        world,
+        main: command.main.map(|main| root.join(main)),
+        open: HashSet::new(),
+        frames: vec![],
+        diagnosed: HashSet::new(),
+    };
+
+    server.run(&connection)?;
+
+    drop(connection);
+    threads
+        .join()
+        .map_err(|err| eco_format!("failed to shut down language server ({err})"))?;
+
+    tracing::info!("Language server shut down");
+    Ok(())
+}
+
+/// The capabilities the server announces to the client.
+fn capabilities() -> lsp::ServerCapabilities {
+    lsp::ServerCapabilities {
+        text_document_sync: Some(lsp::TextDocumentSyncCapability::Options(
+            lsp::TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(lsp::TextDocumentSyncKind::INCREMENTAL),
+                save: Some(lsp::TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        completion_provider: Some(lsp::CompletionOptions {
+            trigger_characters: Some(
+                ["#", ".", "@", "<", "(", "$"].map(String::from).to_vec(),
+            ),
+            ..Default::default()
+        }),
+        hover_provider: Some(lsp::HoverProviderCapability::Simple(true)),
+        definition_provider: Some(lsp::OneOf::Left(true)),
+        references_provider: Some(lsp::OneOf::Left(true)),
+        rename_provider: Some(lsp::OneOf::Left(true)),
+        semantic_tokens_provider: Some(
+            lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                lsp::SemanticTokensOptions {
+                    legend: lsp::SemanticTokensLegend {
+                        token_types: TOKEN_TYPES.to_vec(),
+                        token_modifiers: vec![],
+                    },
+                    full: Some(lsp::SemanticTokensFullOptions::Bool(true)),
+                    ..Default::default()
+                },
+            ),
+        ),
+        ..Default::default()
+    }
+}
+
+/// The state of a running language server.
+struct Server {
+    /// The world that serves sources, fonts and files.
+    world: SystemWorld,
+    /// The file to compile instead of the saved one, if any.
+    main: Option<PathBuf>,
+    /// The documents that are currently open in the client.
+    open: HashSet<PathBuf>,
+    /// The pages of the last successful compilation.
+    frames: Vec<Frame>,
+    /// The documents for which diagnostics were last published.
+    diagnosed: HashSet<Url>,
+}
+
+impl Server {
+    /// Handle messages until the client shuts the server down.
+    fn run(&mut self, connection: &Connection) -> StrResult<()> {
+        for message in &connection.receiver {
+            match message {
+                Message::Request(request) => {
+                    let shutdown = connection
+                        .handle_shutdown(&request)
+                        .map_err(|err| eco_format!("failed to shut down ({err})"))?;
+                    if shutdown {
+                        break;
+                    }
+
+                    let response = self.request(request);
+                    send(connection, response.into())?;
+                }
+                Message::Notification(notification) => {
+                    for notification in self.notification(notification) {
+                        send(connection, notification.into())?;
+                    }
+                }
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Answer a request from the client.
+    fn request(&mut self, request: Request) -> Response {
+        match request.method.as_str() {
+            Completion::METHOD => {
+                respond::<Completion>(request, |p| Ok(self.complete(p)))
+            }
+            HoverRequest::METHOD => {
+                respond::<HoverRequest>(request, |p| Ok(self.hover(p)))
+            }
+            GotoDefinition::METHOD => {
+                respond::<GotoDefinition>(request, |p| Ok(self.definition(p)))
+            }
+            References::METHOD => {
+                respond::<References>(request, |p| Ok(self.references(p)))
+            }
+            SemanticTokensFullRequest::METHOD => {
+                respond::<SemanticTokensFullRequest>(request, |p| Ok(self.tokens(p)))
+            }
+            Rename::METHOD => respond::<Rename>(request, |p| self.rename(p)),
+            JumpFromCursor::METHOD => {
+                respond::<JumpFromCursor>(request, |p| Ok(self.jump(p)))
+            }
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request: {}", request.method),
+            ),
+        }
+    }
+
+    /// Process a notification from the client, possibly producing
+    /// notifications in return.
+    fn notification(&mut self, notification: Notification) -> Vec<Notification> {
+        let params = notification.params;
+        let result = match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                serde_json::from_value(params).map(|p| self.did_open(p))
+            }
+            DidChangeTextDocument::METHOD => {
+                serde_json::from_value(params).map(|p| self.did_change(p))
+            }
+            DidSaveTextDocument::METHOD => {
+                serde_json::from_value(params).map(|p| self.did_save(p))
+            }
+            DidCloseTextDocument::METHOD => {
+                serde_json::from_value(params).map(|p| self.did_close(p))
+            }
+            _ => return vec![],
+        };
+
+        result.unwrap_or_else(|err| {
+            tracing::warn!("Failed to parse notification ({err})");
+            vec![]
+        })
+    }
+
+    /// Take over the client's version of a newly opened document.
+    fn did_open(&mut self, params: lsp::DidOpenTextDocumentParams) -> Vec<Notification> {
+        let document = params.text_document;
+        let Ok(path) = document.uri.to_file_path() else { return vec![] };
+        if let Ok(id) = self.world.resolve(&path) {
+            self.world.source_mut(id).replace(document.text);
+        }
+
+        self.open.insert(path.clone());
+        self.compile(path)
+    }
+
+    /// Apply the client's edits to an open document.
+    fn did_change(
+        &mut self,
+        params: lsp::DidChangeTextDocumentParams,
+    ) -> Vec<Notification> {
+        let Ok(path) = params.text_document.uri.to_file_path() else { return vec![] };
+        let Ok(id) = self.world.resolve(&path) else { return vec![] };
+
+        let source = self.world.source_mut(id);
+        for change in params.content_changes {
+            match change.range.and_then(|range| to_byte_range(source, range)) {
+                Some(range) => {
+                    source.edit(range, &change.text);
+                }
+                None => source.replace(change.text),
+            }
+        }
+
+        vec![]
+    }
+
+    /// Recompile after a document was saved.
+    fn did_save(&mut self, params: lsp::DidSaveTextDocumentParams) -> Vec<Notification> {
+        let Ok(path) = params.text_document.uri.to_file_path() else { return vec![] };
+        self.compile(path)
+    }
+
+    /// Forget about a closed document.
+    fn did_close(
+        &mut self,
+        params: lsp::DidCloseTextDocumentParams,
+    ) -> Vec<Notification> {
+        if let Ok(path) = params.text_document.uri.to_file_path() {
+            self.open.remove(&path);
+        }
+
+        vec![]
+    }
+
+    /// Compile the document and publish its diagnostics.
+    ///
+    /// Compiles the configured main file or, if there is none, the given one.
+    fn compile(&mut self, path: PathBuf) -> Vec<Notification> {
+        tracing::info!("Starting compilation");
+
+        // Reload everything from disk except for the open documents, whose
+        // contents the client owns.
+        let texts: Vec<_> = self
+            .open
+            .iter()
+            .filter_map(|path| {
+                let id = self.world.resolve(path).ok()?;
+                Some((path.clone(), self.world.source(id).text().to_string()))
+            })
+            .collect();
+
+        self.world.reset();
+        for (path, text) in texts {
+            if let Ok(id) = self.world.resolve(&path) {
+                self.world.source_mut(id).replace(text);
+            }
+        }
+
+        let main = self.main.clone().unwrap_or(path);
+        self.world.main = match self.world.resolve(&main) {
+            Ok(id) => id,
+            Err(err) => {
+                tracing::warn!("Failed to load {} ({err})", main.display());
+                return vec![];
+            }
+        };
+
+        let mut tracer = Tracer::default();
+        let result = typst::compile(&self.world, &mut tracer);
+        let mut diagnostics = tracer.warnings().to_vec();
+        match result {
+            Ok(document) => {
+                tracing::info!("Compilation succeeded");
+                self.frames = document.pages;
+            }
+            Err(errors) => {
+                tracing::info!("Compilation failed");
+                diagnostics.extend(*errors);
+            }
+        }
+
+        self.publish(&diagnostics)
+    }
+
+    /// Convert diagnostics into notifications, one per affected document.
+    ///
+    /// Documents that had diagnostics before but have none now receive an
+    /// empty list so that the client clears them.
+    fn publish(&mut self, diagnostics: &[SourceError]) -> Vec<Notification> {
+        let mut grouped: HashMap<Url, Vec<lsp::Diagnostic>> = HashMap::new();
+        for error in diagnostics {
+            let (source, range) = if error.span.is_detached() {
+                (self.world.main(), 0..0)
+            } else {
+                (self.world.source(error.span.source()), error.range(&self.world))
+            };
+
+            let Some(uri) = to_uri(source) else { continue };
+            let related = error
+                .trace
+                .iter()
+                .filter(|point| !point.span.is_detached())
+                .filter_map(|point| {
+                    let source = self.world.source(point.span.source());
+                    Some(lsp::DiagnosticRelatedInformation {
+                        location: lsp::Location::new(
+                            to_uri(source)?,
+                            to_lsp_range(source, source.range(point.span)),
+                        ),
+                        message: point.v.to_string(),
+                    })
+                })
+                .collect();
+
+            grouped.entry(uri).or_default().push(lsp::Diagnostic {
+                range: to_lsp_range(source, range),
+                severity: Some(match error.severity {
+                    Severity::Error => lsp::DiagnosticSeverity::ERROR,
+                    Severity::Warning => lsp::DiagnosticSeverity::WARNING,
+                }),
+                source: Some("typst".into()),
+                message: error.message.to_string(),
+                related_information: Some(related),
+                ..Default::default()
+            });
+        }
+
+        let diagnosed: HashSet<Url> = grouped.keys().cloned().collect();
+        for uri in self.diagnosed.drain() {
+            grouped.entry(uri).or_default();
+        }
+        self.diagnosed = diagnosed;
+
+        grouped
+            .into_iter()
+            .map(|(uri, diagnostics)| {
+                Notification::new(
+                    PublishDiagnostics::METHOD.into(),
+                    lsp::PublishDiagnosticsParams::new(uri, diagnostics, None),
+                )
+            })
+            .collect()
+    }
+
+    /// Look up the source file and byte offset a client position refers to.
+    fn locate(
+        &self,
+        params: &lsp::TextDocumentPositionParams,
+    ) -> Option<(&Source, usize)> {
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let id = self.world.resolve(&path).ok()?;
+        let source = self.world.source(id);
+        let cursor = to_byte(source, params.position)?;
+        Some((source, cursor))
+    }
+
+    /// Autocomplete at a position.
+    fn complete(
+        &mut self,
+        params: lsp::CompletionParams,
+    ) -> Option<lsp::CompletionResponse> {
+        let (source, cursor) = self.locate(&params.text_document_position)?;
+        let explicit = params.context.map_or(true, |context| {
+            context.trigger_kind == lsp::CompletionTriggerKind::INVOKED
+        });
+
+        let (from, completions) =
+            autocomplete(&self.world, &self.frames, source, cursor, explicit)?;
+
+        let range = to_lsp_range(source, from..cursor);
+        let items = completions
+            .into_iter()
+            .map(|completion| {
+                let detail = match completion.kind {
+                    CompletionKind::Symbol(c) => Some(c.into()),
+                    _ => completion.detail,
+                };
+
+                lsp::CompletionItem {
+                    kind: Some(match completion.kind {
+                        CompletionKind::Syntax => lsp::CompletionItemKind::SNIPPET,
+                        CompletionKind::Func => lsp::CompletionItemKind::FUNCTION,
+                        CompletionKind::Param => lsp::CompletionItemKind::VARIABLE,
+                        CompletionKind::Constant => lsp::CompletionItemKind::CONSTANT,
+                        CompletionKind::Symbol(_) => lsp::CompletionItemKind::TEXT,
+                    }),
+                    text_edit: Some(lsp::CompletionTextEdit::Edit(lsp::TextEdit::new(
+                        range,
+                        to_snippet(
+                            completion.apply.as_ref().unwrap_or(&completion.label),
+                        ),
+                    ))),
+                    insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+                    label: completion.label.into(),
+                    detail: detail.map(Into::into),
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        Some(lsp::CompletionResponse::Array(items))
+    }
+
+    /// Describe the item at a position.
+    fn hover(&mut self, params: lsp::HoverParams) -> Option<lsp::Hover> {
+        let (source, cursor) = self.locate(&params.text_document_position_params)?;
+        let value = match tooltip(&self.world, &self.frames, source, cursor)? {
+            Tooltip::Text(text) => text.into(),
+            Tooltip::Code(code) => format!("```typst\n{code}\n```"),
+        };
+
+        Some(lsp::Hover {
+            contents: lsp::HoverContents::Markup(lsp::MarkupContent {
+                kind: lsp::MarkupKind::Markdown,
+                value,
+            }),
+            range: None,
+        })
+    }
+
+    /// Find the definition of the identifier at a position.
+    fn definition(
+        &mut self,
+        params: lsp::GotoDefinitionParams,
+    ) -> Option<lsp::GotoDefinitionResponse> {
+        let (source, cursor) = self.locate(&params.text_document_position_params)?;
+        let found = definition(&self.world, source, cursor)?;
+        let target = self.world.source(found.source);
+        Some(lsp::GotoDefinitionResponse::Scalar(lsp::Location::new(
+            to_uri(target)?,
+            to_lsp_range(target, found.range),
+        )))
+    }
+
+    /// Find all occurrences of the identifier or label at a position.
+    fn references(&mut self, params: lsp::ReferenceParams) -> Option<Vec<lsp::Location>> {
+        let (source, cursor) = self.locate(&params.text_document_position)?;
+        let include = params.context.include_declaration;
+        let locations = references(&self.world, source, cursor)
+            .into_iter()
+            .filter(|reference| include || !reference.definition)
+            .filter_map(|reference| {
+                let source = self.world.source(reference.source);
+                Some(lsp::Location::new(
+                    to_uri(source)?,
+                    to_lsp_range(source, reference.range),
+                ))
+            })
+            .collect();
+        Some(locations)
+    }
+
+    /// Rename the identifier or label at a position across all files.
+    fn rename(
+        &mut self,
+        params: lsp::RenameParams,
+    ) -> StrResult<Option<lsp::WorkspaceEdit>> {
+        let Some((source, cursor)) = self.locate(&params.text_document_position) else {
+            return Ok(None);
+        };
+
+        let mut changes: HashMap<Url, Vec<lsp::TextEdit>> = HashMap::new();
+        for edit in rename(&self.world, source, cursor, &params.new_name)? {
+            let source = self.world.source(edit.source);
+            let Some(uri) = to_uri(source) else { continue };
+            changes.entry(uri).or_default().push(lsp::TextEdit::new(
+                to_lsp_range(source, edit.range),
+                edit.text.into(),
+            ));
+        }
+
+        Ok(Some(lsp::WorkspaceEdit::new(changes)))
+    }
+
+    /// Highlight a whole document.
+    fn tokens(
+        &mut self,
+        params: lsp::SemanticTokensParams,
+    ) -> Option<lsp::SemanticTokensResult> {
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let id = self.world.resolve(&path).ok()?;
+        let source = self.world.source(id);
+
+        let mut leaves = vec![];
+        collect_leaves(&LinkedNode::new(source.root()), None, &mut leaves);
+
+        let data = encode_tokens(source, leaves);
+        Some(lsp::SemanticTokens { result_id: None, data }.into())
+    }
+
+    /// Find the position in the document that corresponds to a cursor.
+    fn jump(&mut self, params: lsp::TextDocumentPositionParams) -> Option<JumpPosition> {
+        let (source, cursor) = self.locate(&params)?;
+        let position = jump_from_cursor(&self.frames, source, cursor)?;
+        Some(JumpPosition {
+            page: position.page.get(),
+            x: position.point.x.to_pt(),
+            y: position.point.y.to_pt(),
+        })
+    }
+}
+
+/// Request to find the position in the compiled document that corresponds to
+/// a cursor position in a source file. Lets editors scroll a preview along.
+enum JumpFromCursor {}
+
+impl lsp::request::Request for JumpFromCursor {
+    type Params = lsp::TextDocumentPositionParams;
+    type Result = Option<JumpPosition>;
+    const METHOD: &'static str = "typst/jumpFromCursor";
+}
+
+/// A position in the compiled document.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JumpPosition {
+    /// The page, starting at 1.
+    page: usize,
+    /// The horizontal offset from the top-left corner of the page, in points.
+    x: f64,
+    /// The vertical offset from the top-left corner of the page, in points.
+    y: f64,
+}
+
+/// Parse a request's parameters and respond with the handler's result or
+/// error.
+fn respond<R: lsp::request::Request>(
+    request: Request,
+    handler: impl FnOnce(R::Params) -> StrResult<R::Result>,
+) -> Response {
+    match serde_json::from_value(request.params).map(handler) {
+        Ok(Ok(result)) => Response::new_ok(request.id, result),
+        Ok(Err(message)) => {
+            Response::new_err(request.id, ErrorCode::RequestFailed as i32, message.into())
+        }
+        Err(err) => Response::new_err(
+            request.id,
+            ErrorCode::InvalidParams as i32,
+            err.to_string(),
+        ),
+    }
+}
+
+/// Send a message to the client.
+fn send(connection: &Connection, message: Message) -> StrResult<()> {
+    connection
+        .sender
+        .send(message)
+        .map_err(|_| "failed to send message to client".into())
+}
+
+/// The file URI of a source file.
+fn to_uri(source: &Source) -> Option<Url> {
+    Url::from_file_path(source.path()).ok()
+}
+
+/// Convert a client position into a byte offset.
+///
+/// Positions past the end of a line are clamped to the line's end, before its
+/// line break.
+fn to_byte(source: &Source, position: lsp::Position) -> Option<usize> {
+    let line = position.line as usize;
+    if line == source.len_lines() {
+        return Some(source.len_bytes());
+    }
+
+    let range = source.line_to_range(line)?;
+    let text = source.get(range.clone())?.trim_end_matches(is_newline);
+    let start = source.byte_to_utf16(range.start)?;
+    let end = source.byte_to_utf16(range.start + text.len())?;
+    source.utf16_to_byte((start + position.character as usize).min(end))
+}
+
+/// Convert a client range into a byte range.
+fn to_byte_range(source: &Source, range: lsp::Range) -> Option<Range<usize>> {
+    Some(to_byte(source, range.start)?..to_byte(source, range.end)?)
+}
+
+/// Convert a byte offset into a client position.
+///
+/// Client positions count UTF-16 code units.
+fn to_lsp_position(source: &Source, byte_idx: usize) -> lsp::Position {
+    let line = source.byte_to_line(byte_idx).unwrap_or_default();
+    let start = source
+        .line_to_byte(line)
+        .and_then(|start| source.byte_to_utf16(start))
+        .unwrap_or_default();
+    let utf16 = source.byte_to_utf16(byte_idx).unwrap_or(start);
+    lsp::Position::new(line as u32, (utf16 - start) as u32)
+}
+
+/// Convert a byte range into a client range.
+fn to_lsp_range(source: &Source, range: Range<usize>) -> lsp::Range {
+    lsp::Range::new(
+        to_lsp_position(source, range.start),
+        to_lsp_position(source, range.end),
+    )
+}
+
+/// Convert a completion in Typst's snippet syntax into a client snippet.
+///
+/// Typst marks placeholders as `${name}`, while clients expect numbered
+/// tabstops like `${1:name}` and escaped dollar signs elsewhere.
+fn to_snippet(apply: &str) -> String {
+    let mut snippet = String::new();
+    let mut tabstop = 0;
+    let mut rest = apply;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        if c == '$' && rest.starts_with('{') {
+            let end = rest.find('}').unwrap_or(rest.len());
+            let placeholder = &rest[1..end];
+            rest = rest.get(end + 1..).unwrap_or_default();
+
+            // Some placeholders are already numbered.
+            let name = match placeholder.split_once(':') {
+                Some((n, name)) if n.chars().all(|c| c.is_ascii_digit()) => name,
+                _ => placeholder,
+            };
+
+            tabstop += 1;
+            if name.is_empty() {
+                snippet.push_str(&format!("${tabstop}"));
+            } else {
+                snippet.push_str(&format!("${{{tabstop}:{}}}", escape_snippet(name)));
+            }
+        } else {
+            snippet.push_str(&escape_snippet(c.encode_utf8(&mut [0; 4])));
+        }
+    }
+
+    snippet
+}
+
+/// Escape characters that have a special meaning in client snippets.
+fn escape_snippet(text: &str) -> EcoString {
+    let mut escaped = EcoString::new();
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The semantic token types, one for each highlighting tag.
+const TOKEN_TYPES: [SemanticTokenType; 20] = [
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::new("punctuation"),
+    SemanticTokenType::new("escape"),
+    SemanticTokenType::new("strong"),
+    SemanticTokenType::new("emph"),
+    SemanticTokenType::new("link"),
+    SemanticTokenType::new("raw"),
+    SemanticTokenType::new("label"),
+    SemanticTokenType::new("ref"),
+    SemanticTokenType::new("heading"),
+    SemanticTokenType::new("marker"),
+    SemanticTokenType::new("term"),
+    SemanticTokenType::new("delim"),
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::STRING,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::new("error"),
+];
+
+/// The index of a highlighting tag's token type in [`TOKEN_TYPES`].
+fn token_type(tag: Tag) -> u32 {
+    match tag {
+        Tag::Comment => 0,
+        Tag::Punctuation => 1,
+        Tag::Escape => 2,
+        Tag::Strong => 3,
+        Tag::Emph => 4,
+        Tag::Link => 5,
+        Tag::Raw => 6,
+        Tag::Label => 7,
+        Tag::Ref => 8,
+        Tag::Heading => 9,
+        Tag::ListMarker => 10,
+        Tag::ListTerm => 11,
+        Tag::MathDelimiter => 12,
+        Tag::MathOperator | Tag::Operator => 13,
+        Tag::Keyword => 14,
+        Tag::Number => 15,
+        Tag::String => 16,
+        Tag::Function => 17,
+        Tag::Interpolated => 18,
+        Tag::Error => 19,
+    }
+}
+
+/// Encode highlighted byte ranges as semantic tokens.
+///
+/// Each token's position is relative to the previous one's and no token spans
+/// multiple lines.
+fn encode_tokens(
+    source: &Source,
+    leaves: Vec<(Range<usize>, Tag)>,
+) -> Vec<lsp::SemanticToken> {
+    let mut data = vec![];
+    let mut prev = lsp::Position::new(0, 0);
+    for (range, tag) in leaves {
+        let mut offset = range.start;
+        for line in source.get(range).unwrap_or_default().split_inclusive('\n') {
+            let text = line.trim_end_matches(['\r', '\n']);
+            let start = to_lsp_position(source, offset);
+            let end = to_lsp_position(source, offset + text.len());
+            offset += line.len();
+            if start == end {
+                continue;
+            }
+
+            data.push(lsp::SemanticToken {
+                delta_line: start.line - prev.line,
+                delta_start: if start.line == prev.line {
+                    start.character - prev.character
+                } else {
+                    start.character
+                },
+                length: end.character - start.character,
+                token_type: token_type(tag),
+                token_modifiers_bitset: 0,
+            });
+            prev = start;
+        }
+    }
+    data
+}
+
+/// Collect the byte ranges of all highlighted leaves along with their tags.
+///
+/// Leaves inherit the tag of their closest highlighted ancestor. Errors are
+/// left to the diagnostics.
+fn collect_leaves(
+    node: &LinkedNode,
+    inherited: Option<Tag>,
+    leaves: &mut Vec<(Range<usize>, Tag)>,
+) {
+    let tag = highlight(node).or(inherited);
+    if !node.text().is_empty() {
+        if let Some(tag) = tag.filter(|&tag| tag != Tag::Error) {
+            leaves.push((node.range(), tag));
+        }
+        return;
+    }
+
+    for child in node.children() {
+        collect_leaves(&child, tag, leaves);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lsp_positions() {
+        // "ä" is two bytes and one UTF-16 unit, "𝔸" is four bytes and two units.
+        let source = Source::detached("ä𝔸b\nxy\n");
+        let pos = |line, character| lsp::Position::new(line, character);
+
+        assert_eq!(to_byte(&source, pos(0, 0)), Some(0));
+        assert_eq!(to_byte(&source, pos(0, 1)), Some(2));
+        assert_eq!(to_byte(&source, pos(0, 3)), Some(6));
+        assert_eq!(to_byte(&source, pos(1, 1)), Some(9));
+        assert_eq!(to_byte(&source, pos(2, 0)), Some(11));
+        assert_eq!(to_byte(&source, pos(3, 0)), Some(11));
+        assert_eq!(to_byte(&source, pos(4, 0)), None);
+
+        assert_eq!(to_lsp_position(&source, 0), pos(0, 0));
+        assert_eq!(to_lsp_position(&source, 2), pos(0, 1));
+        assert_eq!(to_lsp_position(&source, 6), pos(0, 3));
+        assert_eq!(to_lsp_position(&source, 9), pos(1, 1));
+        assert_eq!(to_lsp_position(&source, 11), pos(2, 0));
+
+        for byte in [0, 2, 6, 7, 8, 9, 10] {
+            assert_eq!(to_byte(&source, to_lsp_position(&source, byte)), Some(byte));
+        }
+    }
+
+    #[test]
+    fn test_lsp_position_clamped_to_line_end() {
+        let source = Source::detached("ä𝔸b\nxy");
+        let pos = |line, character| lsp::Position::new(line, character);
+        assert_eq!(to_byte(&source, pos(0, 4)), Some(7));
+        assert_eq!(to_byte(&source, pos(0, 100)), Some(7));
+        assert_eq!(to_byte(&source, pos(1, 100)), Some(10));
+        assert_eq!(
+            to_byte_range(&source, lsp::Range::new(pos(0, 1), pos(0, 9))),
+            Some(2..7)
+        );
+    }
+
+    #[test]
+    fn test_lsp_snippets() {
+        assert_eq!(to_snippet("plain"), "plain");
+        assert_eq!(to_snippet("f(${})"), "f($1)");
+        assert_eq!(to_snippet("let ${x} = ${value}"), "let ${1:x} = ${2:value}");
+        assert_eq!(to_snippet("${1:a} ${b}"), "${1:a} ${2:b}");
+        assert_eq!(to_snippet("$${x}$"), "\\$${1:x}\\$");
+        assert_eq!(to_snippet("{${}}"), "{$1\\}");
+        assert_eq!(escape_snippet("a$b}c\\d"), "a\\$b\\}c\\\\d");
+        assert_eq!(escape_snippet("ä{"), "ä{");
+    }
+
+    #[test]
+    fn test_lsp_semantic_tokens() {
+        let source = Source::detached("ä *b*\n  𝔸c");
+        let leaves = vec![
+            (3..4, Tag::Strong),
+            (4..5, Tag::Strong),
+            (5..6, Tag::Strong),
+            (9..14, Tag::Interpolated),
+        ];
+
+        let tokens: Vec<_> = encode_tokens(&source, leaves)
+            .into_iter()
+            .map(|token| {
+                (token.delta_line, token.delta_start, token.length, token.token_type)
+            })
+            .collect();
+
+        assert_eq!(tokens, [(0, 2, 1, 3), (0, 1, 1, 3), (0, 1, 1, 3), (1, 2, 3, 18)]);
+    }
+
+    #[test]
+    fn test_lsp_semantic_tokens_split_at_newlines() {
+        let source = Source::detached("`a\r\nä\n\nb` c");
+        let leaves = vec![(0..10, Tag::Raw), (11..12, Tag::Interpolated)];
+        let tokens: Vec<_> = encode_tokens(&source, leaves)
+            .into_iter()
+            .map(|token| (token.delta_line, token.delta_start, token.length))
+            .collect();
+
+        assert_eq!(tokens, [(0, 0, 2), (1, 0, 1), (2, 0, 2), (0, 3, 1)]);
+    }
+}
@@ -1,4 +1,7 @@
 mod args;
+mod fmt;
+mod lsp;
+mod query;
 mod trace;
 
 use std::cell::{RefCell, RefMut};
@@ -6,9 +9,11 @@ use std::collections::HashMap;
 use std::fs::{self, File};
 use std::hash::Hash;
 use std::io::{self, Write};
+use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::process;
 
+use chrono::Datelike;
 use clap::Parser;
 use codespan_reporting::diagnostic::{Diagnostic, Label};
 use codespan_reporting::term::{self, termcolor};
@@ -18,17 +23,25 @@ use memmap2::Mmap;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use once_cell::unsync::OnceCell;
 use same_file::{is_same_file, Handle};
+use serde::Serialize;
 use siphasher::sip128::{Hasher128, SipHasher13};
 use termcolor::{ColorChoice, StandardStream, WriteColor};
-use typst::diag::{FileError, FileResult, SourceError, StrResult};
-use typst::eval::Library;
+use typst::diag::{
+    FileError, FileResult, PackageError, PackageResult, Severity, SourceError, StrResult,
+};
+use typst::doc::Document;
+use typst::eval::{Datetime, Library, PackageSpec, Tracer};
 use typst::font::{Font, FontBook, FontInfo, FontVariant};
+use typst::geom::Color;
 use typst::syntax::{Source, SourceId};
 use typst::util::{Buffer, PathExt};
 use typst::World;
 use walkdir::WalkDir;
 
-use crate::args::{CliArguments, Command, CompileCommand};
+use crate::args::{CliArguments, Command, CompileCommand, DiagnosticFormat};
+use crate::fmt::{fmt, FmtSettings};
+use crate::lsp::{lsp, LspSettings};
+use crate::query::{query, QuerySettings};
 use crate::trace::init_tracing;
 
 type CodespanResult<T> = Result<T, CodespanError>;
@@ -55,25 +68,48 @@ struct CompileSettings {
     /// The paths to search for fonts.
     font_paths: Vec<PathBuf>,
 
+    /// The directory in which local packages are looked up.
+    package_path: Option<PathBuf>,
+
     /// The open command to use.
     open: Option<Option<String>>,
+
+    /// The PPI to use for PNG export.
+    ppi: f32,
+
+    /// The format to emit diagnostics in.
+    diagnostic_format: DiagnosticFormat,
 }
 
 impl CompileSettings {
     /// Create a new compile settings from the field values.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         input: PathBuf,
         output: Option<PathBuf>,
         watch: bool,
         root: Option<PathBuf>,
         font_paths: Vec<PathBuf>,
+        package_path: Option<PathBuf>,
         open: Option<Option<String>>,
+        ppi: f32,
+        diagnostic_format: DiagnosticFormat,
     ) -> Self {
         let output = match output {
             Some(path) => path,
             None => input.with_extension("pdf"),
         };
-        Self { input, output, watch, root, font_paths, open }
+        Self {
+            input,
+            output,
+            watch,
+            root,
+            font_paths,
+            package_path,
+            open,
+            ppi,
+            diagnostic_format,
+        }
     }
 
     /// Create a new compile settings from the CLI arguments and a compile command.
@@ -82,12 +118,22 @@ impl CompileSettings {
     /// Panics if the command is not a compile or watch command.
     pub fn with_arguments(args: CliArguments) -> Self {
         let watch = matches!(args.command, Command::Watch(_));
-        let CompileCommand { input, output, open, .. } = match args.command {
+        let CompileCommand { input, output, open, ppi, .. } = match args.command {
             Command::Compile(command) => command,
             Command::Watch(command) => command,
             _ => unreachable!(),
         };
-        Self::new(input, output, watch, args.root, args.font_paths, open)
+        Self::new(
+            input,
+            output,
+            watch,
+            args.root,
+            args.font_paths,
+            args.package_path,
+            open,
+            ppi,
+            args.diagnostic_format,
+        )
     }
 }
 
@@ -132,6 +178,9 @@ fn main() {
         Command::Compile(_) | Command::Watch(_) => {
             compile(CompileSettings::with_arguments(arguments))
         }
+        Command::Query(_) => query(QuerySettings::with_arguments(arguments)),
+        Command::Fmt(_) => fmt(FmtSettings::with_arguments(arguments)),
+        Command::Lsp(_) => lsp(LspSettings::with_arguments(arguments)),
         Command::Fonts(_) => fonts(FontsSettings::with_arguments(arguments)),
     };
 
@@ -152,38 +201,52 @@ fn print_error(msg: &str) -> io::Result<()> {
     writeln!(w, ": {msg}.")
 }
 
-/// Execute a compilation command.
-fn compile(mut command: CompileSettings) -> StrResult<()> {
-    let root = if let Some(root) = &command.root {
-        root.clone()
-    } else if let Some(dir) = command
-        .input
-        .canonicalize()
-        .ok()
-        .as_ref()
-        .and_then(|path| path.parent())
+/// Create the world that serves sources, fonts and files for an input file.
+fn create_world(
+    input: &Path,
+    root: Option<&Path>,
+    font_paths: &[PathBuf],
+    package_path: Option<&Path>,
+) -> SystemWorld {
+    let root = if let Some(root) = root {
+        root.into()
+    } else if let Some(dir) =
+        input.canonicalize().ok().as_ref().and_then(|path| path.parent())
     {
         dir.into()
     } else {
         PathBuf::new()
     };
 
-    // Create the world that serves sources, fonts and files.
-    let mut world = SystemWorld::new(root, &command.font_paths);
+    let package_path = package_path
+        .map(Into::into)
+        .or_else(|| Some(dirs::data_dir()?.join("typst/packages")));
+
+    SystemWorld::new(root, font_paths, package_path)
+}
+
+/// Execute a compilation command.
+fn compile(mut command: CompileSettings) -> StrResult<()> {
+    let mut world = create_world(
+        &command.input,
+        command.root.as_deref(),
+        &command.font_paths,
+        command.package_path.as_deref(),
+    );
 
     // Perform initial compilation.
-    let failed = compile_once(&mut world, &command)?;
+    let output = compile_once(&mut world, &command)?;
 
     // open the file if requested, this must be done on the first **successful** compilation
-    if !failed {
+    if let Some(path) = &output {
         if let Some(open) = command.open.take() {
-            open_file(open.as_deref(), &command.output)?;
+            open_file(open.as_deref(), path)?;
         }
     }
 
     if !command.watch {
         // Return with non-zero exit code in case of error.
-        if failed {
+        if output.is_none() {
             process::exit(1);
         }
 
@@ -222,20 +285,27 @@ fn compile(mut command: CompileSettings) -> StrResult<()> {
         }
 
         if recompile {
-            compile_once(&mut world, &command)?;
+            let output = compile_once(&mut world, &command)?;
             comemo::evict(30);
 
             // open the file if requested, this must be done on the first **successful** compilation
-            if let Some(open) = command.open.take() {
-                open_file(open.as_deref(), &command.output)?;
+            if let Some(path) = &output {
+                if let Some(open) = command.open.take() {
+                    open_file(open.as_deref(), path)?;
+                }
             }
         }
     }
 }
 
 /// Compile a single time.
+///
+/// Returns the path of the first exported file if compilation succeeded.
 #[tracing::instrument(skip_all)]
-fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult<bool> {
+fn compile_once(
+    world: &mut SystemWorld,
+    command: &CompileSettings,
+) -> StrResult<Option<PathBuf>> {
     tracing::info!("Starting compilation");
 
     status(command, Status::Compiling).unwrap();
@@ -243,29 +313,119 @@ fn compile_once(world: &mut SystemWorld, command: &CompileSettings) -> StrResult
     world.reset();
     world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
 
-    match typst::compile(world) {
-        // Export the PDF.
+    let mut tracer = Tracer::default();
+    let result = typst::compile(world, &mut tracer);
+    let warnings = tracer.warnings();
+
+    match result {
+        // Export the PDF / SVG and print warnings.
         Ok(document) => {
-            let buffer = typst::export::pdf(&document);
-            fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
-            status(command, Status::Success).unwrap();
+            let path = export(&document, command)?;
+            if warnings.is_empty() {
+                status(command, Status::Success).unwrap();
+            } else {
+                status(command, Status::PartialSuccess).unwrap();
+            }
+
+            print_diagnostics(world, &[], warnings, command.diagnostic_format)
+                .map_err(|_| "failed to print diagnostics")?;
 
             tracing::info!("Compilation succeeded");
-            Ok(false)
+            Ok(Some(path))
         }
 
         // Print diagnostics.
         Err(errors) => {
             status(command, Status::Error).unwrap();
-            print_diagnostics(world, *errors)
+            print_diagnostics(world, &errors, warnings, command.diagnostic_format)
                 .map_err(|_| "failed to print diagnostics")?;
 
             tracing::info!("Compilation failed");
-            Ok(true)
+            Ok(None)
         }
     }
 }
 
+/// Export into the target format.
+///
+/// Returns the path of the first written file.
+fn export(document: &Document, command: &CompileSettings) -> StrResult<PathBuf> {
+    match command.output.extension() {
+        Some(ext) if ext.eq_ignore_ascii_case("png") => {
+            export_image(document, command, ImageExportFormat::Png)
+        }
+        Some(ext) if ext.eq_ignore_ascii_case("svg") => {
+            export_image(document, command, ImageExportFormat::Svg)
+        }
+        _ => {
+            let buffer = typst::export::pdf(document);
+            fs::write(&command.output, buffer).map_err(|_| "failed to write PDF file")?;
+            Ok(command.output.clone())
+        }
+    }
+}
+
+/// An image format to export in.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum ImageExportFormat {
+    Png,
+    Svg,
+}
+
+/// Export to one or multiple PNGs or SVGs, one per page.
+///
+/// If the output path contains a page number template (`{n}` or `{p}`), one
+/// file is written per page. Otherwise, the document must have exactly one
+/// page. Returns the path of the first written file.
+fn export_image(
+    document: &Document,
+    command: &CompileSettings,
+    fmt: ImageExportFormat,
+) -> StrResult<PathBuf> {
+    let string = command.output.to_str().unwrap_or_default();
+    let numbered = string.contains("{n}") || string.contains("{p}");
+    if !numbered && document.pages.len() > 1 {
+        let ext = match fmt {
+            ImageExportFormat::Png => "PNG",
+            ImageExportFormat::Svg => "SVG",
+        };
+        return Err(format!(
+            "cannot export multiple {ext}s without `{{n}}` or `{{p}}` in output path"
+        )
+        .into());
+    }
+
+    let width = 1 + document.pages.len().checked_ilog10().unwrap_or(0) as usize;
+    let page_path = |number: usize| -> PathBuf {
+        if numbered {
+            string
+                .replace("{n}", &format!("{number}"))
+                .replace("{p}", &format!("{number:0width$}"))
+                .into()
+        } else {
+            command.output.clone()
+        }
+    };
+
+    for (i, frame) in document.pages.iter().enumerate() {
+        let path = page_path(i + 1);
+
+        match fmt {
+            ImageExportFormat::Png => {
+                let pixmap =
+                    typst::export::render(frame, command.ppi / 72.0, Color::WHITE);
+                pixmap.save_png(path).map_err(|_| "failed to write PNG file")?;
+            }
+            ImageExportFormat::Svg => {
+                let svg = typst::export::svg(frame);
+                fs::write(path, svg).map_err(|_| "failed to write SVG file")?;
+            }
+        }
+    }
+
+    Ok(page_path(1))
+}
+
 /// Clear the terminal and render the status message.
 #[tracing::instrument(skip_all)]
 fn status(command: &CompileSettings, status: Status) -> io::Result<()> {
@@ -305,6 +465,7 @@ fn status(command: &CompileSettings, status: Status) -> io::Result<()> {
 enum Status {
     Compiling,
     Success,
+    PartialSuccess,
     Error,
 }
 
@@ -313,6 +474,7 @@ impl Status {
         match self {
             Self::Compiling => "compiling ...",
             Self::Success => "compiled successfully",
+            Self::PartialSuccess => "compiled with warnings",
             Self::Error => "compiled with errors",
         }
     }
@@ -321,6 +483,7 @@ impl Status {
         let styles = term::Styles::default();
         match self {
             Self::Error => styles.header_error,
+            Self::PartialSuccess => styles.header_warning,
             _ => styles.header_note,
         }
     }
@@ -329,22 +492,38 @@ impl Status {
 /// Print diagnostic messages to the terminal.
 fn print_diagnostics(
     world: &SystemWorld,
-    errors: Vec<SourceError>,
+    errors: &[SourceError],
+    warnings: &[SourceError],
+    format: DiagnosticFormat,
 ) -> Result<(), codespan_reporting::files::Error> {
+    let diagnostics = warnings.iter().chain(errors);
+    if format == DiagnosticFormat::Json {
+        return Ok(print_json_diagnostics(world, diagnostics)?);
+    }
+
     let mut w = StandardStream::stderr(ColorChoice::Auto);
-    let config = term::Config { tab_width: 2, ..Default::default() };
+    let mut config = term::Config { tab_width: 2, ..Default::default() };
+    if format == DiagnosticFormat::Short {
+        config.display_style = term::DisplayStyle::Short;
+    }
 
-    for error in errors {
+    for error in diagnostics {
         // The main diagnostic.
-        let range = error.range(world);
-        let diag = Diagnostic::error()
-            .with_message(error.message)
-            .with_labels(vec![Label::primary(error.span.source(), range)]);
+        let diag = match error.severity {
+            Severity::Error => Diagnostic::error(),
+            Severity::Warning => Diagnostic::warning(),
+        };
+
+        let mut diag = diag.with_message(error.message.clone());
+        if !error.span.is_detached() {
+            let range = error.range(world);
+            diag = diag.with_labels(vec![Label::primary(error.span.source(), range)]);
+        }
 
         term::emit(&mut w, &config, world, &diag)?;
 
         // Stacktrace-like helper diagnostics.
-        for point in error.trace {
+        for point in &error.trace {
             let message = point.v.to_string();
             let help = Diagnostic::help().with_message(message).with_labels(vec![
                 Label::primary(
@@ -360,6 +539,105 @@ fn print_diagnostics(
     Ok(())
 }
 
+/// Print diagnostic messages as JSON, one object per line and diagnostic.
+fn print_json_diagnostics<'a>(
+    world: &SystemWorld,
+    diagnostics: impl Iterator<Item = &'a SourceError>,
+) -> io::Result<()> {
+    let mut w = io::stderr().lock();
+
+    for error in diagnostics {
+        let diag = JsonDiagnostic {
+            severity: match error.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            },
+            message: error.message.to_string(),
+            location: (!error.span.is_detached()).then(|| {
+                let source = world.source(error.span.source());
+                JsonLocation::new(source, error.range(world))
+            }),
+            trace: error
+                .trace
+                .iter()
+                .map(|point| {
+                    let source = world.source(point.span.source());
+                    JsonTracepoint {
+                        message: point.v.to_string(),
+                        location: JsonLocation::new(source, source.range(point.span)),
+                    }
+                })
+                .collect(),
+        };
+
+        serde_json::to_writer(&mut w, &diag)?;
+        writeln!(w)?;
+    }
+
+    Ok(())
+}
+
+/// A diagnostic in the JSON diagnostic format.
+#[derive(Serialize)]
+struct JsonDiagnostic {
+    /// How severe the diagnostic is: `"error"` or `"warning"`.
+    severity: &'static str,
+    /// The diagnostic's message.
+    message: String,
+    /// Where in the sources the diagnostic occurred. Omitted for diagnostics
+    /// that do not belong to a specific location.
+    #[serde(flatten)]
+    location: Option<JsonLocation>,
+    /// The stacktrace-like trace of the diagnostic, innermost point first.
+    trace: Vec<JsonTracepoint>,
+}
+
+/// A point in the trace of a JSON diagnostic.
+#[derive(Serialize)]
+struct JsonTracepoint {
+    /// A description of the trace point.
+    message: String,
+    /// Where in the sources the trace point lies.
+    #[serde(flatten)]
+    location: JsonLocation,
+}
+
+/// A file path and a range of one-based line/column positions within it.
+#[derive(Serialize)]
+struct JsonLocation {
+    path: String,
+    start: JsonPosition,
+    end: JsonPosition,
+}
+
+impl JsonLocation {
+    /// Locate a byte range in a source file.
+    fn new(source: &Source, range: Range<usize>) -> Self {
+        Self {
+            path: source.path().display().to_string(),
+            start: JsonPosition::new(source, range.start),
+            end: JsonPosition::new(source, range.end),
+        }
+    }
+}
+
+/// A one-based line and column position. Columns count characters.
+#[derive(Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
+impl JsonPosition {
+    /// Locate a byte index in a source file.
+    fn new(source: &Source, byte_idx: usize) -> Self {
+        Self {
+            line: source.byte_to_line(byte_idx).unwrap_or_default() + 1,
+            column: source.byte_to_column(byte_idx).unwrap_or_default() + 1,
+        }
+    }
+}
+
 /// Opens the given file using:
 /// - The default file viewer if `open` is `None`.
 /// - The given viewer provided by `open` if it is `Some`.
@@ -401,6 +679,8 @@ struct SystemWorld {
     paths: RefCell<HashMap<PathHash, PathSlot>>,
     sources: FrozenVec<Box<Source>>,
     main: SourceId,
+    current_date: OnceCell<chrono::DateTime<chrono::Local>>,
+    package_path: Option<PathBuf>,
 }
 
 /// Holds details about the location of a font and lazily the font itself.
@@ -418,7 +698,7 @@ struct PathSlot {
 }
 
 impl SystemWorld {
-    fn new(root: PathBuf, font_paths: &[PathBuf]) -> Self {
+    fn new(root: PathBuf, font_paths: &[PathBuf], package_path: Option<PathBuf>) -> Self {
         let mut searcher = FontSearcher::new();
         searcher.search(font_paths);
 
@@ -431,6 +711,8 @@ impl SystemWorld {
             paths: RefCell::default(),
             sources: FrozenVec::new(),
             main: SourceId::detached(),
+            current_date: OnceCell::new(),
+            package_path,
         }
     }
 }
@@ -484,6 +766,42 @@ impl World for SystemWorld {
             .get_or_init(|| read(path).map(Buffer::from))
             .clone()
     }
+
+    fn package(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
+        // Packages are stored as `{namespace}/{name}/{version}` in the
+        // package directory.
+        let Some(package_path) = &self.package_path else {
+            return Err(PackageError::NotFound(spec.clone()));
+        };
+
+        let dir = package_path
+            .join(spec.namespace.as_str())
+            .join(spec.name.as_str())
+            .join(spec.version.to_string());
+
+        if dir.is_dir() {
+            Ok(dir.normalize())
+        } else {
+            Err(PackageError::NotFound(spec.clone()))
+        }
+    }
+
+    fn today(&self, offset: Option<i64>) -> Option<Datetime> {
+        let now = self.current_date.get_or_init(chrono::Local::now);
+        let naive = match offset {
+            None => now.naive_local(),
+            Some(o) => {
+                let offset = chrono::Duration::milliseconds(o.checked_mul(3_600_000)?);
+                now.naive_utc().checked_add_signed(offset)?
+            }
+        };
+
+        Datetime::from_ymd(
+            naive.year(),
+            naive.month().try_into().ok()?,
+            naive.day().try_into().ok()?,
+        )
+    }
 }
 
 impl SystemWorld {
@@ -507,6 +825,11 @@ impl SystemWorld {
         }))
     }
 
+    /// Access a source file mutably to apply edits to it.
+    fn source_mut(&mut self, id: SourceId) -> &mut Source {
+        &mut self.sources.as_mut()[id.into_u16() as usize]
+    }
+
     #[tracing::instrument(skip_all)]
     fn insert(&self, path: &Path, text: String) -> SourceId {
         let id = SourceId::from_u16(self.sources.len() as u16);
@@ -545,6 +868,7 @@ impl SystemWorld {
         self.sources.as_mut().clear();
         self.hashes.borrow_mut().clear();
         self.paths.borrow_mut().clear();
+        self.current_date.take();
     }
 }
 
@@ -0,0 +1,229 @@
+use std::path::PathBuf;
+use std::process;
+
+use comemo::Track;
+use ecow::{eco_format, EcoString};
+use typst::diag::StrResult;
+use typst::doc::Document;
+use typst::eval::{eval_string, Dict, Tracer, Value};
+use typst::model::{Content, Introspector, LocatableSelector};
+use typst::syntax::Span;
+use typst::World;
+
+use crate::args::{
+    CliArguments, Command, DiagnosticFormat, QueryCommand, SerializationFormat,
+};
+use crate::{create_world, print_diagnostics};
+
+/// A summary of the input arguments relevant to querying.
+pub struct QuerySettings {
+    /// The path to the input file.
+    input: PathBuf,
+
+    /// The root directory for absolute paths.
+    root: Option<PathBuf>,
+
+    /// The paths to search for fonts.
+    font_paths: Vec<PathBuf>,
+
+    /// The directory in which local packages are looked up.
+    package_path: Option<PathBuf>,
+
+    /// The selector that defines which elements to retrieve.
+    selector: String,
+
+    /// The field to extract from the retrieved elements, if any.
+    field: Option<String>,
+
+    /// Whether exactly one element is expected.
+    one: bool,
+
+    /// The format to serialize the retrieved data in.
+    format: SerializationFormat,
+
+    /// The format to emit diagnostics in.
+    diagnostic_format: DiagnosticFormat,
+}
+
+impl QuerySettings {
+    /// Create new query settings from the CLI arguments.
+    ///
+    /// # Panics
+    /// Panics if the command is not a query command.
+    pub fn with_arguments(args: CliArguments) -> Self {
+        let QueryCommand { input, selector, field, one, format } = match args.command {
+            Command::Query(command) => command,
+            _ => unreachable!(),
+        };
+
+        Self {
+            input,
+            root: args.root,
+            font_paths: args.font_paths,
+            package_path: args.package_path,
+            selector,
+            field,
+            one,
+            format,
+            diagnostic_format: args.diagnostic_format,
+        }
+    }
+}
+
+/// Execute a query command.
+pub fn query(command: QuerySettings) -> StrResult<()> {
+    let mut world = create_world(
+        &command.input,
+        command.root.as_deref(),
+        &command.font_paths,
+        command.package_path.as_deref(),
+    );
+
+    tracing::info!("Starting querying");
+
+    world.main = world.resolve(&command.input).map_err(|err| err.to_string())?;
+
+    let mut tracer = Tracer::default();
+    let result = typst::compile(&world, &mut tracer);
+    let warnings = tracer.warnings();
+
+    match result {
+        // Retrieve and print the query results.
+        Ok(document) => {
+            print_diagnostics(&world, &[], warnings, command.diagnostic_format)
+                .map_err(|_| "failed to print diagnostics")?;
+            let data = retrieve(&world, &command, &document)?;
+            let serialized = serialize(data, &command)?;
+            println!("{serialized}");
+            Ok(())
+        }
+
+        // Print diagnostics.
+        Err(errors) => {
+            print_diagnostics(&world, &errors, warnings, command.diagnostic_format)
+                .map_err(|_| "failed to print diagnostics")?;
+            process::exit(1);
+        }
+    }
+}
+
+/// Retrieve the elements matching the selector from the document.
+fn retrieve(
+    world: &(dyn World + 'static),
+    command: &QuerySettings,
+    document: &Document,
+) -> StrResult<Vec<Value>> {
+    let selector = eval_string(world.track(), &command.selector, Span::detached())
+        .map_err(|errors| {
+            let mut message = EcoString::from("failed to evaluate selector");
+            for (i, error) in errors.into_iter().enumerate() {
+                message.push_str(if i == 0 { ": " } else { ", " });
+                message.push_str(&error.message);
+            }
+            message
+        })?
+        .cast::<LocatableSelector>()?;
+
+    let introspector = Introspector::new(&document.pages);
+    let elements = introspector.query(&selector.0);
+
+    Ok(match &command.field {
+        Some(field) => extract_field(&elements, field),
+        None => elements.iter().map(|elem| format_elem(&introspector, elem)).collect(),
+    })
+}
+
+/// Extract a field from each element.
+///
+/// Elements that don't have the field yield `none`, so that there is exactly
+/// one value per retrieved element.
+fn extract_field(elements: &[Content], field: &str) -> Vec<Value> {
+    elements
+        .iter()
+        .map(|elem| elem.field(field).unwrap_or(Value::None))
+        .collect()
+}
+
+/// Convert an element into a dictionary with its fields, the name of its
+/// element function and its position in the document.
+fn format_elem(introspector: &Introspector, elem: &Content) -> Value {
+    let mut dict = Dict::new();
+    dict.insert("func".into(), Value::Str(elem.func().name().into()));
+    for (name, value) in elem.fields() {
+        dict.insert(name.as_str().into(), value);
+    }
+
+    if let Some(location) = elem.location() {
+        dict.insert("location".into(), introspector.position(location).into());
+    }
+
+    Value::Dict(dict)
+}
+
+/// Serialize the retrieved data in the requested format.
+fn serialize(data: Vec<Value>, command: &QuerySettings) -> StrResult<String> {
+    if command.one && data.len() != 1 {
+        Err(eco_format!("expected exactly one element, found {}", data.len()))?;
+    }
+
+    let data = if command.one {
+        data.into_iter().next().unwrap()
+    } else {
+        Value::Array(data.into_iter().collect())
+    };
+
+    match command.format {
+        SerializationFormat::Json => serde_json::to_string_pretty(&data)
+            .map_err(|err| eco_format!("failed to serialize to json ({err})")),
+        SerializationFormat::Yaml => serde_yaml::to_string(&data)
+            .map_err(|err| eco_format!("failed to serialize to yaml ({err})")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+
+    use typst_library::meta::HeadingElem;
+
+    use super::*;
+
+    fn settings(one: bool, format: SerializationFormat) -> QuerySettings {
+        QuerySettings {
+            input: "main.typ".into(),
+            root: None,
+            font_paths: vec![],
+            package_path: None,
+            selector: "heading".into(),
+            field: None,
+            one,
+            format,
+            diagnostic_format: DiagnosticFormat::Human,
+        }
+    }
+
+    #[test]
+    fn test_extract_missing_field() {
+        let level = NonZeroUsize::new(2).unwrap();
+        let elements = [
+            HeadingElem::new(Content::empty()).with_level(level).pack(),
+            HeadingElem::new(Content::empty()).pack(),
+        ];
+
+        assert_eq!(extract_field(&elements, "level"), [Value::Int(2), Value::None]);
+    }
+
+    #[test]
+    fn test_serialize() {
+        let data = vec![Value::Int(1), Value::None];
+        let json = serialize(data.clone(), &settings(false, SerializationFormat::Json));
+        assert_eq!(json.unwrap(), "[\n  1,\n  null\n]");
+
+        let one = serialize(data, &settings(true, SerializationFormat::Json));
+        assert_eq!(one.unwrap_err(), "expected exactly one element, found 2");
+
+        let data = vec![Value::Str("Intro".into())];
+        let yaml = serialize(data, &settings(true, SerializationFormat::Yaml));
+        assert_eq!(yaml.unwrap(), "Intro\n");
+    }
+}
@@ -1,9 +1,11 @@
 use std::ops::Range;
+use std::path::PathBuf;
 
 use comemo::Prehashed;
 use md::escape::escape_html;
 use pulldown_cmark as md;
-use typst::diag::FileResult;
+use typst::diag::{FileResult, PackageError, PackageResult};
+use typst::eval::{Datetime, PackageSpec, Tracer};
 use typst::font::{Font, FontBook};
 use typst::geom::{Point, Size};
 use typst::syntax::{Source, SourceId};
@@ -231,11 +233,11 @@ impl<'a> Handler<'a> {
 
             if let Some(group) = GROUPS
                 .iter()
-                .find(|group| group.functions.iter().any(|func| func == info.name))
+                .find(|group| group.functions.iter().any(|func| func == &info.name))
             {
                 route.push_str(&group.name);
                 route.push_str("/#");
-                route.push_str(info.name);
+                route.push_str(&info.name);
                 if let Some(param) = param {
                     route.push_str("-parameters--");
                     route.push_str(param);
@@ -314,7 +316,8 @@ fn code_block(resolver: &dyn Resolver, lang: &str, text: &str) -> Html {
 
     let source = Source::new(SourceId::from_u16(0), Path::new("main.typ"), compile);
     let world = DocWorld(source);
-    let mut frames = match typst::compile(&world) {
+    let mut tracer = Tracer::default();
+    let mut frames = match typst::compile(&world, &mut tracer) {
         Ok(doc) => doc.pages,
         Err(err) => {
             let msg = &err[0].message;
@@ -383,4 +386,12 @@ impl World for DocWorld {
             .contents()
             .into())
     }
+
+    fn package(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
+        Err(PackageError::NotFound(spec.clone()))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
 }
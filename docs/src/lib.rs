@@ -212,14 +212,14 @@ fn category_page(resolver: &dyn Resolver, category: &str) -> PageModel {
         if grouped
             .iter()
             .flat_map(|group| &group.functions)
-            .any(|f| f == info.name)
+            .any(|f| f == &info.name)
         {
             continue;
         }
 
         let subpage = function_page(resolver, &route, func, info);
         items.push(CategoryItem {
-            name: info.name.into(),
+            name: info.name.to_string(),
             route: subpage.route.clone(),
             oneliner: oneliner(info.docs).into(),
             code: true,
@@ -297,8 +297,8 @@ fn category_page(resolver: &dyn Resolver, category: &str) -> PageModel {
 /// Details about a function.
 #[derive(Debug, Serialize)]
 pub struct FuncModel {
-    pub name: &'static str,
-    pub display: &'static str,
+    pub name: String,
+    pub display: String,
     pub oneliner: &'static str,
     pub element: bool,
     pub details: Html,
@@ -323,7 +323,7 @@ fn function_page(
     info: &FuncInfo,
 ) -> PageModel {
     PageModel {
-        route: format!("{parent}{}/", urlify(info.name)),
+        route: format!("{parent}{}/", urlify(&info.name)),
         title: info.display.to_string(),
         description: format!("Documentation for the `{}` function.", info.name),
         part: None,
@@ -337,8 +337,8 @@ fn func_model(resolver: &dyn Resolver, func: &Func, info: &FuncInfo) -> FuncMode
     let mut s = unscanny::Scanner::new(info.docs);
     let docs = s.eat_until("\n## Methods").trim();
     FuncModel {
-        name: info.name,
-        display: info.display,
+        name: info.name.to_string(),
+        display: info.display.to_string(),
         oneliner: oneliner(docs),
         element: func.element().is_some(),
         details: Html::markdown(resolver, docs),
@@ -351,7 +351,7 @@ fn func_model(resolver: &dyn Resolver, func: &Func, info: &FuncInfo) -> FuncMode
 /// Details about a function parameter.
 #[derive(Debug, Serialize)]
 pub struct ParamModel {
-    pub name: &'static str,
+    pub name: String,
     pub details: Html,
     pub example: Option<Html>,
     pub types: Vec<&'static str>,
@@ -391,7 +391,7 @@ fn param_model(resolver: &dyn Resolver, info: &ParamInfo) -> ParamModel {
     }
 
     ParamModel {
-        name: info.name,
+        name: info.name.to_string(),
         details: Html::markdown(resolver, details),
         example: example.map(|md| Html::markdown(resolver, md)),
         types,
@@ -578,7 +578,7 @@ fn method_model(resolver: &dyn Resolver, part: &'static str) -> MethodModel {
         s.expect(')');
 
         params.push(ParamModel {
-            name,
+            name: name.into(),
             details: Html::markdown(resolver, s.eat_until("\n- ").trim()),
             example: None,
             types,
@@ -744,7 +744,11 @@ const TYPE_ORDER: &[&str] = &[
     "relative length",
     "fraction",
     "color",
+    "gradient",
+    "datetime",
+    "duration",
     "string",
+    "bytes",
     "regex",
     "label",
     "content",
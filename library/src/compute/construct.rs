@@ -1,7 +1,7 @@
 use std::num::NonZeroI64;
 use std::str::FromStr;
 
-use typst::eval::Regex;
+use typst::eval::{Datetime, Duration, Regex};
 
 use crate::prelude::*;
 
@@ -293,13 +293,15 @@ cast_from_value! {
 /// - Integers are formatted in base 10.
 /// - Floats are formatted in base 10 and never in exponential notation.
 /// - From labels the name is extracted.
+/// - Bytes are decoded as UTF-8.
 ///
 /// ## Example
 /// ```example
 /// #str(10) \
 /// #str(2.7) \
 /// #str(1e8) \
-/// #str(<intro>)
+/// #str(<intro>) \
+/// #str(bytes((72, 105)))
 /// ```
 ///
 /// Display: String
@@ -322,6 +324,72 @@ cast_from_value! {
     v: f64 => Self(format_str!("{}", v)),
     v: Label => Self(v.0.into()),
     v: Str => Self(v),
+    v: Bytes => Self(v.to_str()?),
+}
+
+/// Convert a value to bytes.
+///
+/// - Strings are encoded in UTF-8.
+/// - Arrays of integers between `{0}` and `{255}` are converted directly.
+///
+/// Bytes are much more compact than an array of integers and are thus the
+/// preferred representation of binary data like the contents of an image file.
+///
+/// ## Example
+/// ```example
+/// #bytes("Hello 😃") \
+/// #bytes((123, 160, 22, 0))
+/// ```
+///
+/// Display: Bytes
+/// Category: construct
+/// Returns: bytes
+#[func]
+pub fn bytes(
+    /// The value that should be converted to bytes.
+    value: ToBytes,
+) -> Value {
+    Value::Bytes(value.0)
+}
+
+/// A value that can be cast to bytes.
+struct ToBytes(Bytes);
+
+cast_from_value! {
+    ToBytes,
+    v: Str => Self(v.as_bytes().into()),
+    v: Array => Self(Bytes::from_array(&v)?),
+    v: Bytes => Self(v),
+}
+
+/// Convert a value to an array.
+///
+/// Bytes are converted into an array of integers between `{0}` and `{255}`.
+///
+/// ## Example
+/// ```example
+/// #let hi = "Hello 😃"
+/// #array(bytes(hi))
+/// ```
+///
+/// Display: Array
+/// Category: construct
+/// Returns: array
+#[func]
+pub fn array(
+    /// The value that should be converted to an array.
+    value: ToArray,
+) -> Value {
+    Value::Array(value.0)
+}
+
+/// A value that can be cast to an array.
+struct ToArray(Array);
+
+cast_from_value! {
+    ToArray,
+    v: Bytes => Self(v.to_array()),
+    v: Array => Self(v),
 }
 
 /// Create a label from a string.
@@ -445,3 +513,235 @@ pub fn range(
 
     Value::Array(array)
 }
+
+/// Create a new datetime.
+///
+/// You can specify the [datetime]($type/datetime) using a year, month, day,
+/// hour, minute, and second. You can also get the current date with
+/// [`datetime.today`]($func/datetime.today).
+///
+/// ## Example
+/// ```example
+/// #let date = datetime(
+///   year: 2012,
+///   month: 8,
+///   day: 3,
+/// )
+///
+/// #date.display() \
+/// #date.display(
+///   "[day].[month].[year]"
+/// )
+/// ```
+///
+/// ## Format
+/// _Note_: Depending on which components of the datetime you specify, Typst
+/// will store it in one of the following three ways:
+/// * If you specify year, month and day, Typst will store just a date.
+/// * If you specify hour, minute and second, Typst will store just a time.
+/// * If you specify all of year, month, day, hour, minute and second, Typst
+///   will store a full datetime.
+///
+/// Depending on how it is stored, the [`display`]($type/datetime.display)
+/// method will choose a different formatting by default.
+///
+/// Display: Datetime
+/// Category: construct
+/// Returns: datetime
+#[func]
+#[scope(
+    scope.define("today", today);
+    scope
+)]
+pub fn datetime(
+    /// The year of the datetime.
+    #[named]
+    #[default]
+    year: Option<YearComponent>,
+    /// The month of the datetime.
+    #[named]
+    #[default]
+    month: Option<MonthComponent>,
+    /// The day of the datetime.
+    #[named]
+    #[default]
+    day: Option<DayComponent>,
+    /// The hour of the datetime.
+    #[named]
+    #[default]
+    hour: Option<HourComponent>,
+    /// The minute of the datetime.
+    #[named]
+    #[default]
+    minute: Option<MinuteComponent>,
+    /// The second of the datetime.
+    #[named]
+    #[default]
+    second: Option<SecondComponent>,
+) -> Value {
+    let datetime = match (year, month, day, hour, minute, second) {
+        (Some(year), Some(month), Some(day), Some(hour), Some(minute), Some(second)) => {
+            match Datetime::from_ymd_hms(
+                year.0, month.0, day.0, hour.0, minute.0, second.0,
+            ) {
+                Some(datetime) => datetime,
+                None => bail!(args.span, "datetime is invalid"),
+            }
+        }
+        (Some(year), Some(month), Some(day), None, None, None) => {
+            match Datetime::from_ymd(year.0, month.0, day.0) {
+                Some(date) => date,
+                None => bail!(args.span, "date is invalid"),
+            }
+        }
+        (None, None, None, Some(hour), Some(minute), Some(second)) => {
+            match Datetime::from_hms(hour.0, minute.0, second.0) {
+                Some(time) => time,
+                None => bail!(args.span, "time is invalid"),
+            }
+        }
+        (None, None, None, None, None, None) => {
+            bail!(args.span, "at least one of date or time must be fully specified")
+        }
+        _ => bail!(args.span, "time or date is incomplete"),
+    };
+
+    Value::Datetime(datetime)
+}
+
+struct YearComponent(i32);
+struct MonthComponent(u8);
+struct DayComponent(u8);
+struct HourComponent(u8);
+struct MinuteComponent(u8);
+struct SecondComponent(u8);
+
+cast_from_value!(
+    YearComponent,
+    v: i64 => match i32::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("year is invalid")?
+    }
+);
+
+cast_from_value!(
+    MonthComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("month is invalid")?
+    }
+);
+
+cast_from_value!(
+    DayComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("day is invalid")?
+    }
+);
+
+cast_from_value!(
+    HourComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("hour is invalid")?
+    }
+);
+
+cast_from_value!(
+    MinuteComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("minute is invalid")?
+    }
+);
+
+cast_from_value!(
+    SecondComponent,
+    v: i64 => match u8::try_from(v) {
+        Ok(n) => Self(n),
+        _ => Err("second is invalid")?
+    }
+);
+
+/// Returns the current date.
+///
+/// ## Example
+/// ```example
+/// Today's date is
+/// #datetime.today().display().
+/// ```
+///
+/// Display: Today
+/// Category: construct
+/// Returns: datetime
+#[func]
+pub fn today(
+    /// An offset in hours to apply to the current UTC date. If set to
+    /// `{auto}`, the offset will be the local offset. Must be between `{-24}`
+    /// and `{24}`.
+    #[named]
+    #[default(Spanned::new(Smart::Auto, Span::detached()))]
+    offset: Spanned<Smart<i64>>,
+) -> Value {
+    let hours = offset.v.as_custom();
+    if hours.map_or(false, |hours| !(-24..=24).contains(&hours)) {
+        bail!(offset.span, "offset must be between -24 and 24 hours");
+    }
+
+    let current_date = match vm.world().today(hours) {
+        Some(d) => d,
+        None => bail!(args.span, "unable to get the current date"),
+    };
+
+    Value::Datetime(current_date)
+}
+
+/// Create a new duration.
+///
+/// You can specify the [duration]($type/duration) using weeks, days, hours,
+/// minutes and seconds. Durations can be added to and subtracted from
+/// datetimes and from each other.
+///
+/// ## Example
+/// ```example
+/// #let duration = duration(
+///   days: 4,
+///   hours: 2,
+///   minutes: 10,
+/// )
+///
+/// #duration.hours()
+/// ```
+///
+/// Display: Duration
+/// Category: construct
+/// Returns: duration
+#[func]
+pub fn duration(
+    /// The number of seconds.
+    #[named]
+    #[default]
+    seconds: i64,
+    /// The number of minutes.
+    #[named]
+    #[default]
+    minutes: i64,
+    /// The number of hours.
+    #[named]
+    #[default]
+    hours: i64,
+    /// The number of days.
+    #[named]
+    #[default]
+    days: i64,
+    /// The number of weeks.
+    #[named]
+    #[default]
+    weeks: i64,
+) -> Value {
+    match Duration::from_parts(seconds, minutes, hours, days, weeks) {
+        Some(duration) => Value::Duration(duration),
+        None => bail!(args.span, "duration is too large"),
+    }
+}
@@ -2,9 +2,11 @@ use typst::diag::{format_xml_like_error, FileError};
 
 use crate::prelude::*;
 
-/// Read plain text from a file.
+/// Read plain text or raw data from a file.
 ///
-/// The file will be read and returned as a string.
+/// By default, the file will be read as UTF-8 and returned as a string. With
+/// `{encoding: none}`, the file's raw [bytes]($type/bytes) are returned
+/// instead.
 ///
 /// ## Example
 /// ```example
@@ -12,23 +14,44 @@ use crate::prelude::*;
 ///
 /// An example for a HTML file:\
 /// #raw(text, lang: "html")
+///
+/// Raw bytes:
+/// #read("tiger.jpg", encoding: none)
 /// ```
 ///
-/// Display: Plain text
+/// Display: Read
 /// Category: data-loading
-/// Returns: string
+/// Returns: string or bytes
 #[func]
 pub fn read(
     /// Path to a file.
     path: Spanned<EcoString>,
+    /// The encoding to read the file with.
+    ///
+    /// If set to `{none}`, this function returns raw bytes.
+    #[named]
+    #[default(Some(Encoding::Utf8))]
+    encoding: Option<Encoding>,
 ) -> Value {
     let Spanned { v: path, span } = path;
     let path = vm.locate(&path).at(span)?;
     let data = vm.world().file(&path).at(span)?;
-    let text = std::str::from_utf8(&data)
-        .map_err(|_| "file is not valid utf-8")
-        .at(span)?;
-    Value::Str(text.into())
+    match encoding {
+        None => Value::Bytes(data.into()),
+        Some(Encoding::Utf8) => Value::Str(
+            std::str::from_utf8(&data)
+                .map_err(|_| "file is not valid utf-8")
+                .at(span)?
+                .into(),
+        ),
+    }
+}
+
+/// An encoding of a file.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Cast)]
+pub enum Encoding {
+    /// The Unicode UTF-8 encoding.
+    Utf8,
 }
 
 /// Read structured data from a CSV file.
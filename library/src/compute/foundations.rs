@@ -1,3 +1,5 @@
+use typst::eval::{Module, Plugin, Scope};
+
 use crate::prelude::*;
 
 /// Determine a value's type.
@@ -139,3 +141,106 @@ pub fn eval(
     let Spanned { v: text, span } = source;
     typst::eval::eval_string(vm.world(), &text, span)?
 }
+
+/// Load a WebAssembly plugin.
+///
+/// Plugins make it possible to call functions written in any language that
+/// compiles to WebAssembly from Typst code. This function returns a module
+/// that contains one function for each function exported by the plugin. These
+/// functions take and return [bytes]($type/bytes).
+///
+/// Plugins are fully sandboxed: They cannot read files, access the system
+/// clock, or otherwise interact with their environment. The same call with
+/// the same arguments thus always yields the same result, which means that
+/// Typst can cache it.
+///
+/// ## Example
+/// ```typ
+/// #let parser = plugin("chem.wasm")
+/// #let formula = parser.parse(bytes("H2O"))
+/// #str(formula)
+/// ```
+///
+/// ## Protocol
+/// A plugin function receives the lengths of its arguments as 32-bit
+/// integers and must return a 32-bit integer: zero on success and any other
+/// value on failure. It can import two functions from the `typst_env`
+/// module:
+///
+/// - `wasm_minimal_protocol_write_args_to_buffer(ptr)` copies the
+///   concatenated arguments into the plugin's memory at the given pointer.
+/// - `wasm_minimal_protocol_send_result_to_host(ptr, len)` hands the output
+///   of the call (or an error message on failure) back to Typst.
+///
+/// The plugin must export its memory as `memory`.
+///
+/// Display: Plugin
+/// Category: foundations
+/// Returns: module
+#[func]
+pub fn plugin(
+    /// Path to a WebAssembly file.
+    path: Spanned<EcoString>,
+) -> Value {
+    let Spanned { v: path, span } = path;
+    let path = vm.locate(&path).at(span)?;
+    let data = vm.world().file(&path).at(span)?;
+    let plugin = Plugin::new(data.into()).at(span)?;
+
+    let mut scope = Scope::new();
+    for name in plugin.functions() {
+        scope.define(name.clone(), plugin.func(name.clone()));
+    }
+
+    let name = path.file_stem().unwrap_or_default().to_string_lossy();
+    Value::Module(Module::new(name.as_ref()).with_scope(scope))
+}
+
+/// Define a new element.
+///
+/// The fields of the element are the parameters of the given function:
+/// Positional parameters become required fields and named parameters become
+/// optional fields whose defaults can be changed with set rules. Parameters
+/// may carry [type annotations]($type/function/#annotations), which are checked both
+/// when the element is created and in set rules.
+///
+/// The function itself defines how the element looks by default. It receives
+/// the element's fields as arguments. Just like built-in elements, the new
+/// element can be customized with show rules, selected with
+/// [`where`]($type/function.where), queried and counted.
+///
+/// ## Example
+/// ```example
+/// #let theorem = element("theorem", (
+///   body as content,
+///   title as content or none: none,
+/// ) => block[
+///   *Theorem*
+///   #if title != none [(#title)]
+///   #body
+/// ])
+///
+/// #show theorem.where(title: none): set text(blue)
+///
+/// #theorem[Every prime larger than two is odd.]
+/// #theorem(title: [Euclid])[
+///   There are infinitely many primes.
+/// ]
+/// ```
+///
+/// Display: Element
+/// Category: foundations
+/// Returns: function
+#[func]
+pub fn element(
+    /// The name of the element.
+    name: Spanned<EcoString>,
+    /// The function whose parameters define the element's fields and that
+    /// displays the element.
+    func: Spanned<Func>,
+) -> Value {
+    if name.v.is_empty() {
+        bail!(name.span, "element name must not be empty");
+    }
+    ElemFunc::define(name.v, func.v).at(func.span)?.into()
+}
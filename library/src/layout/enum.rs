@@ -5,7 +5,7 @@ use crate::meta::{Numbering, NumberingPattern};
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::GridLayouter;
+use super::{Cell, GridLayouter};
 
 /// A numbered list.
 ///
@@ -193,11 +193,11 @@ impl Layout for EnumElem {
                 }
             };
 
-            cells.push(Content::empty());
+            cells.push(Cell::new(Content::empty()));
             // avoid '#set align' interference with the enum
-            cells.push(resolved.aligned(Align::LEFT_TOP.into()));
-            cells.push(Content::empty());
-            cells.push(item.body().styled(Self::set_parents(Parent(number))));
+            cells.push(Cell::new(resolved.aligned(Align::LEFT_TOP.into())));
+            cells.push(Cell::new(Content::empty()));
+            cells.push(Cell::new(item.body().styled(Self::set_parents(Parent(number)))));
             number = number.saturating_add(1);
         }
 
@@ -1,4 +1,7 @@
+use std::mem;
+
 use super::{AlignElem, BlockElem, ColbreakElem, ParElem, PlaceElem, Spacing, VElem};
+use crate::meta::{FootnoteElem, FootnoteEntry};
 use crate::prelude::*;
 use crate::visualize::{
     CircleElem, EllipseElem, ImageElem, PathElem, PolygonElem, RectElem, SquareElem,
@@ -26,7 +29,7 @@ impl Layout for FlowElem {
         styles: StyleChain,
         regions: Regions,
     ) -> SourceResult<Fragment> {
-        let mut layouter = FlowLayouter::new(regions);
+        let mut layouter = FlowLayouter::new(regions, styles);
 
         for mut child in &self.children() {
             let outer = styles;
@@ -37,7 +40,7 @@ impl Layout for FlowElem {
             }
 
             if let Some(elem) = child.to::<VElem>() {
-                layouter.layout_spacing(elem, styles);
+                layouter.layout_spacing(vt, elem, styles)?;
             } else if let Some(elem) = child.to::<ParElem>() {
                 layouter.layout_par(vt, elem, styles)?;
             } else if child.is::<RectElem>()
@@ -63,21 +66,25 @@ impl Layout for FlowElem {
             } else if child.is::<ColbreakElem>() {
                 if !layouter.regions.backlog.is_empty() || layouter.regions.last.is_some()
                 {
-                    layouter.finish_region();
+                    layouter.finish_region(vt)?;
                 }
             } else {
                 bail!(child.span(), "unexpected flow child");
             }
         }
 
-        Ok(layouter.finish())
+        layouter.finish(vt)
     }
 }
 
 /// Performs flow layout.
 struct FlowLayouter<'a> {
+    /// Whether this is the root flow.
+    root: bool,
     /// The regions to layout children into.
     regions: Regions<'a>,
+    /// The shared styles.
+    styles: StyleChain<'a>,
     /// Whether the flow should expand to fill the region.
     expand: Axes<bool>,
     /// The initial size of `regions.size` that was available before we started
@@ -87,10 +94,24 @@ struct FlowLayouter<'a> {
     last_was_par: bool,
     /// Spacing and layouted blocks.
     items: Vec<FlowItem>,
+    /// Whether we have any footnotes in the current region.
+    has_footnotes: bool,
+    /// Footnote configuration.
+    footnote_config: FootnoteConfig,
+    /// Floats that didn't fit into the current region, along with their
+    /// placement and clearance.
+    pending_floats: Vec<(Frame, Smart<Align>, Abs)>,
     /// Finished frames for previous regions.
     finished: Vec<Frame>,
 }
 
+/// Cached footnote configuration.
+struct FootnoteConfig {
+    separator: Content,
+    clearance: Abs,
+    gap: Abs,
+}
+
 /// A prepared item in a flow layout.
 #[derive(Debug)]
 enum FlowItem {
@@ -102,36 +123,59 @@ enum FlowItem {
     Frame(Frame, Axes<Align>, bool),
     /// An absolutely placed frame.
     Placed(Frame),
+    /// A floating frame, whether it is placed at the top or bottom of the
+    /// region, and its clearance.
+    Float(Frame, Align, Abs),
+    /// A footnote frame (can also be the separator).
+    Footnote(Frame),
 }
 
 impl<'a> FlowLayouter<'a> {
     /// Create a new flow layouter.
-    fn new(mut regions: Regions<'a>) -> Self {
+    fn new(mut regions: Regions<'a>, styles: StyleChain<'a>) -> Self {
         let expand = regions.expand;
 
-        // Disable vertical expansion for children.
+        // Disable vertical expansion & root for children.
         regions.expand.y = false;
+        let root = mem::replace(&mut regions.root, false);
 
         Self {
+            root,
             regions,
+            styles,
             expand,
             initial: regions.size,
             last_was_par: false,
             items: vec![],
+            has_footnotes: false,
+            footnote_config: FootnoteConfig {
+                separator: FootnoteElem::separator_in(styles),
+                clearance: FootnoteElem::clearance_in(styles),
+                gap: FootnoteElem::gap_in(styles),
+            },
+            pending_floats: vec![],
             finished: vec![],
         }
     }
 
     /// Layout vertical spacing.
     #[tracing::instrument(name = "FlowLayouter::layout_spacing", skip_all)]
-    fn layout_spacing(&mut self, v: &VElem, styles: StyleChain) {
-        self.layout_item(match v.amount() {
-            Spacing::Rel(rel) => FlowItem::Absolute(
-                rel.resolve(styles).relative_to(self.initial.y),
-                v.weakness(styles) > 0,
-            ),
-            Spacing::Fr(fr) => FlowItem::Fractional(fr),
-        });
+    fn layout_spacing(
+        &mut self,
+        vt: &mut Vt,
+        v: &VElem,
+        styles: StyleChain,
+    ) -> SourceResult<()> {
+        self.layout_item(
+            vt,
+            match v.amount() {
+                Spacing::Rel(rel) => FlowItem::Absolute(
+                    rel.resolve(styles).relative_to(self.initial.y),
+                    v.weakness(styles) > 0,
+                ),
+                Spacing::Fr(fr) => FlowItem::Fractional(fr),
+            },
+        )
     }
 
     /// Layout a paragraph.
@@ -149,31 +193,21 @@ impl<'a> FlowLayouter<'a> {
             .layout(vt, styles, consecutive, self.regions.base(), self.regions.expand.x)?
             .into_frames();
 
-        let mut sticky = self.items.len();
-        for (i, item) in self.items.iter().enumerate().rev() {
-            match *item {
-                FlowItem::Absolute(_, _) => {}
-                FlowItem::Frame(.., true) => sticky = i,
-                _ => break,
-            }
-        }
-
+        // If the first lines don't fit, move them to the next region along
+        // with the sticky blocks before them.
         if let [first, ..] = frames.as_slice() {
             if !self.regions.size.y.fits(first.height()) && !self.regions.in_last() {
-                let carry: Vec<_> = self.items.drain(sticky..).collect();
-                self.finish_region();
-                for item in carry {
-                    self.layout_item(item);
-                }
+                let sticky = self.sticky_start();
+                self.carry(vt, sticky)?;
             }
         }
 
         for (i, frame) in frames.into_iter().enumerate() {
             if i > 0 {
-                self.layout_item(FlowItem::Absolute(leading, true));
+                self.layout_item(vt, FlowItem::Absolute(leading, true))?;
             }
 
-            self.layout_item(FlowItem::Frame(frame, aligns, false));
+            self.layout_item(vt, FlowItem::Frame(frame, aligns, false))?;
         }
 
         self.last_was_par = true;
@@ -193,7 +227,7 @@ impl<'a> FlowLayouter<'a> {
         let sticky = BlockElem::sticky_in(styles);
         let pod = Regions::one(self.regions.base(), Axes::splat(false));
         let frame = content.layout(vt, styles, pod)?.into_frame();
-        self.layout_item(FlowItem::Frame(frame, aligns, sticky));
+        self.layout_item(vt, FlowItem::Frame(frame, aligns, sticky))?;
         self.last_was_par = false;
         Ok(())
     }
@@ -206,11 +240,22 @@ impl<'a> FlowLayouter<'a> {
         styles: StyleChain,
     ) -> SourceResult<()> {
         // Placed elements that are out of flow produce placed items which
-        // aren't aligned later.
+        // aren't aligned later. Floating ones are moved to the top or bottom
+        // of the region.
         if let Some(placed) = block.to::<PlaceElem>() {
+            if placed.float(styles) {
+                let frame = block.layout(vt, styles, self.regions)?.into_frame();
+                let placement = match placed.alignment(styles).y {
+                    Some(GenAlign::Specific(align)) => Smart::Custom(align),
+                    _ => Smart::Auto,
+                };
+                let clearance = placed.clearance(styles);
+                return self.layout_float(vt, frame, placement, clearance);
+            }
+
             if placed.out_of_flow(styles) {
                 let frame = block.layout(vt, styles, self.regions)?.into_frame();
-                self.layout_item(FlowItem::Placed(frame));
+                self.layout_item(vt, FlowItem::Placed(frame))?;
                 return Ok(());
             }
         }
@@ -225,14 +270,25 @@ impl<'a> FlowLayouter<'a> {
         }
         .resolve(styles);
 
+        // If nothing of the block fits into this region, the sticky blocks
+        // before it, e.g. a heading, move to the next region along with it.
+        let start = self.sticky_start();
+        if self.can_carry(start) && !self.regions.in_last() {
+            let fragment = block.measure(vt, styles, self.regions)?;
+            if fragment.len() > 1 && fragment.iter().next().map_or(false, Frame::is_empty)
+            {
+                self.carry(vt, start)?;
+            }
+        }
+
         // Layout the block itself.
         let sticky = BlockElem::sticky_in(styles);
         let fragment = block.layout(vt, styles, self.regions)?;
         for (i, frame) in fragment.into_iter().enumerate() {
             if i > 0 {
-                self.finish_region();
+                self.finish_region(vt)?;
             }
-            self.layout_item(FlowItem::Frame(frame, aligns, sticky));
+            self.layout_item(vt, FlowItem::Frame(frame, aligns, sticky))?;
         }
 
         self.last_was_par = false;
@@ -242,26 +298,121 @@ impl<'a> FlowLayouter<'a> {
 
     /// Layout a finished frame.
     #[tracing::instrument(name = "FlowLayouter::layout_item", skip_all)]
-    fn layout_item(&mut self, item: FlowItem) {
+    fn layout_item(&mut self, vt: &mut Vt, item: FlowItem) -> SourceResult<()> {
         match item {
             FlowItem::Absolute(v, _) => self.regions.size.y -= v,
             FlowItem::Fractional(_) => {}
             FlowItem::Frame(ref frame, ..) => {
                 let size = frame.size();
                 if !self.regions.size.y.fits(size.y) && !self.regions.in_last() {
-                    self.finish_region();
+                    // Keep sticky blocks together with this one, unless
+                    // there is nothing else in the region.
+                    let sticky = self.sticky_start();
+                    if self.can_carry(sticky) {
+                        self.carry(vt, sticky)?;
+                    } else {
+                        self.finish_region(vt)?;
+                    }
                 }
 
                 self.regions.size.y -= size.y;
+                if self.root {
+                    return self.handle_footnotes(vt, item, size.y);
+                }
             }
             FlowItem::Placed(_) => {}
+            FlowItem::Float(..) => {}
+            FlowItem::Footnote(_) => {}
         }
 
         self.items.push(item);
+        Ok(())
+    }
+
+    /// The index of the first item in the trailing run of sticky blocks,
+    /// which should stay in the same region as the next block.
+    fn sticky_start(&self) -> usize {
+        let mut sticky = self.items.len();
+        for (i, item) in self.items.iter().enumerate().rev() {
+            match *item {
+                FlowItem::Absolute(_, _) | FlowItem::Footnote(_) => {}
+                FlowItem::Frame(.., true) => sticky = i,
+                _ => break,
+            }
+        }
+        sticky
+    }
+
+    /// Whether moving the items from `start` onwards to the next region is
+    /// useful, i.e. whether there are any and something else precedes them.
+    fn can_carry(&self, start: usize) -> bool {
+        start < self.items.len()
+            && self.items[..start]
+                .iter()
+                .any(|item| matches!(item, FlowItem::Frame(..) | FlowItem::Float(..)))
+    }
+
+    /// Move the items from `start` onwards to the next region.
+    fn carry(&mut self, vt: &mut Vt, start: usize) -> SourceResult<()> {
+        // Footnotes of the carried items are found again when they are laid
+        // out in the next region.
+        let carry: Vec<_> = self
+            .items
+            .drain(start..)
+            .filter(|item| !matches!(item, FlowItem::Footnote(_)))
+            .collect();
+        self.finish_region(vt)?;
+        for item in carry {
+            self.layout_item(vt, item)?;
+        }
+        Ok(())
+    }
+
+    /// Layout a floating frame at the top or bottom of the current region or
+    /// queue it for the next region if it doesn't fit. Floats are placed in
+    /// the order in which they appear. A float that doesn't fit into an empty
+    /// region is placed anyway, as it wouldn't fit into the next one either.
+    fn layout_float(
+        &mut self,
+        vt: &mut Vt,
+        frame: Frame,
+        placement: Smart<Align>,
+        clearance: Abs,
+    ) -> SourceResult<()> {
+        let height = frame.height() + clearance;
+        let empty = !self
+            .items
+            .iter()
+            .any(|item| matches!(item, FlowItem::Frame(..) | FlowItem::Float(..)));
+        if !self.pending_floats.is_empty()
+            || (!self.regions.size.y.fits(height) && !self.regions.in_last() && !empty)
+        {
+            self.pending_floats.push((frame, placement, clearance));
+            return Ok(());
+        }
+
+        // Without a given placement, the float moves to whichever end of the
+        // region is closer to its position in the flow.
+        let placement = placement.unwrap_or_else(|| {
+            if self.regions.size.y - height / 2.0 >= self.initial.y / 2.0 {
+                Align::Top
+            } else {
+                Align::Bottom
+            }
+        });
+
+        let item = FlowItem::Float(frame, placement, clearance);
+        self.regions.size.y -= height;
+        if self.root {
+            return self.handle_footnotes(vt, item, height);
+        }
+
+        self.items.push(item);
+        Ok(())
     }
 
     /// Finish the frame for one region.
-    fn finish_region(&mut self) {
+    fn finish_region(&mut self, vt: &mut Vt) -> SourceResult<()> {
         // Trim weak spacing.
         while self
             .items
@@ -274,6 +425,10 @@ impl<'a> FlowLayouter<'a> {
         // Determine the used size.
         let mut fr = Fr::zero();
         let mut used = Size::zero();
+        let mut footnote_height = Abs::zero();
+        let mut float_top_height = Abs::zero();
+        let mut float_bottom_height = Abs::zero();
+        let mut first_footnote = true;
         for item in &self.items {
             match item {
                 FlowItem::Absolute(v, _) => used.y += *v,
@@ -284,19 +439,43 @@ impl<'a> FlowLayouter<'a> {
                     used.x.set_max(size.x);
                 }
                 FlowItem::Placed(_) => {}
+                FlowItem::Float(frame, placement, clearance) => {
+                    let height = frame.height() + *clearance;
+                    if *placement == Align::Bottom {
+                        float_bottom_height += height;
+                    } else {
+                        float_top_height += height;
+                    }
+                    used.x.set_max(frame.width());
+                }
+                FlowItem::Footnote(frame) => {
+                    let size = frame.size();
+                    footnote_height += size.y;
+                    if !first_footnote {
+                        footnote_height += self.footnote_config.gap;
+                    }
+                    first_footnote = false;
+                    used.x.set_max(size.x);
+                }
             }
         }
+        used.y += footnote_height + float_top_height + float_bottom_height;
 
         // Determine the size of the flow in this region depending on whether
-        // the region expands. Also account for fractional spacing.
+        // the region expands. Also account for fractional spacing and
+        // footnotes, which are placed at the bottom of the region. Floats are
+        // placed at the top of the region and above the footnotes.
         let mut size = self.expand.select(self.initial, used).min(self.initial);
-        if fr.get() > 0.0 && self.initial.y.is_finite() {
+        if (fr.get() > 0.0 || self.has_footnotes) && self.initial.y.is_finite() {
             size.y = self.initial.y;
         }
 
         let mut output = Frame::new(size);
-        let mut offset = Abs::zero();
+        let mut offset = float_top_height;
         let mut ruler = Align::Top;
+        let mut footnote_offset = size.y - footnote_height;
+        let mut float_top_offset = Abs::zero();
+        let mut float_bottom_offset = footnote_offset - float_bottom_height;
 
         // Place all frames.
         for item in self.items.drain(..) {
@@ -319,6 +498,23 @@ impl<'a> FlowLayouter<'a> {
                 FlowItem::Placed(frame) => {
                     output.push_frame(Point::zero(), frame);
                 }
+                FlowItem::Float(frame, placement, clearance) => {
+                    let y = if placement == Align::Bottom {
+                        let y = float_bottom_offset + clearance;
+                        float_bottom_offset = y + frame.height();
+                        y
+                    } else {
+                        let y = float_top_offset;
+                        float_top_offset += frame.height() + clearance;
+                        y
+                    };
+                    output.push_frame(Point::with_y(y), frame);
+                }
+                FlowItem::Footnote(frame) => {
+                    let pos = Point::with_y(footnote_offset);
+                    footnote_offset += frame.height() + self.footnote_config.gap;
+                    output.push_frame(pos, frame);
+                }
             }
         }
 
@@ -326,17 +522,153 @@ impl<'a> FlowLayouter<'a> {
         self.finished.push(output);
         self.regions.next();
         self.initial = self.regions.size;
+        self.has_footnotes = false;
+
+        // Try to place the floats that didn't fit into the previous region.
+        for (frame, placement, clearance) in mem::take(&mut self.pending_floats) {
+            self.layout_float(vt, frame, placement, clearance)?;
+        }
+
+        Ok(())
     }
 
     /// Finish layouting and return the resulting fragment.
-    fn finish(mut self) -> Fragment {
+    fn finish(mut self, vt: &mut Vt) -> SourceResult<Fragment> {
         if self.expand.y {
             while !self.regions.backlog.is_empty() {
-                self.finish_region();
+                self.finish_region(vt)?;
             }
         }
 
-        self.finish_region();
-        Fragment::frames(self.finished)
+        self.finish_region(vt)?;
+
+        // Floats that were deferred at the end of the flow get regions of
+        // their own.
+        while !self.items.is_empty() || !self.pending_floats.is_empty() {
+            self.finish_region(vt)?;
+        }
+
+        Ok(Fragment::frames(self.finished))
+    }
+
+    /// Processes all footnotes in the frame.
+    #[tracing::instrument(skip_all)]
+    fn handle_footnotes(
+        &mut self,
+        vt: &mut Vt,
+        item: FlowItem,
+        height: Abs,
+    ) -> SourceResult<()> {
+        // Find footnotes in the frame.
+        let mut notes = Vec::new();
+        if let FlowItem::Frame(frame, ..) | FlowItem::Float(frame, ..) = &item {
+            find_footnotes(&mut notes, frame);
+        }
+
+        // Remember where we started so that we can move the item to the
+        // next region if its footnotes don't fit into this one.
+        let mut start = self.items.len();
+        let mut had_footnotes = self.has_footnotes;
+        self.items.push(item);
+
+        // No new footnotes.
+        if notes.is_empty() {
+            return Ok(());
+        }
+
+        // The currently handled footnote.
+        let mut k = 0;
+
+        // Whether we can still skip one region to ensure that the footnote
+        // and its entry are on the same page.
+        let mut can_skip = !self.regions.in_last();
+
+        // Process footnotes.
+        while k < notes.len() {
+            if !self.has_footnotes {
+                self.layout_footnote_separator(vt)?;
+            }
+
+            self.regions.size.y -= self.footnote_config.gap;
+            let frames = FootnoteEntry::new(notes[k].clone())
+                .pack()
+                .layout(vt, self.styles, self.regions)?
+                .into_frames();
+
+            // If the entry doesn't start in this region, move the item
+            // containing the footnote to the next region and start over
+            // there. We only do this once to avoid an infinite loop.
+            let fits = frames.first().map_or(false, |frame| {
+                !frame.height().is_zero() && self.regions.size.y.fits(frame.height())
+            });
+
+            if !fits && can_skip {
+                let item = self.items.drain(start..).next().unwrap();
+                self.has_footnotes = had_footnotes;
+                self.finish_region(vt)?;
+
+                start = self.items.len();
+                had_footnotes = self.has_footnotes;
+                self.regions.size.y -= height;
+                self.items.push(item);
+
+                can_skip = false;
+                k = 0;
+                continue;
+            }
+
+            // The entry may be split across multiple regions.
+            for (i, frame) in frames.into_iter().enumerate() {
+                if i > 0 {
+                    self.finish_region(vt)?;
+                    self.layout_footnote_separator(vt)?;
+                    can_skip = false;
+                    self.regions.size.y -= self.footnote_config.gap;
+                }
+
+                self.regions.size.y -= frame.height();
+                self.items.push(FlowItem::Footnote(frame));
+            }
+
+            k += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Layout and save the footnote separator, typically a line.
+    #[tracing::instrument(skip_all)]
+    fn layout_footnote_separator(&mut self, vt: &mut Vt) -> SourceResult<()> {
+        let expand = Axes::new(self.regions.expand.x, false);
+        let pod = Regions::one(self.regions.base(), expand);
+        let separator = &self.footnote_config.separator;
+
+        let mut frame = separator.layout(vt, self.styles, pod)?.into_frame();
+        frame.size_mut().y += self.footnote_config.clearance;
+        frame.translate(Point::with_y(self.footnote_config.clearance));
+
+        self.has_footnotes = true;
+        self.regions.size.y -= frame.height();
+        self.items.push(FlowItem::Footnote(frame));
+
+        Ok(())
+    }
+}
+
+/// Finds all footnotes in the frame.
+#[tracing::instrument(skip_all)]
+fn find_footnotes(notes: &mut Vec<FootnoteElem>, frame: &Frame) {
+    for (_, item) in frame.items() {
+        match item {
+            FrameItem::Group(group) => find_footnotes(notes, &group.frame),
+            FrameItem::Meta(Meta::Elem(content), _)
+                if !notes.iter().any(|note| note.0.location() == content.location()) =>
+            {
+                if let Some(footnote) = content.to::<FootnoteElem>() {
+                    notes.push(footnote.clone());
+                }
+            }
+            _ => {}
+        }
     }
 }
@@ -110,7 +110,7 @@ impl Layout for GridElem {
         regions: Regions,
     ) -> SourceResult<Fragment> {
         // Prepare grid layout by unifying content and gutter tracks.
-        let cells = self.children();
+        let cells: Vec<_> = self.children().into_iter().map(Cell::new).collect();
         let layouter = GridLayouter::new(
             vt,
             Axes::new(&self.columns(styles).0, &self.rows(styles).0),
@@ -140,16 +140,132 @@ cast_to_value! {
     v: TrackSizings => v.0.into()
 }
 
+/// A cell in a grid, which may span multiple columns and rows.
+#[derive(Debug, Clone, Hash)]
+pub struct Cell {
+    /// The cell's content.
+    pub body: Content,
+    /// How many columns the cell spans.
+    pub colspan: NonZeroUsize,
+    /// How many rows the cell spans.
+    pub rowspan: NonZeroUsize,
+}
+
+impl Cell {
+    /// Create a cell that covers a single column and row.
+    pub fn new(body: Content) -> Self {
+        Self {
+            body,
+            colspan: NonZeroUsize::ONE,
+            rowspan: NonZeroUsize::ONE,
+        }
+    }
+}
+
+/// The tracks covered by a cell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Area {
+    /// The first column.
+    pub x: usize,
+    /// The first row.
+    pub y: usize,
+    /// The number of columns.
+    pub colspan: usize,
+    /// The number of rows.
+    pub rowspan: usize,
+}
+
+/// How the cells of a grid are divided into a header, a body and a footer.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Sections {
+    /// The header, formed by the first cells.
+    pub header: Option<Section>,
+    /// The footer, formed by the last cells.
+    pub footer: Option<Section>,
+}
+
+/// A header or footer of a grid.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Section {
+    /// The number of cells in the section.
+    pub cells: usize,
+    /// Whether the section is repeated in every region the grid spans.
+    pub repeat: bool,
+}
+
+/// Determine where cells are placed in a grid with the given number of
+/// columns.
+///
+/// The cells are placed in row-major order, each one into the first position
+/// after the previous cell where it fits without overlapping any other cell.
+/// The body and the footer each start in a new row. Cells that span more
+/// columns than there are get clamped. The returned areas are in content
+/// coordinates, i.e. they don't count gutter tracks.
+pub fn place_cells(columns: usize, cells: &[Cell], sections: Sections) -> Vec<Area> {
+    let c = columns.max(1);
+    let mut occupied: Vec<bool> = vec![];
+    let mut areas = Vec::with_capacity(cells.len());
+    let mut cursor = 0;
+    let mut rows = 0;
+
+    let body = sections.header.map_or(0, |header| header.cells);
+    let footer = cells.len() - sections.footer.map_or(0, |footer| footer.cells);
+
+    for (i, cell) in cells.iter().enumerate() {
+        if (i == body && i > 0) || (i == footer && sections.footer.is_some()) {
+            cursor = cursor.max(rows * c);
+        }
+
+        let colspan = cell.colspan.get().min(c);
+        let rowspan = cell.rowspan.get();
+        let free = |occupied: &[bool], i: usize| {
+            let (x, y) = (i % c, i / c);
+            x + colspan <= c
+                && (y..y + rowspan).all(|y| {
+                    (x..x + colspan)
+                        .all(|x| !occupied.get(y * c + x).copied().unwrap_or(false))
+                })
+        };
+
+        while !free(&occupied, cursor) {
+            cursor += 1;
+        }
+
+        let area = Area { x: cursor % c, y: cursor / c, colspan, rowspan };
+        for y in area.y..area.y + rowspan {
+            for x in area.x..area.x + colspan {
+                let i = y * c + x;
+                if i >= occupied.len() {
+                    occupied.resize(i + 1, false);
+                }
+                occupied[i] = true;
+            }
+        }
+
+        areas.push(area);
+        cursor += colspan;
+        rows = rows.max(area.y + rowspan);
+    }
+
+    areas
+}
+
 /// Performs grid layout.
 pub struct GridLayouter<'a, 'v> {
     /// The core context.
     vt: &'a mut Vt<'v>,
     /// The grid cells.
-    cells: &'a [Content],
-    /// Whether this is an RTL grid.
-    is_rtl: bool,
-    /// Whether this grid has gutters.
-    has_gutter: bool,
+    cells: &'a [Cell],
+    /// The tracks covered by each cell, including gutter tracks.
+    areas: Vec<Area>,
+    /// Which cell covers each track cell.
+    map: CellMap,
+    /// The header rows, if any.
+    header: Option<Header>,
+    /// The footer rows, if any.
+    footer: Option<Footer>,
+    /// Whether the header is currently being laid out.
+    in_header: bool,
     /// The column tracks including gutter tracks.
     cols: Vec<Sizing>,
     /// The row tracks including gutter tracks.
@@ -181,6 +297,29 @@ pub struct GridLayout {
     pub cols: Vec<Abs>,
     /// The heights of the resulting rows segments, by region.
     pub rows: Vec<Vec<RowPiece>>,
+    /// The tracks covered by each cell, including gutter tracks.
+    pub areas: Vec<Area>,
+    /// Which cell covers each track cell.
+    pub map: CellMap,
+}
+
+/// Maps the track cells of a grid to the cells covering them.
+#[derive(Debug, Clone)]
+pub struct CellMap {
+    /// The number of column tracks.
+    cols: usize,
+    /// For each track cell in row-major order, the index of the covering cell.
+    indices: Vec<Option<usize>>,
+}
+
+impl CellMap {
+    /// The index of the cell covering the track cell in column `x` and row
+    /// `y`.
+    ///
+    /// Returns `None` for gutter tracks between cells and for empty positions.
+    pub fn get(&self, x: usize, y: usize) -> Option<usize> {
+        self.indices.get(y * self.cols + x).copied().flatten()
+    }
 }
 
 /// Details about a resulting row piece.
@@ -192,6 +331,28 @@ pub struct RowPiece {
     pub y: usize,
 }
 
+/// The rows at the start of a grid that form its header.
+#[derive(Debug, Copy, Clone)]
+struct Header {
+    /// The index of the first row after the header.
+    end: usize,
+    /// Whether the header is repeated at the top of every region.
+    repeat: bool,
+    /// The height of the header when it was last laid out.
+    height: Abs,
+}
+
+/// The rows at the end of a grid that form its footer.
+#[derive(Debug, Clone)]
+struct Footer {
+    /// The index of the first footer row.
+    start: usize,
+    /// Whether the footer is repeated at the bottom of every region.
+    repeat: bool,
+    /// The heights of the footer rows.
+    heights: Vec<Abs>,
+}
+
 /// Produced by initial row layout, auto and relative rows are already finished,
 /// fractional rows not yet.
 enum Row {
@@ -209,7 +370,28 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         vt: &'a mut Vt<'v>,
         tracks: Axes<&[Sizing]>,
         gutter: Axes<&[Sizing]>,
-        cells: &'a [Content],
+        cells: &'a [Cell],
+        regions: Regions<'a>,
+        styles: StyleChain<'a>,
+    ) -> Self {
+        Self::with_sections(
+            vt,
+            tracks,
+            gutter,
+            cells,
+            Sections::default(),
+            regions,
+            styles,
+        )
+    }
+
+    /// Create a new grid layouter for a grid with a header or footer.
+    pub fn with_sections(
+        vt: &'a mut Vt<'v>,
+        tracks: Axes<&[Sizing]>,
+        gutter: Axes<&[Sizing]>,
+        cells: &'a [Cell],
+        sections: Sections,
         regions: Regions<'a>,
         styles: StyleChain<'a>,
     ) -> Self {
@@ -220,12 +402,12 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let c = tracks.x.len().max(1);
 
         // Number of content rows: At least as many as given, but also at least
-        // as many as needed to place each item.
+        // as many as needed to place each cell.
+        let mut areas = place_cells(c, cells, sections);
         let r = {
-            let len = cells.len();
             let given = tracks.y.len();
-            let needed = len / c + (len % c).clamp(0, 1);
-            given.max(needed)
+            let needed = areas.iter().map(|area| area.y + area.rowspan).max();
+            given.max(needed.unwrap_or(0))
         };
 
         let has_gutter = gutter.any(|tracks| !tracks.is_empty());
@@ -257,12 +439,63 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
             rows.pop();
         }
 
+        // Determine the header and footer rows. With gutter, the gutter row
+        // after the header and the one before the footer belong to them.
+        let header = sections.header.map(|section| {
+            let end = areas[..section.cells]
+                .iter()
+                .map(|area| area.y + area.rowspan)
+                .max()
+                .unwrap_or(0);
+            let end = if has_gutter { 2 * end } else { end };
+            Header {
+                end: end.min(rows.len()),
+                repeat: section.repeat,
+                height: Abs::zero(),
+            }
+        });
+
+        let footer = sections.footer.map(|section| {
+            let start = areas[cells.len() - section.cells..]
+                .iter()
+                .map(|area| area.y)
+                .min()
+                .unwrap_or(r);
+            let start = if has_gutter { (2 * start).saturating_sub(1) } else { start };
+            Footer { start, repeat: section.repeat, heights: vec![] }
+        });
+
         // Reverse for RTL.
         let is_rtl = TextElem::dir_in(styles) == Dir::RTL;
         if is_rtl {
             cols.reverse();
         }
 
+        // Translate the cell areas into track coordinates. With gutter, even
+        // columns and rows are children and odd ones are gutter. A cell also
+        // covers the gutter tracks in between the tracks it spans.
+        let mut indices = vec![None; cols.len() * rows.len()];
+        for (i, area) in areas.iter_mut().enumerate() {
+            if has_gutter {
+                area.x *= 2;
+                area.y *= 2;
+                area.colspan = 2 * area.colspan - 1;
+                area.rowspan = 2 * area.rowspan - 1;
+            }
+
+            // Columns are reordered, but the cell slice is not.
+            if is_rtl {
+                area.x = cols.len() - area.x - area.colspan;
+            }
+
+            for y in area.y..area.y + area.rowspan {
+                for x in area.x..area.x + area.colspan {
+                    indices[y * cols.len() + x] = Some(i);
+                }
+            }
+        }
+
+        let map = CellMap { cols: cols.len(), indices };
         let rcols = vec![Abs::zero(); cols.len()];
         let lrows = vec![];
 
@@ -274,8 +507,11 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         Self {
             vt,
             cells,
-            is_rtl,
-            has_gutter,
+            areas,
+            map,
+            header,
+            footer,
+            in_header: false,
             cols,
             rows,
             regions,
@@ -294,29 +530,152 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
     pub fn layout(mut self) -> SourceResult<GridLayout> {
         self.measure_columns()?;
 
-        for y in 0..self.rows.len() {
+        // A repeated footer is laid out at the end of every region, so we
+        // reserve space for it upfront.
+        let mut end = self.rows.len();
+        if let Some(footer) = self.footer.clone().filter(|footer| footer.repeat) {
+            self.measure_footer()?;
+            self.regions.size.y -= self.footer_height();
+            end = footer.start;
+        }
+
+        self.layout_header()?;
+
+        let start = self.header.map_or(0, |header| header.end);
+        for y in start..end {
             // Skip to next region if current one is full, but only for content
             // rows, not for gutter rows.
-            if y % 2 == 0 && self.regions.is_full() {
-                self.finish_region()?;
+            if y % 2 == 0 && self.regions.is_full() && !self.stuck() {
+                self.finish_region(false)?;
             }
 
-            match self.rows[y] {
-                Sizing::Auto => self.layout_auto_row(y)?,
-                Sizing::Rel(v) => self.layout_relative_row(v, y)?,
-                Sizing::Fr(v) => self.lrows.push(Row::Fr(v, y)),
-            }
+            self.layout_row(y)?;
         }
 
-        self.finish_region()?;
+        self.finish_region(true)?;
+        self.layout_spanning_rows()?;
 
         Ok(GridLayout {
             fragment: Fragment::frames(self.finished),
             cols: self.rcols,
             rows: self.rrows,
+            areas: self.areas,
+            map: self.map,
         })
     }
 
+    /// Layout the row with index `y`.
+    fn layout_row(&mut self, y: usize) -> SourceResult<()> {
+        match self.rows[y] {
+            Sizing::Auto => self.layout_auto_row(y),
+            Sizing::Rel(v) => self.layout_relative_row(v, y),
+            Sizing::Fr(v) => {
+                self.lrows.push(Row::Fr(v, y));
+                Ok(())
+            }
+        }
+    }
+
+    /// Layout the header rows into the current region.
+    fn layout_header(&mut self) -> SourceResult<()> {
+        let Some(header) = self.header else { return Ok(()) };
+
+        self.in_header = true;
+        for y in 0..header.end {
+            self.layout_row(y)?;
+        }
+        self.in_header = false;
+
+        let height: Abs = self
+            .lrows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Frame(frame, y) if *y < header.end => Some(frame.height()),
+                _ => None,
+            })
+            .sum();
+
+        if let Some(header) = &mut self.header {
+            header.height = height;
+        }
+
+        Ok(())
+    }
+
+    /// Determine the heights of the footer rows.
+    ///
+    /// A repeated footer is laid out into a single region in every region, so
+    /// rows that would break across regions are measured as a whole.
+    fn measure_footer(&mut self) -> SourceResult<()> {
+        let Some(start) = self.footer.as_ref().map(|footer| footer.start) else {
+            return Ok(());
+        };
+
+        let mut heights = vec![];
+        for y in start..self.rows.len() {
+            heights.push(match self.rows[y] {
+                Sizing::Auto => self.measure_auto_row(y, false)?.unwrap().iter().sum(),
+                Sizing::Rel(v) => {
+                    v.resolve(self.styles).relative_to(self.regions.base().y)
+                }
+                Sizing::Fr(_) => Abs::zero(),
+            });
+        }
+
+        if let Some(footer) = &mut self.footer {
+            footer.heights = heights;
+        }
+
+        Ok(())
+    }
+
+    /// The height taken up by the footer in every region if it is repeated.
+    fn footer_height(&self) -> Abs {
+        self.footer
+            .as_ref()
+            .filter(|footer| footer.repeat)
+            .map_or(Abs::zero(), |footer| footer.heights.iter().sum())
+    }
+
+    /// Whether the current region holds no rows apart from the header and a
+    /// region break would not provide more space.
+    ///
+    /// When the grid has a header or a repeated footer, skipping to the next
+    /// region in such a case would only repeat them there without making
+    /// progress.
+    fn stuck(&self) -> bool {
+        if self.header.is_none() && self.footer_height() == Abs::zero() {
+            return false;
+        }
+
+        let end = self.header.map_or(0, |header| header.end);
+        let fresh = self.lrows.iter().all(|row| match row {
+            Row::Frame(_, y) | Row::Fr(_, y) => *y < end,
+        });
+
+        let next = self.regions.backlog.first().copied().or(self.regions.last);
+        fresh && next.map_or(true, |height| height <= self.initial.y)
+    }
+
+    /// The regions available to a row.
+    ///
+    /// In the regions after the current one, the repeated header and footer
+    /// take up part of the space.
+    fn row_regions<'b>(&self, backlog: &'b mut Vec<Abs>) -> Regions<'b> {
+        let repeated = self
+            .header
+            .filter(|header| header.repeat)
+            .map_or(Abs::zero(), |header| header.height)
+            + self.footer_height();
+
+        let mut regions =
+            self.regions.map(backlog, |size| Size::new(size.x, size.y - repeated));
+        regions.size = self.regions.size;
+        regions.full = self.regions.full;
+        regions.root = self.regions.root;
+        regions
+    }
+
     /// Determine all column sizes.
     fn measure_columns(&mut self) -> SourceResult<()> {
         // Sum of sizes of resolved relative tracks.
@@ -364,9 +723,6 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
     /// Measure the size that is available to auto columns.
     fn measure_auto_columns(&mut self, available: Abs) -> SourceResult<(Abs, usize)> {
-        let mut auto = Abs::zero();
-        let mut count = 0;
-
         // Determine size of auto columns by laying out all cells in those
         // columns, measuring them and finding the largest one.
         for (x, &col) in self.cols.iter().enumerate() {
@@ -376,31 +732,82 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
             let mut resolved = Abs::zero();
             for y in 0..self.rows.len() {
-                if let Some(cell) = self.cell(x, y) {
-                    // For relative rows, we can already resolve the correct
-                    // base and for auto and fr we could only guess anyway.
-                    let height = match self.rows[y] {
-                        Sizing::Rel(v) => {
-                            v.resolve(self.styles).relative_to(self.regions.base().y)
-                        }
-                        _ => self.regions.base().y,
-                    };
-
-                    let size = Size::new(available, height);
-                    let pod = Regions::one(size, Axes::splat(false));
-                    let frame = cell.measure(self.vt, self.styles, pod)?.into_frame();
-                    resolved.set_max(frame.width());
+                if let Some((cell, area)) = self.cell(x, y) {
+                    if area.colspan == 1 {
+                        let size = Size::new(available, self.measure_height(y));
+                        let pod = Regions::one(size, Axes::splat(false));
+                        let frame =
+                            cell.body.measure(self.vt, self.styles, pod)?.into_frame();
+                        resolved.set_max(frame.width());
+                    }
                 }
             }
 
             self.rcols[x] = resolved;
-            auto += resolved;
-            count += 1;
+        }
+
+        // Grow the auto columns spanned by cells that don't fit into them yet,
+        // narrower cells first. Cells spanning a fractional column are not
+        // considered since that column takes up all the remaining space
+        // anyway.
+        let mut spanning: Vec<_> =
+            (0..self.cells.len()).filter(|&i| self.areas[i].colspan > 1).collect();
+        spanning.sort_by_key(|&i| self.areas[i].colspan);
+
+        for i in spanning {
+            let area = self.areas[i];
+            let spanned = area.x..area.x + area.colspan;
+            let autos: Vec<_> =
+                spanned.clone().filter(|&x| self.cols[x] == Sizing::Auto).collect();
+            if autos.is_empty()
+                || spanned.clone().any(|x| matches!(self.cols[x], Sizing::Fr(_)))
+            {
+                continue;
+            }
+
+            // The cell may use the space of the relative columns it spans in
+            // addition to the space available to auto columns.
+            let rel: Abs = spanned
+                .clone()
+                .filter(|&x| self.cols[x] != Sizing::Auto)
+                .map(|x| self.rcols[x])
+                .sum();
+
+            let size = Size::new(available + rel, self.measure_height(area.y));
+            let pod = Regions::one(size, Axes::splat(false));
+            let frame =
+                self.cells[i].body.measure(self.vt, self.styles, pod)?.into_frame();
+
+            let excess = frame.width() - self.span_width(area);
+            if excess > Abs::zero() {
+                for &x in &autos {
+                    self.rcols[x] += excess / autos.len() as f64;
+                }
+            }
+        }
+
+        let mut auto = Abs::zero();
+        let mut count = 0;
+        for (&col, &rcol) in self.cols.iter().zip(&self.rcols) {
+            if col == Sizing::Auto {
+                auto += rcol;
+                count += 1;
+            }
         }
 
         Ok((auto, count))
     }
 
+    /// The height with which to measure cells in row `y` when sizing columns.
+    fn measure_height(&self, y: usize) -> Abs {
+        // For relative rows, we can already resolve the correct base and for
+        // auto and fr we could only guess anyway.
+        match self.rows[y] {
+            Sizing::Rel(v) => v.resolve(self.styles).relative_to(self.regions.base().y),
+            _ => self.regions.base().y,
+        }
+    }
+
     /// Distribute remaining space to fractional columns.
     fn grow_fractional_columns(&mut self, remaining: Abs, fr: Fr) {
         if fr.is_zero() {
@@ -455,7 +862,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let mut resolved = match self.measure_auto_row(y, true)? {
             Some(resolved) => resolved,
             None => {
-                self.finish_region()?;
+                self.finish_region(false)?;
                 self.measure_auto_row(y, false)?.unwrap()
             }
         };
@@ -475,8 +882,9 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         // Expand all but the last region.
         // Skip the first region if the space is eaten up by an fr row.
         let len = resolved.len();
+        let mut backlog = vec![];
         for (region, target) in self
-            .regions
+            .row_regions(&mut backlog)
             .iter()
             .zip(&mut resolved[..len - 1])
             .skip(self.lrows.iter().any(|row| matches!(row, Row::Fr(..))) as usize)
@@ -490,7 +898,7 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         for (i, frame) in fragment.into_iter().enumerate() {
             self.push_row(frame, y);
             if i + 1 < len {
-                self.finish_region()?;
+                self.finish_region(false)?;
             }
         }
 
@@ -506,12 +914,20 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
     ) -> SourceResult<Option<Vec<Abs>>> {
         let mut resolved: Vec<Abs> = vec![];
 
-        for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let mut pod = self.regions;
-                pod.size.x = rcol;
+        for x in 0..self.cols.len() {
+            let Some(i) = self.index(x, y) else { continue };
+            let area = self.areas[i];
+            if area.x != x {
+                continue;
+            }
 
-                let frames = cell.measure(self.vt, self.styles, pod)?.into_frames();
+            let sizes = if area.rowspan == 1 {
+                let mut backlog = vec![];
+                let mut pod = self.row_regions(&mut backlog);
+                pod.size.x = self.span_width(area);
+
+                let cell = &self.cells[i];
+                let frames = cell.body.measure(self.vt, self.styles, pod)?.into_frames();
 
                 // Skip the first region if one cell in it is empty. Then,
                 // remeasure.
@@ -524,20 +940,76 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
                     }
                 }
 
-                let mut sizes = frames.iter().map(|frame| frame.height());
-                for (target, size) in resolved.iter_mut().zip(&mut sizes) {
-                    target.set_max(size);
-                }
+                frames.iter().map(|frame| frame.height()).collect()
+            } else if area.y + area.rowspan - 1 == y {
+                self.measure_spanned(i, y)?
+            } else {
+                continue;
+            };
 
-                // New heights are maximal by virtue of being new. Note that
-                // this extend only uses the rest of the sizes iterator.
-                resolved.extend(sizes);
+            let mut sizes = sizes.into_iter();
+            for (target, size) in resolved.iter_mut().zip(&mut sizes) {
+                target.set_max(size);
             }
+
+            // New heights are maximal by virtue of being new. Note that
+            // this extend only uses the rest of the sizes iterator.
+            resolved.extend(sizes);
         }
 
         Ok(Some(resolved))
     }
 
+    /// Measure how much space a cell spanning multiple rows needs in its last
+    /// row `y`, given the rows above it that are already laid out.
+    fn measure_spanned(&mut self, i: usize, y: usize) -> SourceResult<Vec<Abs>> {
+        let area = self.areas[i];
+        let spanned = area.y..y;
+
+        // The cell flows through the spanned rows of previous regions and then
+        // into the current region.
+        let mut heights: Vec<Abs> = self
+            .rrows
+            .iter()
+            .filter(|rows| rows.iter().any(|row| spanned.contains(&row.y)))
+            .map(|rows| {
+                rows.iter()
+                    .filter(|row| spanned.contains(&row.y))
+                    .map(|row| row.height)
+                    .sum()
+            })
+            .collect();
+
+        let finished = heights.len();
+        let above: Abs = self
+            .lrows
+            .iter()
+            .filter_map(|row| match row {
+                Row::Frame(frame, y) if spanned.contains(y) => Some(frame.height()),
+                _ => None,
+            })
+            .sum();
+
+        let mut backlog = vec![];
+        let regions = self.row_regions(&mut backlog);
+        heights.push(above + regions.size.y);
+        heights.extend(regions.backlog);
+
+        let mut pod = regions;
+        pod.size = Size::new(self.span_width(area), heights[0]);
+        pod.full = heights[0];
+        pod.backlog = &heights[1..];
+
+        let frames = self.cells[i].body.measure(self.vt, self.styles, pod)?.into_frames();
+        let mut sizes: Vec<Abs> =
+            frames.iter().skip(finished).map(|frame| frame.height()).collect();
+        if let Some(first) = sizes.first_mut() {
+            *first = (*first - above).max(Abs::zero());
+        }
+
+        Ok(sizes)
+    }
+
     /// Layout a row with relative height. Such a row cannot break across
     /// multiple regions, but it may force a region break.
     fn layout_relative_row(&mut self, v: Rel<Length>, y: usize) -> SourceResult<()> {
@@ -546,8 +1018,11 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
 
         // Skip to fitting region.
         let height = frame.height();
-        while !self.regions.size.y.fits(height) && !self.regions.in_last() {
-            self.finish_region()?;
+        while !self.regions.size.y.fits(height)
+            && !self.regions.in_last()
+            && !self.stuck()
+        {
+            self.finish_region(false)?;
 
             // Don't skip multiple regions for gutter and don't push a row.
             if y % 2 == 1 {
@@ -566,14 +1041,16 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         let mut pos = Point::zero();
 
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                let size = Size::new(rcol, height);
-                let mut pod = Regions::one(size, Axes::splat(true));
-                if self.rows[y] == Sizing::Auto {
-                    pod.full = self.regions.full;
+            if let Some((cell, area)) = self.cell(x, y) {
+                if area.rowspan == 1 {
+                    let size = Size::new(self.span_width(area), height);
+                    let mut pod = Regions::one(size, Axes::splat(true));
+                    if self.rows[y] == Sizing::Auto {
+                        pod.full = self.regions.full;
+                    }
+                    let frame = cell.body.layout(self.vt, self.styles, pod)?.into_frame();
+                    output.push_frame(pos, frame);
                 }
-                let frame = cell.layout(self.vt, self.styles, pod)?.into_frame();
-                output.push_frame(pos, frame);
             }
 
             pos.x += rcol;
@@ -599,13 +1076,15 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         // Layout the row.
         let mut pos = Point::zero();
         for (x, &rcol) in self.rcols.iter().enumerate() {
-            if let Some(cell) = self.cell(x, y) {
-                pod.size.x = rcol;
-
-                // Push the layouted frames into the individual output frames.
-                let fragment = cell.layout(self.vt, self.styles, pod)?;
-                for (output, frame) in outputs.iter_mut().zip(fragment) {
-                    output.push_frame(pos, frame);
+            if let Some((cell, area)) = self.cell(x, y) {
+                if area.rowspan == 1 {
+                    pod.size.x = self.span_width(area);
+
+                    // Push the layouted frames into the individual output frames.
+                    let fragment = cell.body.layout(self.vt, self.styles, pod)?;
+                    for (output, frame) in outputs.iter_mut().zip(fragment) {
+                        output.push_frame(pos, frame);
+                    }
                 }
             }
 
@@ -615,14 +1094,103 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         Ok(Fragment::frames(outputs))
     }
 
+    /// Layout the cells spanning multiple rows into the regions that their
+    /// rows ended up in.
+    ///
+    /// This happens once all regions are finished because only then the
+    /// heights of all spanned rows, including fractional ones, are known.
+    fn layout_spanning_rows(&mut self) -> SourceResult<()> {
+        for (cell, &area) in self.cells.iter().zip(&self.areas) {
+            if area.rowspan == 1 {
+                continue;
+            }
+
+            // Find the part of the cell in each region: The region's index,
+            // the offset of the first spanned row and the spanned height.
+            let spanned = area.y..area.y + area.rowspan;
+            let mut parts = vec![];
+            for (i, rows) in self.rrows.iter().enumerate() {
+                let mut offset = Abs::zero();
+                let mut part: Option<(usize, Abs, Abs)> = None;
+                for row in rows {
+                    if spanned.contains(&row.y) {
+                        part.get_or_insert((i, offset, Abs::zero())).2 += row.height;
+                    }
+                    offset += row.height;
+                }
+                parts.extend(part);
+            }
+
+            if parts.is_empty() {
+                continue;
+            }
+
+            // Cells of a repeated header or footer are laid out in full in
+            // every region instead of flowing from one region into the next.
+            let repeated = self
+                .header
+                .map_or(false, |header| header.repeat && area.y < header.end)
+                || self
+                    .footer
+                    .as_ref()
+                    .map_or(false, |footer| footer.repeat && area.y >= footer.start);
+            let groups = parts.chunks(if repeated { 1 } else { parts.len() });
+
+            let dx: Abs = self.rcols[..area.x].iter().sum();
+            for group in groups {
+                let heights: Vec<_> =
+                    group.iter().map(|&(_, _, height)| height).collect();
+                let size = Size::new(self.span_width(area), heights[0]);
+                let mut pod = Regions::one(size, Axes::splat(true));
+                pod.backlog = &heights[1..];
+
+                let fragment = cell.body.layout(self.vt, self.styles, pod)?;
+                for (&(i, dy, _), frame) in group.iter().zip(fragment) {
+                    self.finished[i].push_frame(Point::new(dx, dy), frame);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Push a row frame into the current region.
     fn push_row(&mut self, frame: Frame, y: usize) {
         self.regions.size.y -= frame.height();
         self.lrows.push(Row::Frame(frame, y));
     }
 
-    /// Finish rows for one region.
-    fn finish_region(&mut self) -> SourceResult<()> {
+    /// Finish rows for one region and, unless it is the last one, start the
+    /// next region.
+    fn finish_region(&mut self, last: bool) -> SourceResult<()> {
+        // A header must not end up alone at the end of a region. If there is
+        // nothing else in this region, move the header to the next one.
+        let end = self.header.map_or(0, |header| header.end);
+        let orphaned = !last
+            && !self.in_header
+            && end > 0
+            && !self.lrows.is_empty()
+            && !self.stuck()
+            && self.lrows.iter().all(|row| match row {
+                Row::Frame(_, y) | Row::Fr(_, y) => *y < end,
+            });
+
+        if orphaned {
+            self.lrows.clear();
+        }
+
+        // Place the repeated footer at the end of the region, unless the
+        // region is empty.
+        if let Some(footer) = self.footer.clone().filter(|footer| footer.repeat) {
+            self.regions.size.y += self.footer_height();
+            if last || !self.lrows.is_empty() {
+                for (y, &height) in (footer.start..).zip(&footer.heights) {
+                    let frame = self.layout_single_row(height, y)?;
+                    self.push_row(frame, y);
+                }
+            }
+        }
+
         // Determine the height of existing rows in the region.
         let mut used = Abs::zero();
         let mut fr = Fr::zero();
@@ -667,33 +1235,41 @@ impl<'a, 'v> GridLayouter<'a, 'v> {
         self.regions.next();
         self.initial = self.regions.size;
 
+        if !last {
+            self.regions.size.y -= self.footer_height();
+            if !self.in_header
+                && self.header.map_or(false, |header| header.repeat || orphaned)
+            {
+                self.layout_header()?;
+            }
+        }
+
         Ok(())
     }
 
-    /// Get the content of the cell in column `x` and row `y`.
-    ///
-    /// Returns `None` if it's a gutter cell.
+    /// The width of the columns an area spans.
+    fn span_width(&self, area: Area) -> Abs {
+        self.rcols[area.x..area.x + area.colspan].iter().sum()
+    }
+
+    /// The index of the cell covering the track cell in column `x` and row
+    /// `y`.
     #[track_caller]
-    fn cell(&self, mut x: usize, y: usize) -> Option<&'a Content> {
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
         assert!(x < self.cols.len());
         assert!(y < self.rows.len());
+        self.map.get(x, y)
+    }
 
-        // Columns are reorder, but the cell slice is not.
-        if self.is_rtl {
-            x = self.cols.len() - 1 - x;
-        }
-
-        if self.has_gutter {
-            // Even columns and rows are children, odd ones are gutter.
-            if x % 2 == 0 && y % 2 == 0 {
-                let c = 1 + self.cols.len() / 2;
-                self.cells.get((y / 2) * c + x / 2)
-            } else {
-                None
-            }
-        } else {
-            let c = self.cols.len();
-            self.cells.get(y * c + x)
-        }
+    /// Get the cell that starts in column `x` and row `y` along with the
+    /// tracks it covers.
+    ///
+    /// Returns `None` if it's a gutter cell or covered by a cell starting
+    /// elsewhere.
+    #[track_caller]
+    fn cell(&self, x: usize, y: usize) -> Option<(&'a Cell, Area)> {
+        let i = self.index(x, y)?;
+        let area = self.areas[i];
+        (area.x == x && area.y == y).then(|| (&self.cells[i], area))
     }
 }
@@ -2,7 +2,7 @@ use crate::layout::{BlockElem, ParElem, Sizing, Spacing};
 use crate::prelude::*;
 use crate::text::TextElem;
 
-use super::GridLayouter;
+use super::{Cell, GridLayouter};
 
 /// A bullet list.
 ///
@@ -137,10 +137,10 @@ impl Layout for ListElem {
 
         let mut cells = vec![];
         for item in self.children() {
-            cells.push(Content::empty());
-            cells.push(marker.clone());
-            cells.push(Content::empty());
-            cells.push(item.body().styled(Self::set_depth(Depth)));
+            cells.push(Cell::new(Content::empty()));
+            cells.push(Cell::new(marker.clone()));
+            cells.push(Cell::new(Content::empty()));
+            cells.push(Cell::new(item.body().styled(Self::set_depth(Depth))));
         }
 
         let layouter = GridLayouter::new(
@@ -317,7 +317,8 @@ impl PageElem {
 
         // Layout the child.
         let area = size - margin.sum_by_axis();
-        let regions = Regions::repeat(area, area.map(Abs::is_finite));
+        let mut regions = Regions::repeat(area, area.map(Abs::is_finite));
+        regions.root = true;
         let mut fragment = child.layout(vt, styles, regions)?;
 
         let fill = self.fill(styles);
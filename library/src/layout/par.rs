@@ -99,6 +99,38 @@ pub struct ParElem {
     #[resolve]
     pub hanging_indent: Length,
 
+    /// The minimum number of lines of a paragraph that stay together at the
+    /// bottom of a page or column when the paragraph breaks.
+    ///
+    /// If fewer lines fit, the whole paragraph moves to the next page or
+    /// column. Blocks that are kept with the next one, like headings, move
+    /// along with it. The default value is `{2}`. Set it to `{1}` to allow a
+    /// single line to be left behind.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #set par(orphans: 3)
+    /// #v(30pt)
+    /// = Introduction
+    /// #lorem(12)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub orphans: NonZeroUsize,
+
+    /// The minimum number of lines of a paragraph that are carried over to
+    /// the top of the next page or column when the paragraph breaks.
+    ///
+    /// If fewer lines would be carried over, more lines move along with them.
+    /// The default value is `{2}`.
+    ///
+    /// ```example
+    /// #set page(height: 80pt)
+    /// #set par(widows: 3)
+    /// #lorem(26)
+    /// ```
+    #[default(NonZeroUsize::new(2).unwrap())]
+    pub widows: NonZeroUsize,
+
     /// The contents of the paragraph.
     #[external]
     pub body: Content,
@@ -110,11 +142,11 @@ pub struct ParElem {
 }
 
 impl Construct for ParElem {
-    fn construct(_: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+    fn construct(vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
         // The paragraph constructor is special: It doesn't create a paragraph
         // element. Instead, it just ensures that the passed content lives in a
         // separate paragraph and styles it.
-        let styles = Self::set(args)?;
+        let styles = Self::set(vm, args)?;
         let body = args.expect::<Content>("body")?;
         Ok(Content::sequence([
             ParbreakElem::new().pack(),
@@ -1209,17 +1241,23 @@ fn finalize(
         .map(|line| commit(vt, p, line, width, region.y))
         .collect::<SourceResult<_>>()?;
 
-    // Prevent orphans.
+    // Prevent orphans by keeping the first lines together.
     let leading = ParElem::leading_in(p.styles);
-    if frames.len() >= 2 && !frames[1].is_empty() {
+    for _ in 1..ParElem::orphans_in(p.styles).get() {
+        if frames.len() < 2 || frames[1].is_empty() {
+            break;
+        }
         let second = frames.remove(1);
         let first = &mut frames[0];
         merge(first, second, leading);
     }
 
-    // Prevent widows.
-    let len = frames.len();
-    if len >= 2 && !frames[len - 2].is_empty() {
+    // Prevent widows by keeping the last lines together.
+    for _ in 1..ParElem::widows_in(p.styles).get() {
+        let len = frames.len();
+        if len < 2 || frames[len - 2].is_empty() {
+            break;
+        }
         let second = frames.pop().unwrap();
         let first = frames.last_mut().unwrap();
         merge(first, second, leading);
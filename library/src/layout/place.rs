@@ -6,6 +6,9 @@ use crate::prelude::*;
 /// always relative to its parent container and will be in the foreground of all
 /// other content in the container. Page margins will be respected.
 ///
+/// Alternatively, placed content can [float]($func/place.float) to the top or
+/// bottom of the page. Then, it takes up space and other content flows around
+/// it.
 ///
 /// ## Example
 /// ```example
@@ -48,6 +51,39 @@ pub struct PlaceElem {
     /// The vertical displacement of the placed content.
     pub dy: Rel<Length>,
 
+    /// Whether the content floats to the top or bottom of the current or
+    /// next region, e.g. the page, instead of being placed absolutely.
+    ///
+    /// Floating content takes up space in the region and keeps the order in
+    /// which it appears in the document. Its vertical alignment must be
+    /// `{top}` or `{bottom}`. If no vertical alignment is given, the content
+    /// floats to whichever of the two is closer to its position in the flow.
+    ///
+    /// ```example
+    /// #set page(height: 150pt)
+    /// #let note(where, body) = place(
+    ///   center + where,
+    ///   float: true,
+    ///   clearance: 6pt,
+    ///   rect(body),
+    /// )
+    ///
+    /// #lorem(10)
+    /// #note(bottom)[Bottom 1]
+    /// #note(bottom)[Bottom 2]
+    /// #lorem(40)
+    /// #note(top)[Top]
+    /// #lorem(10)
+    /// ```
+    #[default(false)]
+    pub float: bool,
+
+    /// The spacing between floating content and the other content in the
+    /// region.
+    #[resolve]
+    #[default(Em::new(1.5).into())]
+    pub clearance: Length,
+
     /// The content to place.
     #[required]
     pub body: Content,
@@ -62,6 +98,16 @@ impl Layout for PlaceElem {
         regions: Regions,
     ) -> SourceResult<Fragment> {
         let out_of_flow = self.out_of_flow(styles);
+        let float = self.float(styles);
+        let alignment = self.alignment(styles);
+        if float
+            && !matches!(
+                alignment.y,
+                None | Some(GenAlign::Specific(Align::Top | Align::Bottom))
+            )
+        {
+            bail!(self.span(), "floating placement must be `top` or `bottom`");
+        }
 
         // The pod is the base area of the region because for absolute
         // placement we don't really care about the already used area.
@@ -71,17 +117,22 @@ impl Layout for PlaceElem {
             Regions::one(regions.base(), expand)
         };
 
+        // Floating content is only aligned horizontally here. The flow
+        // moves it to the top or bottom of the region.
         let child = self
             .body()
             .moved(Axes::new(self.dx(styles), self.dy(styles)))
-            .aligned(self.alignment(styles));
+            .aligned(if float { Axes::with_x(alignment.x) } else { alignment });
 
         let mut frame = child.layout(vt, styles, pod)?.into_frame();
 
         // If expansion is off, zero all sizes so that we don't take up any
         // space in our parent. Otherwise, respect the expand settings.
-        let target = regions.expand.select(regions.size, Size::zero());
-        frame.resize(target, Align::LEFT_TOP);
+        // Floating content keeps its size.
+        if !float {
+            let target = regions.expand.select(regions.size, Size::zero());
+            frame.resize(target, Align::LEFT_TOP);
+        }
 
         Ok(Fragment::frame(frame))
     }
@@ -92,7 +143,7 @@ impl PlaceElem {
     /// base origin. Instead of relative to the parent's current flow/cursor
     /// position.
     pub fn out_of_flow(&self, styles: StyleChain) -> bool {
-        self.alignment(styles).y.is_some()
+        self.alignment(styles).y.is_some() && !self.float(styles)
     }
 }
 
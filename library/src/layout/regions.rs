@@ -17,6 +17,12 @@ pub struct Regions<'a> {
     /// Whether elements should expand to fill the regions instead of shrinking
     /// to fit the content.
     pub expand: Axes<bool>,
+    /// Whether these are the root regions or direct descendants.
+    ///
+    /// True for the regions of a page's body and of columns directly in the
+    /// page, false otherwise. The flow uses this to decide whether it is
+    /// responsible for placing footnotes.
+    pub root: bool,
 }
 
 impl Regions<'_> {
@@ -28,6 +34,7 @@ impl Regions<'_> {
             backlog: &[],
             last: None,
             expand,
+            root: false,
         }
     }
 
@@ -39,6 +46,7 @@ impl Regions<'_> {
             backlog: &[],
             last: Some(size.y),
             expand,
+            root: false,
         }
     }
 
@@ -67,6 +75,7 @@ impl Regions<'_> {
             backlog,
             last: self.last.map(|y| f(Size::new(x, y)).y),
             expand: self.expand,
+            root: false,
         }
     }
 
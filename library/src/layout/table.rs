@@ -1,6 +1,12 @@
-use crate::layout::{AlignElem, GridLayouter, TrackSizings};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::layout::{
+    place_cells, AlignElem, Cell, GridLayouter, Section, Sections, TrackSizings,
+};
 use crate::meta::{Figurable, LocalName};
 use crate::prelude::*;
+use crate::text::TextElem;
 
 /// A table of items.
 ///
@@ -10,6 +16,15 @@ use crate::prelude::*;
 /// the [grid documentation]($func/grid) for more information on how to size the
 /// table tracks.
 ///
+/// To merge multiple cells, wrap a cell in [`table.cell`]($func/table.cell)
+/// and specify how many columns and rows it should span. Rows wrapped in
+/// [`table.header`]($func/table.header) or [`table.footer`]($func/table.footer)
+/// are repeated on every page the table spans.
+///
+/// Lines can be styled per cell through the [`stroke`]($func/table.stroke)
+/// and be added explicitly with [`table.hline`]($func/table.hline) and
+/// [`table.vline`]($func/table.vline).
+///
 /// To give a table a caption and make it [referenceable]($func/ref), put it
 /// into a [figure]($func/figure).
 ///
@@ -36,6 +51,14 @@ use crate::prelude::*;
 /// Display: Table
 /// Category: layout
 #[element(Layout, LocalName, Figurable)]
+#[scope(
+    scope.define("cell", TableCell::func());
+    scope.define("header", TableHeader::func());
+    scope.define("footer", TableFooter::func());
+    scope.define("hline", TableHLine::func());
+    scope.define("vline", TableVLine::func());
+    scope
+)]
 pub struct TableElem {
     /// Defines the column sizes. See the [grid documentation]($func/grid) for
     /// more information on track sizing.
@@ -104,11 +127,32 @@ pub struct TableElem {
     /// How to stroke the cells.
     ///
     /// This can be a color, a stroke width, both, or `{none}` to disable
-    /// the stroke.
-    #[resolve]
-    #[fold]
-    #[default(Some(PartialStroke::default()))]
-    pub stroke: Option<PartialStroke>,
+    /// the stroke. To stroke the sides of the cells differently, pass a
+    /// dictionary with the keys `left`, `top`, `right`, `bottom`, `x`, `y` or
+    /// `rest`. Sides that are not mentioned in the dictionary are not stroked.
+    ///
+    /// This can also be a function that returns one of the above. The
+    /// function is passed the cell's column and row index, starting at zero.
+    ///
+    /// Where the sides of two adjacent cells meet, a stroke set on a
+    /// [cell]($func/table.cell) takes precedence over the table's stroke and
+    /// a stroke takes precedence over no stroke. Otherwise, the stroke of the
+    /// cell below or to the right wins.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   stroke: (x, y) => (
+    ///     top: if y == 0 { 1pt } else if y == 1 { 0.5pt },
+    ///     bottom: if y == 2 { 1pt },
+    ///   ),
+    ///   [*Name*], [*Min*], [*Max*],
+    ///   [A], [1], [3],
+    ///   [B], [2], [4],
+    /// )
+    /// ```
+    #[default(Celled::Value(Sides::splat(Some(Some(PartialStroke::default())))))]
+    pub stroke: Celled<Sides<Option<Option<PartialStroke>>>>,
 
     /// How much to pad the cells's content.
     ///
@@ -117,6 +161,11 @@ pub struct TableElem {
     pub inset: Rel<Length>,
 
     /// The contents of the table cells.
+    ///
+    /// The cells are populated in row-major order. A cell that spans multiple
+    /// columns or rows is placed at the next position where it fits. The
+    /// children may also include [horizontal]($func/table.hline) and
+    /// [vertical]($func/table.vline) lines.
     #[variadic]
     pub children: Vec<Content>,
 }
@@ -134,78 +183,311 @@ impl Layout for TableElem {
 
         let tracks = Axes::new(self.columns(styles).0, self.rows(styles).0);
         let gutter = Axes::new(self.column_gutter(styles).0, self.row_gutter(styles).0);
-        let cols = tracks.x.len().max(1);
-        let cells: Vec<_> = self
-            .children()
-            .into_iter()
-            .enumerate()
-            .map(|(i, child)| {
-                let mut child = child.padded(Sides::splat(inset));
-
-                let x = i % cols;
-                let y = i / cols;
-                if let Smart::Custom(alignment) = align.resolve(vt, x, y)? {
-                    child = child.styled(AlignElem::set_alignment(alignment));
+
+        // Unpack the header and footer into their cells and collect the
+        // explicit lines along with the number of cells before them.
+        let mut children = vec![];
+        let mut sections = Sections::default();
+        let mut hlines = vec![];
+        let mut vlines = vec![];
+        let all = self.children();
+        for (i, child) in all.iter().enumerate() {
+            if let Some(header) = child.to::<TableHeader>() {
+                if !children.is_empty() {
+                    bail!(child.span(), "table header must be the first child");
                 }
+                let cells = header.children();
+                let repeat = header.repeat(styles);
+                sections.header = Some(Section { cells: cells.len(), repeat });
+                children.extend(cells);
+            } else if let Some(footer) = child.to::<TableFooter>() {
+                if all[i + 1..]
+                    .iter()
+                    .any(|child| !child.is::<TableHLine>() && !child.is::<TableVLine>())
+                {
+                    bail!(child.span(), "table footer must be the last child");
+                }
+                let cells = footer.children();
+                let repeat = footer.repeat(styles);
+                sections.footer = Some(Section { cells: cells.len(), repeat });
+                children.extend(cells);
+            } else if let Some(line) = child.to::<TableHLine>() {
+                hlines.push((line.clone(), children.len()));
+            } else if let Some(line) = child.to::<TableVLine>() {
+                vlines.push((line.clone(), children.len()));
+            } else {
+                children.push(child.clone());
+            }
+        }
 
-                Ok(child)
+        let mut cells: Vec<_> = children
+            .iter()
+            .map(|child| match child.to::<TableCell>() {
+                Some(cell) => Cell {
+                    body: cell.body(),
+                    colspan: cell.colspan(styles),
+                    rowspan: cell.rowspan(styles),
+                },
+                None => Cell::new(child.clone()),
             })
-            .collect::<SourceResult<_>>()?;
+            .collect();
+
+        // Resolve the cells' properties based on where they are placed.
+        let areas = place_cells(tracks.x.len(), &cells, sections);
+        let mut fills = vec![];
+        for ((cell, child), area) in cells.iter_mut().zip(&children).zip(&areas) {
+            let custom = child.to::<TableCell>();
+            let inset = custom
+                .and_then(|custom| custom.inset(styles).as_custom())
+                .unwrap_or(inset);
+
+            let mut body = cell.body.clone().padded(Sides::splat(inset));
+            let alignment = match custom.map(|custom| custom.align(styles)) {
+                Some(Smart::Custom(alignment)) => Smart::Custom(alignment),
+                _ => align.resolve(vt, area.x, area.y)?,
+            };
+
+            if let Smart::Custom(alignment) = alignment {
+                body = body.styled(AlignElem::set_alignment(alignment));
+            }
+
+            cell.body = body;
+            fills.push(custom.map_or(Smart::Auto, |custom| custom.fill(styles)));
+        }
 
         let fill = self.fill(styles);
-        let stroke = self.stroke(styles).map(PartialStroke::unwrap_or_default);
+        let stroke = self.stroke(styles);
 
         // Prepare grid layout by unifying content and gutter tracks.
-        let layouter = GridLayouter::new(
+        let layouter = GridLayouter::with_sections(
             vt,
             tracks.as_deref(),
             gutter.as_deref(),
             &cells,
+            sections,
             regions,
             styles,
         );
 
         // Measure the columns and layout the grid row-by-row.
         let mut layout = layouter.layout()?;
+        let cols = layout.cols.len();
+
+        // Resolve the strokes of the cells' sides. A stroke set on a cell is
+        // combined with the table's stroke for the same side.
+        let mut strokes = vec![];
+        for (child, area) in children.iter().zip(&layout.areas) {
+            let outer = resolve_stroke(vt, &stroke, area.x, area.y, styles)?;
+            let inner = match child.to::<TableCell>() {
+                Some(cell) => cell.stroke(styles),
+                None => Sides::splat(None),
+            };
+            strokes.push(inner.zip(outer).map(|(inner, outer)| match inner {
+                Some(inner) => {
+                    let inner = inner.map(|inner| {
+                        inner.fold(outer.unwrap_or_default()).unwrap_or_default()
+                    });
+                    (inner, true)
+                }
+                None => (outer.map(PartialStroke::unwrap_or_default), false),
+            }));
+        }
+
+        // Resolve the strokes of gutter tracks and empty positions.
+        let mut empty = HashMap::new();
+        for row in layout.rows.iter().flatten() {
+            for x in 0..cols {
+                if layout.map.get(x, row.y).is_none() && !empty.contains_key(&(x, row.y))
+                {
+                    let sides = resolve_stroke(vt, &stroke, x, row.y, styles)?;
+                    let sides = sides
+                        .map(|side| (side.map(PartialStroke::unwrap_or_default), false));
+                    empty.insert((x, row.y), sides);
+                }
+            }
+        }
+
+        // Translate the explicit lines into track coordinates. With gutter,
+        // a line between two rows or columns is placed before the gutter.
+        let has_gutter = gutter.any(|tracks| !tracks.is_empty());
+        let is_rtl = TextElem::dir_in(styles) == Dir::RTL;
+        let boundary = |i: usize| if has_gutter && i > 0 { 2 * i - 1 } else { i };
+        let span = |start: usize, end: Option<usize>| {
+            let start = if has_gutter { 2 * start } else { start };
+            start..end.map_or(usize::MAX, boundary)
+        };
+
+        let hlines: Vec<_> = hlines
+            .into_iter()
+            .map(|(line, before)| {
+                let y = line.y(styles).unwrap_or_else(|| {
+                    before.checked_sub(1).map_or(0, |i| areas[i].y + 1)
+                });
+                let Range { start, end } = span(line.start(styles), line.end(styles));
+                let end = end.min(cols);
+                let start = start.min(end);
+                Line {
+                    at: boundary(y),
+                    span: if is_rtl { cols - end..cols - start } else { start..end },
+                    stroke: line.stroke(styles).map(PartialStroke::unwrap_or_default),
+                }
+            })
+            .collect();
+
+        let vlines: Vec<_> = vlines
+            .into_iter()
+            .map(|(line, before)| {
+                let x = line.x(styles).unwrap_or_else(|| {
+                    before.checked_sub(1).map_or(0, |i| areas[i].x + areas[i].colspan)
+                });
+                let at = boundary(x).min(cols);
+                Line {
+                    at: if is_rtl { cols - at } else { at },
+                    span: span(line.start(styles), line.end(styles)),
+                    stroke: line.stroke(styles).map(PartialStroke::unwrap_or_default),
+                }
+            })
+            .collect();
+
+        // The stroke of one side of the track cell in column `x` and row
+        // `y`, along with whether it was set on a cell.
+        let side = |x: usize, y: usize, side: Side| match layout.map.get(x, y) {
+            Some(i) => strokes[i].get_ref(side).clone(),
+            None => empty[&(x, y)].get_ref(side).clone(),
+        };
 
         // Add lines and backgrounds.
         for (frame, rows) in layout.fragment.iter_mut().zip(&layout.rows) {
-            // Render table lines.
-            if let Some(stroke) = &stroke {
-                let thickness = stroke.thickness;
-                let half = thickness / 2.0;
-
-                // Render horizontal lines.
-                for offset in points(rows.iter().map(|piece| piece.height)) {
-                    let target = Point::with_x(frame.width() + thickness);
-                    let hline = Geometry::Line(target).stroked(stroke.clone());
+            if rows.is_empty() {
+                continue;
+            }
+
+            // Render table lines. Lines between tracks that are covered by the
+            // same cell are left out and explicit lines take precedence over
+            // the cells' strokes.
+            let merged = |(x1, y1), (x2, y2)| {
+                let cell = layout.map.get(x1, y1);
+                cell.is_some() && cell == layout.map.get(x2, y2)
+            };
+
+            // Render horizontal lines.
+            for (i, offset) in points(rows.iter().map(|piece| piece.height)).enumerate() {
+                let above = i.checked_sub(1).map(|k| rows[k].y);
+                let below = rows.get(i).map(|row| row.y);
+                let line = |x| {
+                    if let (Some(y1), Some(y2)) = (above, below) {
+                        if merged((x, y1), (x, y2)) {
+                            return None;
+                        }
+                    }
+
+                    let explicit = hlines.iter().rev().find(|line| {
+                        line.span.contains(&x)
+                            && (below == Some(line.at)
+                                || above.map(|y| y + 1) == Some(line.at))
+                    });
+
+                    match explicit {
+                        Some(line) => line.stroke.clone(),
+                        None => pick(
+                            above.map(|y| side(x, y, Side::Bottom)),
+                            below.map(|y| side(x, y, Side::Top)),
+                        ),
+                    }
+                };
+
+                for (start, length, stroke) in segments(layout.cols.iter().copied(), line)
+                {
+                    let half = stroke.thickness / 2.0;
+                    let target = Point::with_x(length + stroke.thickness);
+                    let hline = Geometry::Line(target).stroked(stroke);
                     frame.prepend(
-                        Point::new(-half, offset),
+                        Point::new(start - half, offset),
                         FrameItem::Shape(hline, self.span()),
                     );
                 }
+            }
+
+            // Render vertical lines.
+            for (x, offset) in points(layout.cols.iter().copied()).enumerate() {
+                let left = x.checked_sub(1);
+                let right = (x < cols).then_some(x);
+                let line = |j: usize| {
+                    let y = rows[j].y;
+                    if let (Some(x1), Some(x2)) = (left, right) {
+                        if merged((x1, y), (x2, y)) {
+                            return None;
+                        }
+                    }
 
-                // Render vertical lines.
-                for offset in points(layout.cols.iter().copied()) {
-                    let target = Point::with_y(frame.height() + thickness);
-                    let vline = Geometry::Line(target).stroked(stroke.clone());
+                    let explicit = vlines
+                        .iter()
+                        .rev()
+                        .find(|line| line.at == x && line.span.contains(&y));
+
+                    match explicit {
+                        Some(line) => line.stroke.clone(),
+                        None => pick(
+                            left.map(|x| side(x, y, Side::Right)),
+                            right.map(|x| side(x, y, Side::Left)),
+                        ),
+                    }
+                };
+
+                for (start, length, stroke) in
+                    segments(rows.iter().map(|row| row.height), line)
+                {
+                    let half = stroke.thickness / 2.0;
+                    let target = Point::with_y(length + stroke.thickness);
+                    let vline = Geometry::Line(target).stroked(stroke);
                     frame.prepend(
-                        Point::new(offset, -half),
+                        Point::new(offset, start - half),
                         FrameItem::Shape(vline, self.span()),
                     );
                 }
             }
 
-            // Render cell backgrounds.
+            // Render cell backgrounds. Cells get one background across all
+            // the tracks they cover.
+            for (i, area) in layout.areas.iter().enumerate() {
+                let Some((dy, height, ())) =
+                    segments(rows.iter().map(|row| row.height), |j| {
+                        (area.y..area.y + area.rowspan).contains(&rows[j].y).then_some(())
+                    })
+                    .into_iter()
+                    .next()
+                else {
+                    continue;
+                };
+
+                let fill = match fills[i].clone() {
+                    Smart::Custom(fill) => fill,
+                    Smart::Auto => fill.resolve(vt, area.x, area.y)?,
+                };
+
+                if let Some(fill) = fill {
+                    let dx: Abs = layout.cols[..area.x].iter().sum();
+                    let width: Abs =
+                        layout.cols[area.x..area.x + area.colspan].iter().sum();
+                    let pos = Point::new(dx, dy);
+                    let size = Size::new(width, height);
+                    let rect = Geometry::Rect(size).filled(fill);
+                    frame.prepend(pos, FrameItem::Shape(rect, self.span()));
+                }
+            }
+
+            // Render backgrounds of gutter tracks and empty positions.
             let mut dx = Abs::zero();
             for (x, &col) in layout.cols.iter().enumerate() {
                 let mut dy = Abs::zero();
                 for row in rows {
-                    if let Some(fill) = fill.resolve(vt, x, row.y)? {
-                        let pos = Point::new(dx, dy);
-                        let size = Size::new(col, row.height);
-                        let rect = Geometry::Rect(size).filled(fill);
-                        frame.prepend(pos, FrameItem::Shape(rect, self.span()));
+                    if layout.map.get(x, row.y).is_none() {
+                        if let Some(fill) = fill.resolve(vt, x, row.y)? {
+                            let pos = Point::new(dx, dy);
+                            let size = Size::new(col, row.height);
+                            let rect = Geometry::Rect(size).filled(fill);
+                            frame.prepend(pos, FrameItem::Shape(rect, self.span()));
+                        }
                     }
                     dy += row.height;
                 }
@@ -217,6 +499,218 @@ impl Layout for TableElem {
     }
 }
 
+/// A cell in a table.
+///
+/// Wrapping a table's child in a cell lets it span multiple columns or rows
+/// and override the table's fill, alignment and inset for this cell. Lines
+/// between the tracks covered by a merged cell are not drawn.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   table.cell(colspan: 3, align: center)[*Results*],
+///   table.cell(rowspan: 2)[Runs], [Fast], [Slow],
+///   [12], [7],
+/// )
+/// ```
+///
+/// Display: Table Cell
+/// Category: layout
+#[element(Show)]
+pub struct TableCell {
+    /// The cell's content.
+    #[required]
+    pub body: Content,
+
+    /// How many columns the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub colspan: NonZeroUsize,
+
+    /// How many rows the cell spans.
+    #[default(NonZeroUsize::ONE)]
+    pub rowspan: NonZeroUsize,
+
+    /// How to fill the cell. If set to `{auto}`, the table's fill is used.
+    pub fill: Smart<Option<Paint>>,
+
+    /// How to align the cell's content. If set to `{auto}`, the table's
+    /// alignment is used.
+    pub align: Smart<Axes<Option<GenAlign>>>,
+
+    /// How much to pad the cell's content. If set to `{auto}`, the table's
+    /// inset is used.
+    pub inset: Smart<Rel<Length>>,
+
+    /// How to stroke the cell's sides.
+    ///
+    /// Takes the same values as the table's [`stroke`]($func/table.stroke),
+    /// except for functions. Sides that are not specified use the table's
+    /// stroke and specified ones are combined with it, so that `{red}` only
+    /// changes the color of the lines.
+    ///
+    /// ```example
+    /// #table(
+    ///   columns: 3,
+    ///   [A], table.cell(stroke: (bottom: 2pt + red))[B], [C],
+    ///   [D], [E], [F],
+    /// )
+    /// ```
+    #[resolve]
+    pub stroke: Sides<Option<Option<PartialStroke>>>,
+}
+
+impl Show for TableCell {
+    #[tracing::instrument(name = "TableCell::show", skip_all)]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(self.body())
+    }
+}
+
+/// A header at the top of a table.
+///
+/// The header's cells form the first rows of the table. By default, they are
+/// repeated at the top of every page the table spans. A header is never left
+/// alone at the bottom of a page: If no other row fits below it, it moves to
+/// the next page together with the table.
+///
+/// ## Example
+/// ```example
+/// #set page(height: 100pt)
+/// #table(
+///   columns: 2,
+///   table.header[*Name*][*Value*],
+///   ..range(8).map(n => ([Item #n], [#(n * n)])).flatten(),
+/// )
+/// ```
+///
+/// Display: Table Header
+/// Category: layout
+#[element(Show)]
+pub struct TableHeader {
+    /// Whether to repeat the header at the top of every page the table spans.
+    #[default(true)]
+    pub repeat: bool,
+
+    /// The cells of the header.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableHeader {
+    #[tracing::instrument(name = "TableHeader::show", skip_all)]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// A footer at the bottom of a table.
+///
+/// The footer's cells form the last rows of the table. By default, they are
+/// repeated at the bottom of every page the table spans.
+///
+/// Display: Table Footer
+/// Category: layout
+#[element(Show)]
+pub struct TableFooter {
+    /// Whether to repeat the footer at the bottom of every page the table
+    /// spans.
+    #[default(true)]
+    pub repeat: bool,
+
+    /// The cells of the footer.
+    #[variadic]
+    pub children: Vec<Content>,
+}
+
+impl Show for TableFooter {
+    #[tracing::instrument(name = "TableFooter::show", skip_all)]
+    fn show(&self, _: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        Ok(Content::sequence(self.children()))
+    }
+}
+
+/// A horizontal line in a table.
+///
+/// The line takes precedence over the strokes of the cells it runs along,
+/// but it is not drawn through merged cells. Setting its stroke to `{none}`
+/// removes the lines in its place.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   stroke: none,
+///   table.hline(stroke: 1pt),
+///   [*Name*], [*Min*], [*Max*],
+///   table.hline(stroke: 0.5pt),
+///   [A], [1], [3],
+///   [B], [2], [4],
+///   table.hline(stroke: 1pt),
+/// )
+/// ```
+///
+/// Display: Table Horizontal Line
+/// Category: layout
+#[element]
+pub struct TableHLine {
+    /// The row above which the line is placed, starting at zero. If set to
+    /// `{auto}`, the line is placed below the row of the preceding cell.
+    pub y: Smart<usize>,
+
+    /// The column at which the line starts.
+    pub start: usize,
+
+    /// The column before which the line ends. If set to `{none}`, the line
+    /// extends to the end of the table.
+    pub end: Option<usize>,
+
+    /// How to stroke the line.
+    #[resolve]
+    #[fold]
+    #[default(Some(PartialStroke::default()))]
+    pub stroke: Option<PartialStroke>,
+}
+
+/// A vertical line in a table.
+///
+/// The line takes precedence over the strokes of the cells it runs along,
+/// but it is not drawn through merged cells. Setting its stroke to `{none}`
+/// removes the lines in its place.
+///
+/// ## Example
+/// ```example
+/// #table(
+///   columns: 3,
+///   stroke: (y: 1pt),
+///   [A], table.vline(), [B], [C],
+///   [D], [E], [F],
+/// )
+/// ```
+///
+/// Display: Table Vertical Line
+/// Category: layout
+#[element]
+pub struct TableVLine {
+    /// The column before which the line is placed, starting at zero. If set
+    /// to `{auto}`, the line is placed after the column of the preceding
+    /// cell.
+    pub x: Smart<usize>,
+
+    /// The row at which the line starts.
+    pub start: usize,
+
+    /// The row before which the line ends. If set to `{none}`, the line
+    /// extends to the end of the table.
+    pub end: Option<usize>,
+
+    /// How to stroke the line.
+    #[resolve]
+    #[fold]
+    #[default(Some(PartialStroke::default()))]
+    pub stroke: Option<PartialStroke>,
+}
+
 /// Turn an iterator extents into an iterator of offsets before, in between, and
 /// after the extents, e.g. [10mm, 5mm] -> [0mm, 10mm, 15mm].
 fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
@@ -229,6 +723,70 @@ fn points(extents: impl IntoIterator<Item = Abs>) -> impl Iterator<Item = Abs> {
         })
 }
 
+/// Split a line along the given track extents into segments of tracks with
+/// equal values, leaving out the tracks for which `value` returns `None`.
+/// Returns the offset, length and value of each segment.
+fn segments<T: PartialEq>(
+    extents: impl IntoIterator<Item = Abs>,
+    mut value: impl FnMut(usize) -> Option<T>,
+) -> Vec<(Abs, Abs, T)> {
+    let mut segments = vec![];
+    let mut current: Option<(Abs, Abs, T)> = None;
+    let mut offset = Abs::zero();
+    for (i, extent) in extents.into_iter().enumerate() {
+        match (value(i), current.take()) {
+            (Some(value), Some((start, length, last))) if value == last => {
+                current = Some((start, length + extent, last));
+            }
+            (value, last) => {
+                segments.extend(last);
+                current = value.map(|value| (offset, extent, value));
+            }
+        }
+        offset += extent;
+    }
+    segments.extend(current);
+    segments
+}
+
+/// An explicit table line in track coordinates.
+struct Line {
+    /// The track boundary at which the line is placed.
+    at: usize,
+    /// The tracks along which the line extends.
+    span: Range<usize>,
+    /// The line's stroke.
+    stroke: Option<Stroke>,
+}
+
+/// Resolve the table's stroke for the track cell at the given position.
+fn resolve_stroke(
+    vt: &mut Vt,
+    stroke: &Celled<Sides<Option<Option<PartialStroke>>>>,
+    x: usize,
+    y: usize,
+    styles: StyleChain,
+) -> SourceResult<Sides<Option<PartialStroke<Abs>>>> {
+    Ok(stroke.resolve(vt, x, y)?.resolve(styles).map(Option::flatten))
+}
+
+/// Choose between the strokes of two sides that meet, each with whether it
+/// was set on a cell.
+///
+/// A stroke set on a cell beats the table's stroke and a stroke beats no
+/// stroke. Otherwise, the second side, i.e. the one of the cell below or to
+/// the right, wins.
+fn pick(
+    first: Option<(Option<Stroke>, bool)>,
+    second: Option<(Option<Stroke>, bool)>,
+) -> Option<Stroke> {
+    first
+        .into_iter()
+        .chain(second)
+        .max_by_key(|(stroke, custom)| (*custom, stroke.is_some()))
+        .and_then(|(stroke, _)| stroke)
+}
+
 /// A value that can be configured per cell.
 #[derive(Debug, Clone, PartialEq, Hash)]
 pub enum Celled<T> {
@@ -14,7 +14,7 @@ pub mod text;
 pub mod visualize;
 
 use typst::diag::At;
-use typst::eval::{LangItems, Library, Module, Scope};
+use typst::eval::{LangItems, Library, Module, Scope, Type};
 use typst::geom::{Align, Color, Dir, GenAlign, Smart};
 use typst::model::{Element, Styles};
 
@@ -89,6 +89,7 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("circle", visualize::CircleElem::func());
     global.define("polygon", visualize::PolygonElem::func());
     global.define("path", visualize::PathElem::func());
+    global.define("gradient", visualize::gradient::module());
 
     // Meta.
     global.define("document", meta::DocumentElem::func());
@@ -97,6 +98,7 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("outline", meta::OutlineElem::func());
     global.define("heading", meta::HeadingElem::func());
     global.define("figure", meta::FigureElem::func());
+    global.define("footnote", meta::FootnoteElem::func());
     global.define("cite", meta::CiteElem::func());
     global.define("bibliography", meta::BibliographyElem::func());
     global.define("locate", meta::locate);
@@ -118,6 +120,8 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("panic", compute::panic);
     global.define("assert", compute::assert);
     global.define("eval", compute::eval);
+    global.define("plugin", compute::plugin);
+    global.define("element", compute::element);
     global.define("int", compute::int);
     global.define("float", compute::float);
     global.define("luma", compute::luma);
@@ -125,9 +129,13 @@ fn global(math: Module, calc: Module) -> Module {
     global.define("cmyk", compute::cmyk);
     global.define("symbol", compute::symbol);
     global.define("str", compute::str);
+    global.define("bytes", compute::bytes);
+    global.define("array", compute::array);
     global.define("label", compute::label);
     global.define("regex", compute::regex);
     global.define("range", compute::range);
+    global.define("datetime", compute::datetime);
+    global.define("duration", compute::duration);
     global.define("read", compute::read);
     global.define("csv", compute::csv);
     global.define("json", compute::json);
@@ -254,6 +262,13 @@ fn items() -> LangItems {
             math::AccentElem::new(base, math::Accent::new(accent)).pack()
         },
         math_frac: |num, denom| math::FracElem::new(num, denom).pack(),
+        library_types: &[
+            text::Decoration::TYPE_NAME,
+            meta::Counter::TYPE_NAME,
+            meta::CounterUpdate::TYPE_NAME,
+            meta::State::TYPE_NAME,
+            meta::StateUpdate::TYPE_NAME,
+        ],
         library_method: |vm, dynamic, method, args, span| {
             if let Some(counter) = dynamic.downcast::<meta::Counter>().cloned() {
                 counter.call_method(vm, method, args, span)
@@ -1,3 +1,5 @@
+use typst::util::hash128;
+
 use crate::prelude::*;
 
 /// Provides access to the location of content.
@@ -79,7 +81,9 @@ impl Show for LocateElem {
         }
 
         let location = self.0.location().unwrap();
-        Ok(self.func().call_vt(vt, [location.into()])?.display())
+        let output = self.func().call_vt(vt, [location.into()])?.display();
+        vt.tracer.introspect(location, self.span(), hash128(&output));
+        Ok(output)
     }
 }
 
@@ -4,8 +4,9 @@ use std::str::FromStr;
 use ecow::{eco_vec, EcoVec};
 use smallvec::{smallvec, SmallVec};
 use typst::eval::Tracer;
+use typst::util::hash128;
 
-use super::{FigureElem, HeadingElem, Numbering, NumberingPattern};
+use super::{FigureElem, FootnoteElem, HeadingElem, Numbering, NumberingPattern};
 use crate::layout::PageElem;
 use crate::math::EquationElem;
 use crate::prelude::*;
@@ -630,16 +631,18 @@ impl Show for DisplayElem {
         let numbering = self
             .numbering()
             .or_else(|| {
-                let CounterKey::Selector(Selector::Elem(func, _)) = counter.0 else {
-                return None;
-            };
+                let CounterKey::Selector(Selector::Elem(func, _)) = &counter.0 else {
+                    return None;
+                };
 
-                if func == HeadingElem::func() {
+                if *func == HeadingElem::func() {
                     HeadingElem::numbering_in(styles)
-                } else if func == FigureElem::func() {
+                } else if *func == FigureElem::func() {
                     FigureElem::numbering_in(styles)
-                } else if func == EquationElem::func() {
+                } else if *func == EquationElem::func() {
                     EquationElem::numbering_in(styles)
+                } else if *func == FootnoteElem::func() {
+                    Some(FootnoteElem::numbering_in(styles))
                 } else {
                     None
                 }
@@ -651,7 +654,10 @@ impl Show for DisplayElem {
         } else {
             counter.at(vt, location)?
         };
-        state.display(vt, &numbering)
+
+        let output = state.display(vt, &numbering)?;
+        vt.tracer.introspect(location, self.span(), hash128(&output));
+        Ok(output)
     }
 }
 
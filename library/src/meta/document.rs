@@ -1,3 +1,5 @@
+use typst::eval::Datetime;
+
 use crate::layout::{LayoutRoot, PageElem};
 use crate::prelude::*;
 
@@ -29,6 +31,19 @@ pub struct DocumentElem {
     /// The document's authors.
     pub author: Author,
 
+    /// The document's creation date.
+    ///
+    /// If this is `{auto}` (default), Typst uses the current date when
+    /// compiling. Setting it to `{none}` omits the date from the PDF.
+    ///
+    /// The year component must be at least zero in order to be embedded into
+    /// a PDF.
+    ///
+    /// ```example
+    /// #set document(date: datetime(year: 2023, month: 5, day: 1))
+    /// ```
+    pub date: Smart<Option<Datetime>>,
+
     /// The page runs.
     #[internal]
     #[variadic]
@@ -70,6 +85,7 @@ impl LayoutRoot for DocumentElem {
             pages,
             title: self.title(styles),
             author: self.author(styles).0,
+            date: self.date(styles).unwrap_or_else(|| vt.world.today(Some(0))),
         })
     }
 }
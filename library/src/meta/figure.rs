@@ -3,7 +3,7 @@ use std::str::FromStr;
 use super::{
     Count, Counter, CounterKey, CounterUpdate, LocalName, Numbering, NumberingPattern,
 };
-use crate::layout::{BlockElem, VElem};
+use crate::layout::{BlockElem, PlaceElem, VElem};
 use crate::meta::{Refable, Supplement};
 use crate::prelude::*;
 use crate::text::TextElem;
@@ -78,6 +78,33 @@ pub struct FigureElem {
     #[required]
     pub body: Content,
 
+    /// The figure's placement on the page.
+    ///
+    /// - `{none}`: The figure stays in the flow exactly where it was
+    ///   specified, like other content.
+    /// - `{auto}`: The figure floats to the top or bottom of the page,
+    ///   whichever is closer.
+    /// - `{top}`: The figure floats to the top of the page.
+    /// - `{bottom}`: The figure floats to the bottom of the page.
+    ///
+    /// Floating figures keep their order. If a figure doesn't fit onto the
+    /// current page, it moves to the next one. The spacing between a floating
+    /// figure and the other content is controlled by the
+    /// [`clearance`]($func/place.clearance) of the `place` function.
+    ///
+    /// ```example
+    /// #set page(height: 200pt)
+    ///
+    /// = Introduction
+    /// #figure(
+    ///   placement: bottom,
+    ///   caption: [A glacier],
+    ///   image("glacier.jpg", width: 60%),
+    /// )
+    /// #lorem(60)
+    /// ```
+    pub placement: Option<Smart<GenAlign>>,
+
     /// The figure's caption.
     pub caption: Option<Content>,
 
@@ -180,7 +207,7 @@ impl Synthesize for FigureElem {
         };
 
         let content = match &kind {
-            FigureKind::Elem(func) => self.find_of_elem(*func),
+            FigureKind::Elem(func) => self.find_of_elem(func.clone()),
             FigureKind::Name(_) => None,
         }
         .unwrap_or_else(|| self.body());
@@ -192,12 +219,13 @@ impl Synthesize for FigureElem {
         let supplement = match self.supplement(styles) {
             Smart::Auto => match &kind {
                 FigureKind::Elem(func) => {
-                    let elem = Content::new(*func).with::<dyn LocalName>().map(|c| {
-                        TextElem::packed(c.local_name(
-                            TextElem::lang_in(styles),
-                            TextElem::region_in(styles),
-                        ))
-                    });
+                    let elem =
+                        Content::new(func.clone()).with::<dyn LocalName>().map(|c| {
+                            TextElem::packed(c.local_name(
+                                TextElem::lang_in(styles),
+                                TextElem::region_in(styles),
+                            ))
+                        });
 
                     if numbering.is_some() {
                         Some(elem
@@ -226,6 +254,7 @@ impl Synthesize for FigureElem {
             }),
         )));
 
+        self.push_placement(self.placement(styles));
         self.push_caption(self.caption(styles));
         self.push_kind(Smart::Custom(kind));
         self.push_supplement(Smart::Custom(Supplement::Content(
@@ -252,11 +281,22 @@ impl Show for FigureElem {
         }
 
         // We wrap the contents in a block.
-        Ok(BlockElem::new()
+        realized = BlockElem::new()
             .with_body(Some(realized))
             .with_breakable(false)
             .pack()
-            .aligned(Axes::with_x(Some(Align::Center.into()))))
+            .aligned(Axes::with_x(Some(Align::Center.into())));
+
+        // Floating figures are placed by the flow.
+        if let Some(placement) = self.placement(styles) {
+            realized = PlaceElem::new(realized)
+                .with_alignment(Axes::new(None, placement.as_custom()))
+                .with_float(true)
+                .pack()
+                .spanned(self.span());
+        }
+
+        Ok(realized)
     }
 }
 
@@ -0,0 +1,194 @@
+use std::str::FromStr;
+
+use super::{Counter, Numbering, NumberingPattern};
+use crate::layout::{HElem, ParElem};
+use crate::prelude::*;
+use crate::text::{SuperElem, TextElem, TextSize};
+use crate::visualize::LineElem;
+
+/// A footnote.
+///
+/// Includes additional remarks and references on the same page with footnotes.
+/// A footnote will insert a superscript number that links to the note at the
+/// bottom of the page. Notes are numbered sequentially throughout your document
+/// and can break across multiple pages.
+///
+/// ## Example
+/// ```example
+/// Check the docs for more details.
+/// #footnote[https://typst.app/docs]
+/// ```
+///
+/// The footnote automatically attaches itself to the preceding word, even if
+/// there is a space before it in the markup. To force a space, you can use the
+/// string `[#" "]` or explicit [horizontal spacing]($func/h).
+///
+/// _Note:_ Set rules for the footnote's `separator`, `clearance`, `gap` and
+/// `indent` are applied at the level of the page. To configure them, place
+/// the set rule at the top of your document instead of close to the footnote.
+///
+/// Display: Footnote
+/// Category: meta
+#[element(Locatable, Synthesize, Show)]
+pub struct FootnoteElem {
+    /// How to number footnotes.
+    ///
+    /// By default, the footnote numbering continues throughout your document.
+    /// If you prefer per-page footnote numbering, you can reset the footnote
+    /// [counter]($func/counter) in the page [header]($func/page.header). In
+    /// the future, there might be a simpler way to achieve this.
+    ///
+    /// ```example
+    /// #set footnote(numbering: "*")
+    ///
+    /// Footnotes:
+    /// #footnote[Star],
+    /// #footnote[Dagger]
+    /// ```
+    #[default(Numbering::Pattern(NumberingPattern::from_str("1").unwrap()))]
+    pub numbering: Numbering,
+
+    /// The separator between the document body and the footnote listing.
+    ///
+    /// ```example
+    /// #set footnote(separator: repeat[.])
+    ///
+    /// Testing a different separator.
+    /// #footnote[
+    ///   Unconventional, but maybe
+    ///   not that bad?
+    /// ]
+    /// ```
+    #[default(
+        LineElem::new()
+            .with_length(Ratio::new(0.3).into())
+            .with_stroke(PartialStroke {
+                thickness: Smart::Custom(Abs::pt(0.5).into()),
+                ..Default::default()
+            })
+            .pack()
+    )]
+    pub separator: Content,
+
+    /// The amount of clearance between the document body and the separator.
+    ///
+    /// ```example
+    /// #set footnote(clearance: 3em)
+    ///
+    /// Footnotes also need ...
+    /// #footnote[
+    ///   ... some space to breathe.
+    /// ]
+    /// ```
+    #[default(Em::new(1.0).into())]
+    #[resolve]
+    pub clearance: Length,
+
+    /// The gap between footnote entries.
+    ///
+    /// ```example
+    /// #set footnote(gap: 0.8em)
+    ///
+    /// Footnotes:
+    /// #footnote[Spaced],
+    /// #footnote[Apart]
+    /// ```
+    #[default(Em::new(0.5).into())]
+    #[resolve]
+    pub gap: Length,
+
+    /// The indent of each footnote entry.
+    ///
+    /// ```example
+    /// #set footnote(indent: 0em)
+    ///
+    /// Footnotes:
+    /// #footnote[No],
+    /// #footnote[Indent]
+    /// ```
+    #[default(Em::new(1.0).into())]
+    pub indent: Length,
+
+    /// The content to put into the footnote.
+    #[required]
+    pub body: Content,
+}
+
+impl Synthesize for FootnoteElem {
+    fn synthesize(&mut self, _: &mut Vt, styles: StyleChain) -> SourceResult<()> {
+        self.push_numbering(self.numbering(styles));
+        self.push_indent(self.indent(styles));
+        Ok(())
+    }
+}
+
+impl Show for FootnoteElem {
+    #[tracing::instrument(name = "FootnoteElem::show", skip_all)]
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let loc = self.0.location().unwrap();
+        let marker = self.marker(vt)?;
+        let hole = HElem::new(Abs::zero().into()).with_weak(true).pack();
+        Ok(hole + marker.linked(Destination::Location(loc.variant(1))))
+    }
+}
+
+impl FootnoteElem {
+    /// The superscript number of the footnote, as shown in the text and in
+    /// front of its entry.
+    fn marker(&self, vt: &mut Vt) -> SourceResult<Content> {
+        let loc = self.0.location().unwrap();
+        let numbering = self.numbering(StyleChain::default());
+        let counter = Counter::of(Self::func());
+        let num = counter.at(vt, loc)?.display(vt, &numbering)?;
+        Ok(SuperElem::new(num).pack())
+    }
+}
+
+/// An entry in the footnote listing at the bottom of a page.
+///
+/// Entries are created by the flow layout for each footnote it encounters
+/// and are not meant to be constructed manually.
+///
+/// Display: Footnote Entry
+/// Category: meta
+#[element(Show, Finalize)]
+pub struct FootnoteEntry {
+    /// The footnote for this entry. Its location must be available.
+    #[required]
+    pub note: FootnoteElem,
+}
+
+impl Show for FootnoteEntry {
+    #[tracing::instrument(name = "FootnoteEntry::show", skip_all)]
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let note = self.note();
+        let number_gap = Em::new(0.05);
+        let loc = note.0.location().unwrap();
+        let marker = note
+            .marker(vt)?
+            .linked(Destination::Location(loc))
+            .backlinked(loc.variant(1));
+        Ok(Content::sequence([
+            HElem::new(note.indent(StyleChain::default()).into()).pack(),
+            marker,
+            HElem::new(number_gap.into()).with_weak(true).pack(),
+            note.body(),
+        ]))
+    }
+}
+
+impl Finalize for FootnoteEntry {
+    fn finalize(&self, realized: Content, _: StyleChain) -> Content {
+        let text_size = Em::new(0.85);
+        let leading = Em::new(0.5);
+        let mut styles = Styles::new();
+        styles.set(ParElem::set_leading(leading.into()));
+        styles.set(TextElem::set_size(TextSize(text_size.into())));
+        realized.styled_with_map(styles)
+    }
+}
+
+cast_from_value! {
+    FootnoteElem,
+    v: Content => v.to::<Self>().ok_or("expected footnote")?.clone(),
+}
@@ -2,6 +2,7 @@ use std::fmt::{self, Debug, Formatter, Write};
 
 use ecow::{eco_vec, EcoVec};
 use typst::eval::Tracer;
+use typst::util::hash128;
 
 use crate::prelude::*;
 
@@ -406,10 +407,13 @@ impl Show for DisplayElem {
 
         let location = self.0.location().unwrap();
         let value = self.state().at(vt, location)?;
-        Ok(match self.func() {
+        let output = match self.func() {
             Some(func) => func.call_vt(vt, [value])?.display(),
             None => value.display(),
-        })
+        };
+
+        vt.tracer.introspect(location, self.span(), hash128(&output));
+        Ok(output)
     }
 }
 
@@ -59,6 +59,15 @@ pub struct TextElem {
     /// هذا عربي.
     ///
     /// ```
+    ///
+    /// If a family is not available at all, Typst emits a warning.
+    #[parse(
+        let font_list: Option<Spanned<FontList>> = args.named("font")?;
+        if let Some(font_list) = &font_list {
+            check_font_list(vm, font_list);
+        }
+        font_list.map(|font_list| font_list.v)
+    )]
     #[default(FontList(vec![FontFamily::new("Linux Libertine")]))]
     pub font: FontList,
 
@@ -487,11 +496,11 @@ impl TextElem {
 }
 
 impl Construct for TextElem {
-    fn construct(_: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+    fn construct(vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
         // The text constructor is special: It doesn't create a text element.
         // Instead, it leaves the passed argument structurally unchanged, but
         // styles all text in it.
-        let styles = Self::set(args)?;
+        let styles = Self::set(vm, args)?;
         let body = args.expect::<Content>("body")?;
         Ok(body.styled_with_map(styles))
     }
@@ -557,6 +566,21 @@ cast_to_value! {
     v: FontList => v.0.into()
 }
 
+/// Warn about font families that are not available.
+fn check_font_list(vm: &mut Vm, font_list: &Spanned<FontList>) {
+    let world = vm.world();
+    let book = world.book();
+    for family in &font_list.v.0 {
+        if !book.contains_family(family.as_str()) {
+            vm.vt.tracer.warn(warning!(
+                font_list.span,
+                "unknown font family: {}",
+                family.as_str(),
+            ));
+        }
+    }
+}
+
 /// The size of text.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct TextSize(pub Length);
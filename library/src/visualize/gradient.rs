@@ -0,0 +1,246 @@
+//! Color gradients.
+
+use std::sync::Arc;
+
+use typst::eval::{Module, Scope};
+
+use crate::prelude::*;
+
+/// A module with functions for creating gradients.
+pub fn module() -> Module {
+    let mut scope = Scope::new();
+    scope.define("linear", linear);
+    scope.define("radial", radial);
+    scope.define("conic", conic);
+    Module::new("gradient").with_scope(scope)
+}
+
+/// Create a linear gradient.
+///
+/// A gradient can be used wherever a color is accepted as a paint: as the
+/// fill or stroke of a shape, as the fill of text and as the fill of table
+/// cells.
+///
+/// ## Example
+/// ```example
+/// #rect(
+///   width: 100%,
+///   fill: gradient.linear(red, blue),
+/// )
+/// #rect(
+///   width: 100%,
+///   fill: gradient.linear(
+///     (red, 0%), (yellow, 30%), (blue, 100%),
+///     angle: 45deg,
+///   ),
+/// )
+/// ```
+///
+/// ## Stops
+/// A gradient is made of at least two color stops. A stop is either just a
+/// color or an array of a color and an offset between `{0%}` and `{100%}`.
+/// If no stop has an offset, the stops are distributed evenly. Otherwise,
+/// all stops must have an offset and the offsets must be in ascending order.
+///
+/// ## Relativeness
+/// By default, a gradient is stretched over the bounding box of the element
+/// it paints. With `{relative: "parent"}`, it is instead stretched over the
+/// element's parent container, which lets multiple elements share one
+/// gradient. Text is painted relative to its parent container by default.
+///
+/// _Note:_ In PDF export, the transparency of color stops is currently
+/// ignored and gradients are always painted opaquely.
+///
+/// Display: Linear Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn linear(
+    /// The color stops of the gradient.
+    #[variadic]
+    stops: Vec<Spanned<GradientStop>>,
+    /// The direction of the gradient. At `{0deg}`, the gradient runs from left
+    /// to right and it turns clockwise with increasing angles.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// What the gradient is relative to: Either `{"self"}` or `{"parent"}`.
+    #[named]
+    #[default]
+    relative: Smart<Relative>,
+) -> Value {
+    Value::Gradient(Gradient::Linear(Arc::new(LinearGradient {
+        stops: process_stops(args.span, stops)?,
+        angle,
+        relative,
+    })))
+}
+
+/// Create a radial gradient.
+///
+/// The gradient radiates from its center outwards. Just like a
+/// [linear gradient]($func/gradient.linear), it is stretched over the
+/// bounding box of the painted element, which turns its circles into
+/// ellipses for non-square boxes.
+///
+/// ## Example
+/// ```example
+/// #circle(
+///   radius: 20pt,
+///   fill: gradient.radial(white, blue),
+/// )
+/// #square(
+///   size: 40pt,
+///   fill: gradient.radial(
+///     red, yellow,
+///     center: (30%, 30%),
+///     radius: 70%,
+///   ),
+/// )
+/// ```
+///
+/// Display: Radial Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn radial(
+    /// The color stops of the gradient.
+    #[variadic]
+    stops: Vec<Spanned<GradientStop>>,
+    /// The center of the gradient, relative to the bounding box.
+    #[named]
+    #[default(Axes::splat(Ratio::new(0.5)))]
+    center: Axes<Ratio>,
+    /// The radius of the gradient, relative to the bounding box.
+    #[named]
+    #[default(Ratio::new(0.5))]
+    radius: Ratio,
+    /// What the gradient is relative to: Either `{"self"}` or `{"parent"}`.
+    #[named]
+    #[default]
+    relative: Smart<Relative>,
+) -> Value {
+    if radius.get() <= 0.0 {
+        bail!(args.span, "radius must be positive");
+    }
+
+    Value::Gradient(Gradient::Radial(Arc::new(RadialGradient {
+        stops: process_stops(args.span, stops)?,
+        center,
+        radius,
+        relative,
+    })))
+}
+
+/// Create a conic gradient.
+///
+/// The gradient sweeps clockwise around its center.
+///
+/// ## Example
+/// ```example
+/// #circle(
+///   radius: 20pt,
+///   fill: gradient.conic(red, yellow, green, blue, red),
+/// )
+/// ```
+///
+/// Display: Conic Gradient
+/// Category: visualize
+/// Returns: gradient
+#[func]
+pub fn conic(
+    /// The color stops of the gradient.
+    #[variadic]
+    stops: Vec<Spanned<GradientStop>>,
+    /// The angle at which the gradient starts. At `{0deg}`, it starts to the
+    /// right of the center.
+    #[named]
+    #[default(Angle::zero())]
+    angle: Angle,
+    /// The center of the gradient, relative to the bounding box.
+    #[named]
+    #[default(Axes::splat(Ratio::new(0.5)))]
+    center: Axes<Ratio>,
+    /// What the gradient is relative to: Either `{"self"}` or `{"parent"}`.
+    #[named]
+    #[default]
+    relative: Smart<Relative>,
+) -> Value {
+    Value::Gradient(Gradient::Conic(Arc::new(ConicGradient {
+        stops: process_stops(args.span, stops)?,
+        angle,
+        center,
+        relative,
+    })))
+}
+
+/// A color stop of a gradient.
+struct GradientStop {
+    /// The color of the stop.
+    color: Color,
+    /// The offset of the stop, if it was specified.
+    offset: Option<Ratio>,
+}
+
+cast_from_value! {
+    GradientStop,
+    color: Color => Self { color, offset: None },
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => {
+                Self { color: a.cast()?, offset: Some(b.cast()?) }
+            }
+            _ => Err("a color stop must contain exactly two entries")?,
+        }
+    },
+}
+
+/// Validate the color stops of a gradient and assign offsets to them.
+///
+/// The resulting stops always start at `0%` and end at `100%`.
+fn process_stops(
+    span: Span,
+    stops: Vec<Spanned<GradientStop>>,
+) -> SourceResult<Vec<(Color, Ratio)>> {
+    if stops.len() < 2 {
+        bail!(span, "a gradient must have at least two stops");
+    }
+
+    let count = stops.len();
+    let has_offsets = stops[0].v.offset.is_some();
+    let mut processed: Vec<(Color, Ratio)> = Vec::with_capacity(count + 2);
+
+    for (i, Spanned { v: stop, span }) in stops.into_iter().enumerate() {
+        let offset = match (has_offsets, stop.offset) {
+            (true, Some(offset)) => {
+                if !(0.0..=1.0).contains(&offset.get()) {
+                    bail!(span, "offset must be between 0% and 100%");
+                }
+                if processed.last().map_or(false, |&(_, prev)| offset < prev) {
+                    bail!(span, "offsets must be in ascending order");
+                }
+                offset
+            }
+            (false, None) => Ratio::new(i as f64 / (count - 1) as f64),
+            _ => bail!(span, "either all or no stops must have an offset"),
+        };
+
+        processed.push((stop.color, offset));
+    }
+
+    // Extend the first and last colors to the edges.
+    if let Some(&(color, offset)) = processed.first() {
+        if !offset.is_zero() {
+            processed.insert(0, (color, Ratio::zero()));
+        }
+    }
+
+    if let Some(&(color, offset)) = processed.last() {
+        if !offset.is_one() {
+            processed.push((color, Ratio::one()));
+        }
+    }
+
+    Ok(processed)
+}
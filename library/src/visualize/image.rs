@@ -9,7 +9,8 @@ use crate::text::families;
 
 /// A raster or vector graphic.
 ///
-/// Supported formats are PNG, JPEG, GIF and SVG.
+/// Supported formats are PNG, JPEG, GIF and SVG. Images can be loaded from a
+/// file or from raw [bytes]($type/bytes).
 ///
 /// ## Example
 /// ```example
@@ -22,20 +23,39 @@ use crate::text::families;
 /// )
 /// ```
 ///
+/// ```example
+/// #let data = read("rhino.png", encoding: none)
+/// #image(data, format: "png", width: 50%)
+/// ```
+///
 /// Display: Image
 /// Category: visualize
 #[element(Layout, LocalName, Figurable)]
 pub struct ImageElem {
-    /// Path to an image file.
+    /// Path to an image file or the raw bytes of an image.
     #[required]
     #[parse(
-        let Spanned { v: path, span } =
-            args.expect::<Spanned<EcoString>>("path to image file")?;
-        let path: EcoString = vm.locate(&path).at(span)?.to_string_lossy().into();
-        let _ = load(vm.world(), &path, None, None).at(span)?;
-        path
+        let Spanned { v: source, span } =
+            args.expect::<Spanned<ImageSource>>("path to image file or bytes")?;
+        let source = match source {
+            ImageSource::Path(path) => ImageSource::Path(
+                vm.locate(&path).at(span)?.to_string_lossy().into(),
+            ),
+            ImageSource::Bytes(bytes) => ImageSource::Bytes(bytes),
+        };
+        let format = args.clone().named("format")?.unwrap_or(Smart::Auto);
+        if format.is_custom() || matches!(source, ImageSource::Path(_)) {
+            let _ = load(vm.world(), &source, format, None, None).at(span)?;
+        }
+        source
     )]
-    pub path: EcoString,
+    pub source: ImageSource,
+
+    /// The image's format.
+    ///
+    /// By default, the format is detected from the file extension. When
+    /// passing raw bytes, the format must be specified explicitly.
+    pub format: Smart<ImageFormat>,
 
     /// The width of the image.
     pub width: Smart<Rel<Length>>,
@@ -61,8 +81,14 @@ impl Layout for ImageElem {
     ) -> SourceResult<Fragment> {
         let first = families(styles).next();
         let fallback_family = first.as_ref().map(|f| f.as_str());
-        let image =
-            load(vt.world, &self.path(), fallback_family, self.alt(styles)).unwrap();
+        let image = load(
+            vt.world,
+            &self.source(),
+            self.format(styles),
+            fallback_family,
+            self.alt(styles),
+        )
+        .at(self.span())?;
         let sizing = Axes::new(self.width(styles), self.height(styles));
         let region = sizing
             .zip(regions.base())
@@ -162,23 +188,63 @@ pub enum ImageFit {
     Stretch,
 }
 
-/// Load an image from a path.
+/// Where an image is loaded from.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum ImageSource {
+    /// The resolved path to an image file.
+    Path(EcoString),
+    /// The raw bytes of an image.
+    Bytes(Bytes),
+}
+
+cast_from_value! {
+    ImageSource,
+    v: EcoString => Self::Path(v),
+    v: Bytes => Self::Bytes(v),
+}
+
+cast_to_value! {
+    v: ImageSource => match v {
+        ImageSource::Path(path) => path.into(),
+        ImageSource::Bytes(bytes) => bytes.into(),
+    }
+}
+
+/// Load an image from a path or from bytes.
 #[comemo::memoize]
 fn load(
     world: Tracked<dyn World>,
-    full: &str,
+    source: &ImageSource,
+    format: Smart<ImageFormat>,
     fallback_family: Option<&str>,
     alt: Option<EcoString>,
 ) -> StrResult<Image> {
-    let full = Path::new(full);
-    let buffer = world.file(full)?;
-    let ext = full.extension().and_then(OsStr::to_str).unwrap_or_default();
-    let format = match ext.to_lowercase().as_str() {
-        "png" => ImageFormat::Raster(RasterFormat::Png),
-        "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
-        "gif" => ImageFormat::Raster(RasterFormat::Gif),
-        "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
-        _ => return Err("unknown image format".into()),
+    let (buffer, format) = match source {
+        ImageSource::Path(full) => {
+            let full = Path::new(full.as_str());
+            let buffer = world.file(full)?;
+            let format = match format {
+                Smart::Custom(format) => format,
+                Smart::Auto => {
+                    let ext =
+                        full.extension().and_then(OsStr::to_str).unwrap_or_default();
+                    match ext.to_lowercase().as_str() {
+                        "png" => ImageFormat::Raster(RasterFormat::Png),
+                        "jpg" | "jpeg" => ImageFormat::Raster(RasterFormat::Jpg),
+                        "gif" => ImageFormat::Raster(RasterFormat::Gif),
+                        "svg" | "svgz" => ImageFormat::Vector(VectorFormat::Svg),
+                        _ => return Err("unknown image format".into()),
+                    }
+                }
+            };
+            (buffer, format)
+        }
+        ImageSource::Bytes(bytes) => {
+            let Smart::Custom(format) = format else {
+                return Err("format must be specified when loading from bytes".into());
+            };
+            (bytes.buffer().clone(), format)
+        }
     };
     Image::with_fonts(buffer, format, world, fallback_family, alt)
 }
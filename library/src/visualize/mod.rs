@@ -1,5 +1,6 @@
 //! Drawing and visualization.
 
+pub mod gradient;
 mod image;
 mod line;
 mod path;
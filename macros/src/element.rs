@@ -15,6 +15,7 @@ struct Elem {
     ident: Ident,
     capable: Vec<Ident>,
     fields: Vec<Field>,
+    scope: Option<BlockWithReturn>,
 }
 
 struct Field {
@@ -28,7 +29,7 @@ struct Field {
     synthesized: bool,
     fold: bool,
     resolve: bool,
-    parse: Option<FieldParser>,
+    parse: Option<BlockWithReturn>,
     default: syn::Expr,
     vis: syn::Visibility,
     ident: Ident,
@@ -50,21 +51,6 @@ impl Field {
     }
 }
 
-struct FieldParser {
-    prefix: Vec<syn::Stmt>,
-    expr: syn::Stmt,
-}
-
-impl Parse for FieldParser {
-    fn parse(input: ParseStream) -> Result<Self> {
-        let mut stmts = syn::Block::parse_within(input)?;
-        let Some(expr) = stmts.pop() else {
-            return Err(input.error("expected at least on expression"));
-        };
-        Ok(Self { prefix: stmts, expr })
-    }
-}
-
 /// Preprocess the element's definition.
 fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
     let syn::Fields::Named(named) = &body.fields else {
@@ -137,7 +123,8 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
         .into_iter()
         .collect();
 
-    let docs = documentation(&body.attrs);
+    let mut attrs = body.attrs.clone();
+    let docs = documentation(&attrs);
     let mut lines = docs.split('\n').collect();
     let category = meta_line(&mut lines, "Category")?.into();
     let display = meta_line(&mut lines, "Display")?.into();
@@ -152,9 +139,10 @@ fn prepare(stream: TokenStream, body: &syn::ItemStruct) -> Result<Elem> {
         ident: body.ident.clone(),
         capable,
         fields,
+        scope: parse_attr(&mut attrs, "scope")?.flatten(),
     };
 
-    validate_attrs(&body.attrs)?;
+    validate_attrs(&attrs)?;
     Ok(element)
 }
 
@@ -346,6 +334,7 @@ fn create_set_field_method(field: &Field) -> TokenStream {
 fn create_pack_impl(element: &Elem) -> TokenStream {
     let Elem { ident, name, display, category, docs, .. } = element;
     let vtable_func = create_vtable_func(element);
+    let scope = create_scope_builder(element.scope.as_ref());
     let infos = element
         .fields
         .iter()
@@ -371,12 +360,13 @@ fn create_pack_impl(element: &Elem) -> TokenStream {
                     construct: <#ident as ::typst::model::Construct>::construct,
                     set: <#ident as ::typst::model::Set>::set,
                     info: ::typst::eval::Lazy::new(|| typst::eval::FuncInfo {
-                        name: #name,
-                        display: #display,
+                        name: #name.into(),
+                        display: #display.into(),
                         docs: #docs,
                         params: ::std::vec![#(#infos),*],
                         returns: ::std::vec!["content"],
                         category: #category,
+                        scope: #scope,
                     }),
                 };
                 (&NATIVE).into()
@@ -422,7 +412,7 @@ fn create_param_info(field: &Field) -> TokenStream {
     };
     quote! {
         ::typst::eval::ParamInfo {
-            name: #name,
+            name: #name.into(),
             docs: #docs,
             cast: <#ty as ::typst::eval::Cast<
                 ::typst::syntax::Spanned<::typst::eval::Value>
@@ -507,6 +497,7 @@ fn create_set_impl(element: &Elem) -> TokenStream {
     quote! {
         impl ::typst::model::Set for #ident {
             fn set(
+                vm: &mut ::typst::eval::Vm,
                 args: &mut ::typst::eval::Args,
             ) -> ::typst::diag::SourceResult<::typst::model::Styles> {
                 let mut styles = ::typst::model::Styles::new();
@@ -519,7 +510,7 @@ fn create_set_impl(element: &Elem) -> TokenStream {
 
 /// Create argument parsing code for a field.
 fn create_field_parser(field: &Field) -> (TokenStream, TokenStream) {
-    if let Some(FieldParser { prefix, expr }) = &field.parse {
+    if let Some(BlockWithReturn { prefix, expr }) = &field.parse {
         return (quote! { #(#prefix);* }, quote! { #expr });
     }
 
@@ -17,6 +17,7 @@ struct Func {
     ident: Ident,
     params: Vec<Param>,
     returns: Vec<String>,
+    scope: Option<BlockWithReturn>,
     body: syn::Block,
 }
 
@@ -41,11 +42,9 @@ fn prepare(item: &syn::ItemFn) -> Result<Func> {
         };
 
         let syn::Pat::Ident(syn::PatIdent {
-            by_ref: None,
-            mutability: None,
-            ident,
-            ..
-        }) = &*typed.pat else {
+            by_ref: None, mutability: None, ident, ..
+        }) = &*typed.pat
+        else {
             bail!(typed.pat, "expected identifier");
         };
 
@@ -72,7 +71,8 @@ fn prepare(item: &syn::ItemFn) -> Result<Func> {
         validate_attrs(&attrs)?;
     }
 
-    let docs = documentation(&item.attrs);
+    let mut attrs = item.attrs.clone();
+    let docs = documentation(&attrs);
     let mut lines = docs.split('\n').collect();
     let returns = meta_line(&mut lines, "Returns")?
         .split(" or ")
@@ -91,10 +91,11 @@ fn prepare(item: &syn::ItemFn) -> Result<Func> {
         ident: sig.ident.clone(),
         params,
         returns,
+        scope: parse_attr(&mut attrs, "scope")?.flatten(),
         body: (*item.block).clone(),
     };
 
-    validate_attrs(&item.attrs)?;
+    validate_attrs(&attrs)?;
     Ok(func)
 }
 
@@ -113,6 +114,7 @@ fn create(func: &Func) -> TokenStream {
     } = func;
     let handlers = params.iter().filter(|param| !param.external).map(create_param_parser);
     let params = params.iter().map(create_param_info);
+    let scope = create_scope_builder(func.scope.as_ref());
     quote! {
         #[doc = #docs]
         #vis fn #ident() -> &'static ::typst::eval::NativeFunc {
@@ -123,12 +125,13 @@ fn create(func: &Func) -> TokenStream {
                     Ok(#body)
                 },
                 info: ::typst::eval::Lazy::new(|| typst::eval::FuncInfo {
-                    name: #name,
-                    display: #display,
+                    name: #name.into(),
+                    display: #display.into(),
                     docs: #docs,
                     params: ::std::vec![#(#params),*],
                     returns: ::std::vec![#(#returns),*],
                     category: #category,
+                    scope: #scope,
                 }),
             };
             &FUNC
@@ -148,7 +151,7 @@ fn create_param_info(param: &Param) -> TokenStream {
     };
     quote! {
         ::typst::eval::ParamInfo {
-            name: #name,
+            name: #name.into(),
             docs: #docs,
             cast: <#ty as ::typst::eval::Cast<
                 ::typst::syntax::Spanned<::typst::eval::Value>
@@ -55,6 +55,35 @@ pub fn validate_attrs(attrs: &[syn::Attribute]) -> Result<()> {
     Ok(())
 }
 
+/// A list of statements where the final one is an expression.
+pub struct BlockWithReturn {
+    pub prefix: Vec<syn::Stmt>,
+    pub expr: syn::Stmt,
+}
+
+impl Parse for BlockWithReturn {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut stmts = syn::Block::parse_within(input)?;
+        let Some(expr) = stmts.pop() else {
+            return Err(input.error("expected at least one expression"));
+        };
+        Ok(Self { prefix: stmts, expr })
+    }
+}
+
+/// Create the code that builds the scope of a function or element.
+pub fn create_scope_builder(scope_block: Option<&BlockWithReturn>) -> TokenStream {
+    if let Some(BlockWithReturn { prefix, expr }) = scope_block {
+        quote! { {
+            let mut scope = ::typst::eval::Scope::deduplicating();
+            #(#prefix);*
+            #expr
+        } }
+    } else {
+        quote! { ::typst::eval::Scope::new() }
+    }
+}
+
 /// Convert an identifier to a kebab-case string.
 pub fn kebab_case(name: &Ident) -> String {
     name.to_string().to_kebab_case()
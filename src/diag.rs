@@ -10,6 +10,7 @@ use std::string::FromUtf8Error;
 use comemo::Tracked;
 use ecow::EcoString;
 
+use crate::eval::PackageSpec;
 use crate::syntax::{ErrorPos, Span, Spanned};
 use crate::World;
 
@@ -44,22 +45,41 @@ macro_rules! __error {
 
 #[doc(inline)]
 pub use crate::__error as error;
+
+/// Construct a [`SourceError`] with [warning](Severity::Warning) severity.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! __warning {
+    ($span:expr, $message:expr $(,)?) => {
+        $crate::diag::SourceError::new($span, $message)
+            .with_severity($crate::diag::Severity::Warning)
+    };
+
+    ($span:expr, $fmt:expr, $($arg:expr),+ $(,)?) => {
+        $crate::diag::warning!($span, $crate::diag::eco_format!($fmt, $($arg),+))
+    };
+}
+
+#[doc(inline)]
+pub use crate::__warning as warning;
 #[doc(hidden)]
 pub use ecow::eco_format;
 
 /// A result that can carry multiple source errors.
 pub type SourceResult<T> = Result<T, Box<Vec<SourceError>>>;
 
-/// An error in a source file.
+/// An error or warning in a source file.
 ///
 /// The contained spans will only be detached if any of the input source files
-/// were detached.
+/// were detached or if the diagnostic does not belong to a specific location.
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct SourceError {
     /// The span of the erroneous node in the source code.
     pub span: Span,
     /// The position in the node where the error should be annotated.
     pub pos: ErrorPos,
+    /// Whether this is a hard error or a warning.
+    pub severity: Severity,
     /// A diagnostic message describing the problem.
     pub message: EcoString,
     /// The trace of function calls leading to the error.
@@ -73,6 +93,7 @@ impl SourceError {
         Self {
             span,
             pos: ErrorPos::Full,
+            severity: Severity::Error,
             trace: vec![],
             message: message.into(),
         }
@@ -84,6 +105,12 @@ impl SourceError {
         self
     }
 
+    /// Adjust the severity of the diagnostic.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
     /// The range in the source file identified by
     /// [`self.span.source()`](Span::source) where the error should be
     /// annotated.
@@ -97,6 +124,16 @@ impl SourceError {
     }
 }
 
+/// How severe a [`SourceError`] is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Severity {
+    /// A hard error that makes compilation fail.
+    Error,
+    /// A problem that does not prevent compilation, but likely leads to
+    /// output that differs from what the author intended.
+    Warning,
+}
+
 /// A part of an error's [trace](SourceError::trace).
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub enum Tracepoint {
@@ -248,6 +285,34 @@ impl From<FileError> for EcoString {
     }
 }
 
+/// A result type with a package-related error.
+pub type PackageResult<T> = Result<T, PackageError>;
+
+/// An error that occurred while trying to load a package.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum PackageError {
+    /// The specified package does not exist.
+    NotFound(PackageSpec),
+}
+
+impl std::error::Error for PackageError {}
+
+impl Display for PackageError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound(spec) => {
+                write!(f, "package not found (searched for {spec})")
+            }
+        }
+    }
+}
+
+impl From<PackageError> for EcoString {
+    fn from(error: PackageError) -> Self {
+        eco_format!("{error}")
+    }
+}
+
 /// Format a user-facing error message for an XML-like file format.
 pub fn format_xml_like_error(format: &str, error: roxmltree::Error) -> EcoString {
     match error {
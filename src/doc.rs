@@ -7,7 +7,7 @@ use std::sync::Arc;
 
 use ecow::EcoString;
 
-use crate::eval::{cast_from_value, cast_to_value, dict, Dict, Value};
+use crate::eval::{cast_from_value, cast_to_value, dict, Datetime, Dict, Value};
 use crate::font::Font;
 use crate::geom::{
     self, rounded_rect, Abs, Align, Axes, Color, Corners, Dir, Em, Geometry, Length,
@@ -26,6 +26,8 @@ pub struct Document {
     pub title: Option<EcoString>,
     /// The document's author.
     pub author: Vec<EcoString>,
+    /// The document's creation date.
+    pub date: Option<Datetime>,
 }
 
 /// A finished layout with items at fixed positions.
@@ -485,6 +487,17 @@ impl TextItem {
     pub fn width(&self) -> Abs {
         self.glyphs.iter().map(|g| g.x_advance).sum::<Em>().at(self.size)
     }
+
+    /// The top-left corner and size of the text run's bounding box, relative
+    /// to the start of its baseline.
+    ///
+    /// Vertically, the box spans from the font's ascender to its descender.
+    pub fn bbox(&self) -> (Point, Size) {
+        let metrics = self.font.metrics();
+        let ascender = metrics.ascender.at(self.size);
+        let descender = metrics.descender.at(self.size);
+        (Point::with_y(-ascender), Size::new(self.width(), ascender - descender))
+    }
 }
 
 impl Debug for TextItem {
@@ -0,0 +1,159 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Add, Deref};
+
+use ecow::{eco_format, EcoString};
+use serde::{Serialize, Serializer};
+
+use super::{Array, Str, Value};
+use crate::diag::StrResult;
+use crate::util::Buffer;
+
+/// An immutable, cheaply clonable sequence of bytes.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Bytes(Buffer);
+
+impl Bytes {
+    /// Whether the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The number of bytes.
+    pub fn len(&self) -> i64 {
+        self.0.len() as i64
+    }
+
+    /// A view into the bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// The underlying buffer.
+    pub fn buffer(&self) -> &Buffer {
+        &self.0
+    }
+
+    /// Extract the byte at the given index.
+    pub fn at(&self, index: i64) -> StrResult<i64> {
+        self.locate(index)
+            .and_then(|i| self.0.get(i))
+            .map(|&byte| byte as i64)
+            .ok_or_else(|| out_of_bounds(index, self.len()))
+    }
+
+    /// Extract a contiguous subsequence.
+    pub fn slice(&self, start: i64, end: Option<i64>) -> StrResult<Self> {
+        let len = self.len();
+        let start = self
+            .locate(start)
+            .filter(|&start| start <= self.0.len())
+            .ok_or_else(|| out_of_bounds(start, len))?;
+
+        let end = end.unwrap_or(len);
+        let end = self
+            .locate(end)
+            .filter(|&end| end <= self.0.len())
+            .ok_or_else(|| out_of_bounds(end, len))?
+            .max(start);
+
+        Ok(self.0[start..end].into())
+    }
+
+    /// Convert the bytes into an array of integers.
+    pub fn to_array(&self) -> Array {
+        self.0.iter().map(|&byte| Value::Int(byte as i64)).collect()
+    }
+
+    /// Interpret the bytes as a UTF-8 string.
+    pub fn to_str(&self) -> StrResult<Str> {
+        std::str::from_utf8(&self.0)
+            .map(Into::into)
+            .map_err(|_| "bytes are not valid utf-8".into())
+    }
+
+    /// Create bytes from an array of integers between 0 and 255.
+    pub fn from_array(array: &Array) -> StrResult<Self> {
+        array
+            .iter()
+            .map(|value| match value {
+                &Value::Int(byte) => u8::try_from(byte).map_err(|_| {
+                    eco_format!("byte must be between 0 and 255, found {byte}")
+                }),
+                v => Err(eco_format!("expected integer, found {}", v.type_name())),
+            })
+            .collect::<StrResult<Vec<u8>>>()
+            .map(Into::into)
+    }
+
+    /// Resolve an index.
+    fn locate(&self, index: i64) -> Option<usize> {
+        usize::try_from(if index >= 0 { index } else { self.len().checked_add(index)? })
+            .ok()
+    }
+}
+
+impl From<Buffer> for Bytes {
+    fn from(buffer: Buffer) -> Self {
+        Self(buffer)
+    }
+}
+
+impl From<&[u8]> for Bytes {
+    fn from(slice: &[u8]) -> Self {
+        Self(slice.into())
+    }
+}
+
+impl From<Vec<u8>> for Bytes {
+    fn from(vec: Vec<u8>) -> Self {
+        Self(vec.into())
+    }
+}
+
+impl Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Bytes {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Debug for Bytes {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "bytes({})", self.len())
+    }
+}
+
+impl Add for Bytes {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.is_empty() {
+            return rhs;
+        } else if rhs.is_empty() {
+            return self;
+        }
+
+        let mut vec = self.0.to_vec();
+        vec.extend_from_slice(&rhs);
+        vec.into()
+    }
+}
+
+impl Serialize for Bytes {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self)
+    }
+}
+
+/// The out of bounds access error message.
+#[cold]
+fn out_of_bounds(index: i64, len: i64) -> EcoString {
+    eco_format!("byte index out of bounds (index: {}, len: {})", index, len)
+}
@@ -0,0 +1,248 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Formatter};
+
+use ecow::{eco_format, EcoString, EcoVec};
+use time::error::{Format, InvalidFormatDescription};
+use time::{format_description, PrimitiveDateTime};
+
+use super::Duration;
+use crate::diag::StrResult;
+use crate::util::pretty_array_like;
+
+/// A date, a time, or a combination of both.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Datetime {
+    /// Representation as a date.
+    Date(time::Date),
+    /// Representation as a time.
+    Time(time::Time),
+    /// Representation as a combination of date and time.
+    Datetime(PrimitiveDateTime),
+}
+
+impl Datetime {
+    /// Create a datetime from year, month, and day.
+    pub fn from_ymd(year: i32, month: u8, day: u8) -> Option<Self> {
+        Some(Datetime::Date(
+            time::Date::from_calendar_date(year, time::Month::try_from(month).ok()?, day)
+                .ok()?,
+        ))
+    }
+
+    /// Create a datetime from hour, minute, and second.
+    pub fn from_hms(hour: u8, minute: u8, second: u8) -> Option<Self> {
+        Some(Datetime::Time(time::Time::from_hms(hour, minute, second).ok()?))
+    }
+
+    /// Create a datetime from day and time.
+    pub fn from_ymd_hms(
+        year: i32,
+        month: u8,
+        day: u8,
+        hour: u8,
+        minute: u8,
+        second: u8,
+    ) -> Option<Self> {
+        let date =
+            time::Date::from_calendar_date(year, time::Month::try_from(month).ok()?, day)
+                .ok()?;
+        let time = time::Time::from_hms(hour, minute, second).ok()?;
+        Some(Datetime::Datetime(PrimitiveDateTime::new(date, time)))
+    }
+
+    /// Display the date and/or time in a certain format.
+    ///
+    /// If no pattern is given, a default depending on the kind of the
+    /// datetime is used.
+    pub fn display(&self, pattern: Option<EcoString>) -> StrResult<EcoString> {
+        let pattern = pattern.as_ref().map(EcoString::as_str).unwrap_or(match self {
+            Datetime::Date(_) => "[year]-[month]-[day]",
+            Datetime::Time(_) => "[hour]:[minute]:[second]",
+            Datetime::Datetime(_) => "[year]-[month]-[day] [hour]:[minute]:[second]",
+        });
+
+        let format = format_description::parse(pattern)
+            .map_err(format_time_invalid_format_description_error)?;
+
+        let formatted = match self {
+            Datetime::Date(date) => date.format(&format),
+            Datetime::Time(time) => time.format(&format),
+            Datetime::Datetime(datetime) => datetime.format(&format),
+        };
+
+        formatted.map(EcoString::from).map_err(format_time_format_error)
+    }
+
+    /// The year if it was specified or `None` for times without a date.
+    pub fn year(&self) -> Option<i32> {
+        match self {
+            Datetime::Date(date) => Some(date.year()),
+            Datetime::Time(_) => None,
+            Datetime::Datetime(datetime) => Some(datetime.year()),
+        }
+    }
+
+    /// The month if it was specified or `None` for times without a date.
+    pub fn month(&self) -> Option<u8> {
+        match self {
+            Datetime::Date(date) => Some(date.month().into()),
+            Datetime::Time(_) => None,
+            Datetime::Datetime(datetime) => Some(datetime.month().into()),
+        }
+    }
+
+    /// The day if it was specified or `None` for times without a date.
+    pub fn day(&self) -> Option<u8> {
+        match self {
+            Datetime::Date(date) => Some(date.day()),
+            Datetime::Time(_) => None,
+            Datetime::Datetime(datetime) => Some(datetime.day()),
+        }
+    }
+
+    /// The weekday (counting Monday as 1) or `None` for times without a date.
+    pub fn weekday(&self) -> Option<u8> {
+        match self {
+            Datetime::Date(date) => Some(date.weekday().number_from_monday()),
+            Datetime::Time(_) => None,
+            Datetime::Datetime(datetime) => Some(datetime.weekday().number_from_monday()),
+        }
+    }
+
+    /// The ordinal (day of the year) or `None` for times without a date.
+    pub fn ordinal(&self) -> Option<u16> {
+        match self {
+            Datetime::Date(date) => Some(date.ordinal()),
+            Datetime::Time(_) => None,
+            Datetime::Datetime(datetime) => Some(datetime.ordinal()),
+        }
+    }
+
+    /// The hour if it was specified or `None` for dates without a time.
+    pub fn hour(&self) -> Option<u8> {
+        match self {
+            Datetime::Date(_) => None,
+            Datetime::Time(time) => Some(time.hour()),
+            Datetime::Datetime(datetime) => Some(datetime.hour()),
+        }
+    }
+
+    /// The minute if it was specified or `None` for dates without a time.
+    pub fn minute(&self) -> Option<u8> {
+        match self {
+            Datetime::Date(_) => None,
+            Datetime::Time(time) => Some(time.minute()),
+            Datetime::Datetime(datetime) => Some(datetime.minute()),
+        }
+    }
+
+    /// The second if it was specified or `None` for dates without a time.
+    pub fn second(&self) -> Option<u8> {
+        match self {
+            Datetime::Date(_) => None,
+            Datetime::Time(time) => Some(time.second()),
+            Datetime::Datetime(datetime) => Some(datetime.second()),
+        }
+    }
+
+    /// Shift the datetime by a duration.
+    ///
+    /// Dates are shifted by whole days only. Times wrap around at midnight.
+    /// Returns `None` if the result is out of range.
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        let duration = duration.into();
+        Some(match self {
+            Datetime::Date(date) => Datetime::Date(date.checked_add(duration)?),
+            Datetime::Time(time) => Datetime::Time(time + duration),
+            Datetime::Datetime(datetime) => {
+                Datetime::Datetime(datetime.checked_add(duration)?)
+            }
+        })
+    }
+
+    /// Compute the duration between two datetimes of the same kind.
+    ///
+    /// Returns `None` if the two datetimes are not of the same kind.
+    pub fn difference(self, other: Self) -> Option<Duration> {
+        Some(
+            match (self, other) {
+                (Datetime::Date(a), Datetime::Date(b)) => a - b,
+                (Datetime::Time(a), Datetime::Time(b)) => a - b,
+                (Datetime::Datetime(a), Datetime::Datetime(b)) => a - b,
+                _ => return None,
+            }
+            .into(),
+        )
+    }
+}
+
+impl PartialOrd for Datetime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self, other) {
+            (Datetime::Date(a), Datetime::Date(b)) => a.partial_cmp(b),
+            (Datetime::Time(a), Datetime::Time(b)) => a.partial_cmp(b),
+            (Datetime::Datetime(a), Datetime::Datetime(b)) => a.partial_cmp(b),
+            _ => None,
+        }
+    }
+}
+
+impl Debug for Datetime {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let year = self.year().map(|y| eco_format!("year: {y}"));
+        let month = self.month().map(|m| eco_format!("month: {m}"));
+        let day = self.day().map(|d| eco_format!("day: {d}"));
+        let hour = self.hour().map(|h| eco_format!("hour: {h}"));
+        let minute = self.minute().map(|m| eco_format!("minute: {m}"));
+        let second = self.second().map(|s| eco_format!("second: {s}"));
+        let filtered = [year, month, day, hour, minute, second]
+            .into_iter()
+            .flatten()
+            .collect::<EcoVec<_>>();
+
+        write!(f, "datetime{}", &pretty_array_like(&filtered, false))
+    }
+}
+
+/// Format the `Format` error of the time crate in an appropriate way.
+fn format_time_format_error(error: Format) -> EcoString {
+    match error {
+        Format::InvalidComponent(name) => eco_format!("invalid component '{}'", name),
+        _ => "failed to format datetime in the requested format".into(),
+    }
+}
+
+/// Format the `InvalidFormatDescription` error of the time crate in an
+/// appropriate way.
+fn format_time_invalid_format_description_error(
+    error: InvalidFormatDescription,
+) -> EcoString {
+    match error {
+        InvalidFormatDescription::UnclosedOpeningBracket { index, .. } => {
+            eco_format!("missing closing bracket for bracket at index {}", index)
+        }
+        InvalidFormatDescription::InvalidComponentName { name, index, .. } => {
+            eco_format!("invalid component name '{}' at index {}", name, index)
+        }
+        InvalidFormatDescription::InvalidModifier { value, index, .. } => {
+            eco_format!("invalid modifier '{}' at index {}", value, index)
+        }
+        InvalidFormatDescription::Expected { what, index, .. } => {
+            eco_format!("expected {} at index {}", what, index)
+        }
+        InvalidFormatDescription::MissingComponentName { index, .. } => {
+            eco_format!("expected component name at index {}", index)
+        }
+        InvalidFormatDescription::MissingRequiredModifier { name, index, .. } => {
+            eco_format!(
+                "missing required modifier {} for component at index {}",
+                name,
+                index
+            )
+        }
+        InvalidFormatDescription::NotSupported { context, what, index, .. } => {
+            eco_format!("{} is not supported in {} at index {}", what, context, index)
+        }
+        _ => "failed to parse datetime format".into(),
+    }
+}
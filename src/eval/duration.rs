@@ -0,0 +1,165 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::Div;
+
+use ecow::{eco_format, EcoVec};
+use time::ext::NumericalDuration;
+
+use crate::util::pretty_array_like;
+
+/// Represents a positive or negative span of time.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration(time::Duration);
+
+impl Duration {
+    /// Create a duration from its components.
+    ///
+    /// Returns `None` if the resulting duration is out of range.
+    pub fn from_parts(
+        seconds: i64,
+        minutes: i64,
+        hours: i64,
+        days: i64,
+        weeks: i64,
+    ) -> Option<Self> {
+        let mut duration = time::Duration::ZERO;
+        for part in [
+            seconds.seconds(),
+            minutes.checked_mul(60)?.seconds(),
+            hours.checked_mul(60 * 60)?.seconds(),
+            days.checked_mul(60 * 60 * 24)?.seconds(),
+            weeks.checked_mul(60 * 60 * 24 * 7)?.seconds(),
+        ] {
+            duration = duration.checked_add(part)?;
+        }
+        Some(Self(duration))
+    }
+
+    /// Whether the duration is empty / zero.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    /// The duration expressed in seconds.
+    pub fn seconds(&self) -> f64 {
+        self.0.as_seconds_f64()
+    }
+
+    /// The duration expressed in minutes.
+    pub fn minutes(&self) -> f64 {
+        self.seconds() / 60.0
+    }
+
+    /// The duration expressed in hours.
+    pub fn hours(&self) -> f64 {
+        self.seconds() / 3_600.0
+    }
+
+    /// The duration expressed in days.
+    pub fn days(&self) -> f64 {
+        self.seconds() / 86_400.0
+    }
+
+    /// The duration expressed in weeks.
+    pub fn weeks(&self) -> f64 {
+        self.seconds() / 604_800.0
+    }
+
+    /// Negate the duration.
+    ///
+    /// Returns `None` if the result is out of range.
+    pub fn checked_neg(self) -> Option<Self> {
+        time::Duration::ZERO.checked_sub(self.0).map(Self)
+    }
+
+    /// Add two durations.
+    ///
+    /// Returns `None` if the result is out of range.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        self.0.checked_add(other.0).map(Self)
+    }
+
+    /// Subtract a duration from this one.
+    ///
+    /// Returns `None` if the result is out of range.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+
+    /// Scale the duration by a factor.
+    ///
+    /// Returns `None` if the result is out of range or not finite.
+    pub fn checked_mul(self, factor: f64) -> Option<Self> {
+        Self::from_seconds_f64(self.seconds() * factor)
+    }
+
+    /// Divide the duration by a divisor.
+    ///
+    /// Returns `None` if the result is out of range or not finite.
+    pub fn checked_div(self, divisor: f64) -> Option<Self> {
+        Self::from_seconds_f64(self.seconds() / divisor)
+    }
+
+    /// Create a duration from a number of seconds, if it is in range.
+    fn from_seconds_f64(seconds: f64) -> Option<Self> {
+        (seconds.is_finite() && seconds.abs() < i64::MAX as f64)
+            .then(|| Self(time::Duration::seconds_f64(seconds)))
+    }
+}
+
+impl From<time::Duration> for Duration {
+    fn from(value: time::Duration) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Duration> for time::Duration {
+    fn from(value: Duration) -> Self {
+        value.0
+    }
+}
+
+impl Debug for Duration {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut tmp = self.0;
+        let mut vec = EcoVec::new();
+
+        let weeks = tmp.whole_seconds() / 604_800;
+        if weeks != 0 {
+            vec.push(eco_format!("weeks: {weeks}"));
+        }
+        tmp -= weeks.weeks();
+
+        let days = tmp.whole_days();
+        if days != 0 {
+            vec.push(eco_format!("days: {days}"));
+        }
+        tmp -= days.days();
+
+        let hours = tmp.whole_hours();
+        if hours != 0 {
+            vec.push(eco_format!("hours: {hours}"));
+        }
+        tmp -= hours.hours();
+
+        let minutes = tmp.whole_minutes();
+        if minutes != 0 {
+            vec.push(eco_format!("minutes: {minutes}"));
+        }
+        tmp -= minutes.minutes();
+
+        let seconds = tmp.whole_seconds();
+        if seconds != 0 {
+            vec.push(eco_format!("seconds: {seconds}"));
+        }
+
+        write!(f, "duration{}", &pretty_array_like(&vec, false))
+    }
+}
+
+impl Div for Duration {
+    type Output = f64;
+
+    fn div(self, other: Self) -> f64 {
+        self.0 / other.0
+    }
+}
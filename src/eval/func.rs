@@ -2,18 +2,23 @@ pub use typst_macros::func;
 
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use comemo::{Prehashed, Track, Tracked, TrackedMut};
+use ecow::{eco_format, EcoString};
 use once_cell::sync::Lazy;
 
 use super::{
-    cast_to_value, Args, CastInfo, Eval, Flow, Route, Scope, Scopes, Tracer, Value, Vm,
+    cast_to_value, Args, Bytes, Cast, CastInfo, Eval, Flow, Plugin, Route, Scope, Scopes,
+    Str, Tracer, Value, Vm,
 };
-use crate::diag::{bail, SourceResult};
-use crate::model::{ElemFunc, Introspector, StabilityProvider, Vt};
+use crate::diag::{bail, At, SourceResult, StrResult};
+use crate::geom::{Length, Rel};
+use crate::model::{Content, ElemFunc, Introspector, StabilityProvider, Vt};
 use crate::syntax::ast::{self, AstNode, Expr, Ident};
-use crate::syntax::{SourceId, Span, SyntaxNode};
+use crate::syntax::{SourceId, Span, Spanned, SyntaxNode};
+use crate::util::separated_list;
 use crate::World;
 
 /// An evaluatable function.
@@ -35,6 +40,8 @@ enum Repr {
     Elem(ElemFunc),
     /// A user-defined closure.
     Closure(Arc<Prehashed<Closure>>),
+    /// A function exported by a WebAssembly plugin.
+    Plugin(Arc<(Plugin, EcoString)>),
     /// A nested function with pre-applied arguments.
     With(Arc<(Func, Args)>),
 }
@@ -43,9 +50,10 @@ impl Func {
     /// The name of the function.
     pub fn name(&self) -> Option<&str> {
         match &self.repr {
-            Repr::Native(native) => Some(native.info.name),
-            Repr::Elem(func) => Some(func.info().name),
+            Repr::Native(native) => Some(native.info.name.as_str()),
+            Repr::Elem(func) => Some(func.name()),
             Repr::Closure(closure) => closure.name.as_deref(),
+            Repr::Plugin(arc) => Some(&arc.1),
             Repr::With(arc) => arc.0.name(),
         }
     }
@@ -56,10 +64,57 @@ impl Func {
             Repr::Native(native) => Some(&native.info),
             Repr::Elem(func) => Some(func.info()),
             Repr::Closure(_) => None,
+            Repr::Plugin(_) => None,
             Repr::With(arc) => arc.0.info(),
         }
     }
 
+    /// The parameters of a user-defined closure.
+    ///
+    /// Native functions describe their parameters through their
+    /// [info](Self::info) instead.
+    pub fn params(&self) -> Option<&[Param]> {
+        match &self.repr {
+            Repr::Closure(closure) => Some(&closure.params),
+            Repr::With(arc) => arc.0.params(),
+            _ => None,
+        }
+    }
+
+    /// The type annotation of a user-defined closure's return value.
+    pub fn returns(&self) -> Option<&ast::TypeAnnotation> {
+        match &self.repr {
+            Repr::Closure(closure) => closure.returns.as_ref(),
+            Repr::With(arc) => arc.0.returns(),
+            _ => None,
+        }
+    }
+
+    /// Get a field from this function's scope, if possible.
+    pub fn get(&self, field: &str) -> StrResult<&Value> {
+        match &self.repr {
+            Repr::Native(native) => native.info.scope.get(field).ok_or_else(|| {
+                eco_format!(
+                    "function `{}` does not contain field `{}`",
+                    native.info.name,
+                    field
+                )
+            }),
+            Repr::Elem(func) => func.info().scope.get(field).ok_or_else(|| {
+                eco_format!(
+                    "function `{}` does not contain field `{}`",
+                    func.name(),
+                    field
+                )
+            }),
+            Repr::Closure(_) => {
+                Err("cannot access fields on user-defined functions".into())
+            }
+            Repr::Plugin(_) => Err("cannot access fields on plugin functions".into()),
+            Repr::With(arc) => arc.0.get(field),
+        }
+    }
+
     /// The function's span.
     pub fn span(&self) -> Span {
         self.span
@@ -109,6 +164,13 @@ impl Func {
                     args,
                 )
             }
+            Repr::Plugin(arc) => {
+                let (plugin, name) = &**arc;
+                let span = if self.span.is_detached() { args.span } else { self.span };
+                let bytes = args.all::<Bytes>()?;
+                args.finish()?;
+                Ok(Value::Bytes(plugin.call(name, bytes).at(span)?))
+            }
             Repr::With(arc) => {
                 args.items = arc.1.items.iter().cloned().chain(args.items).collect();
                 arc.0.call_vm(vm, args)
@@ -123,11 +185,17 @@ impl Func {
         vt: &mut Vt,
         args: impl IntoIterator<Item = Value>,
     ) -> SourceResult<Value> {
+        let args = Args::new(self.span(), args);
+        self.call_vt_args(vt, args)
+    }
+
+    /// Call the function with a Vt and prepared arguments.
+    #[tracing::instrument(skip_all)]
+    pub fn call_vt_args(&self, vt: &mut Vt, args: Args) -> SourceResult<Value> {
         let route = Route::default();
         let id = SourceId::detached();
         let scopes = Scopes::new(None);
         let mut vm = Vm::new(vt.reborrow_mut(), route.track(), id, scopes);
-        let args = Args::new(self.span(), args);
         self.call_vm(&mut vm, args)
     }
 
@@ -139,8 +207,8 @@ impl Func {
 
     /// Extract the element function, if it is one.
     pub fn element(&self) -> Option<ElemFunc> {
-        match self.repr {
-            Repr::Elem(func) => Some(func),
+        match &self.repr {
+            Repr::Elem(func) => Some(func.clone()),
             _ => None,
         }
     }
@@ -173,6 +241,13 @@ impl From<ElemFunc> for Func {
     }
 }
 
+impl Plugin {
+    /// Create a function that calls the plugin's export with the given name.
+    pub fn func(&self, name: impl Into<EcoString>) -> Func {
+        Repr::Plugin(Arc::new((self.clone(), name.into()))).into()
+    }
+}
+
 /// A Typst function defined by a native Rust function.
 pub struct NativeFunc {
     /// The function's implementation.
@@ -214,9 +289,9 @@ where
 #[derive(Debug, Clone)]
 pub struct FuncInfo {
     /// The function's name.
-    pub name: &'static str,
+    pub name: EcoString,
     /// The display name of the function.
-    pub display: &'static str,
+    pub display: EcoString,
     /// Documentation for the function.
     pub docs: &'static str,
     /// Details about the function's parameters.
@@ -225,6 +300,8 @@ pub struct FuncInfo {
     pub returns: Vec<&'static str>,
     /// Which category the function is part of.
     pub category: &'static str,
+    /// The function's own scope of fields and sub-functions.
+    pub scope: Scope,
 }
 
 impl FuncInfo {
@@ -238,7 +315,7 @@ impl FuncInfo {
 #[derive(Debug, Clone)]
 pub struct ParamInfo {
     /// The parameter's name.
-    pub name: &'static str,
+    pub name: EcoString,
     /// Documentation for the parameter.
     pub docs: &'static str,
     /// Valid values for the parameter.
@@ -263,22 +340,28 @@ pub struct ParamInfo {
 pub(super) struct Closure {
     /// The source file where the closure was defined.
     pub location: SourceId,
+    /// The root directory of the package the closure was defined in, if any.
+    pub package: Option<PathBuf>,
     /// The name of the closure.
     pub name: Option<Ident>,
     /// Captured values from outer scopes.
     pub captured: Scope,
     /// The list of parameters.
     pub params: Vec<Param>,
+    /// The type annotation of the return value.
+    pub returns: Option<ast::TypeAnnotation>,
     /// The expression the closure should evaluate to.
     pub body: Expr,
 }
 
+/// A parameter of a user-defined closure.
 #[derive(Hash)]
 pub enum Param {
-    /// A positional parameter: `x`.
-    Pos(ast::Pattern),
-    /// A named parameter with a default value: `draw: false`.
-    Named(Ident, Value),
+    /// A positional parameter: `x` or `x as length`.
+    Pos(ast::Pattern, Option<ast::TypeAnnotation>),
+    /// A named parameter with a default value: `draw: false` or
+    /// `gap as length: 1pt`.
+    Named(Ident, Value, Option<ast::TypeAnnotation>),
     /// An argument sink: `..args`.
     Sink(Option<Ident>),
 }
@@ -311,6 +394,7 @@ impl Closure {
         // Evaluate the body.
         let vt = Vt { world, tracer, provider, introspector };
         let mut vm = Vm::new(vt, route, closure.location, scopes);
+        vm.package = closure.package.clone();
         vm.depth = depth;
 
         // Provide the closure itself for recursive calls.
@@ -320,7 +404,7 @@ impl Closure {
 
         // Parse the arguments according to the parameter list.
         let num_pos_params =
-            closure.params.iter().filter(|p| matches!(p, Param::Pos(_))).count();
+            closure.params.iter().filter(|p| matches!(p, Param::Pos(..))).count();
         let num_pos_args = args.to_pos().len() as usize;
         let sink_size = num_pos_args.checked_sub(num_pos_params);
 
@@ -328,16 +412,16 @@ impl Closure {
         let mut sink_pos_values = None;
         for p in &closure.params {
             match p {
-                Param::Pos(pattern) => match pattern {
+                Param::Pos(pattern, annotation) => match pattern {
                     ast::Pattern::Normal(ast::Expr::Ident(ident)) => {
-                        vm.define(ident.clone(), args.expect::<Value>(ident)?)
+                        let value = args.expect(ident)?;
+                        vm.define(ident.clone(), typecheck(annotation.as_ref(), value)?)
                     }
                     ast::Pattern::Normal(_) => unreachable!(),
                     _ => {
-                        pattern.define(
-                            &mut vm,
-                            args.expect::<Value>("pattern parameter")?,
-                        )?;
+                        let value = args.expect("pattern parameter")?;
+                        let value = typecheck(annotation.as_ref(), value)?;
+                        pattern.define(&mut vm, value)?;
                     }
                 },
                 Param::Sink(ident) => {
@@ -346,9 +430,11 @@ impl Closure {
                         sink_pos_values = Some(args.consume(sink_size)?);
                     }
                 }
-                Param::Named(ident, default) => {
-                    let value =
-                        args.named::<Value>(ident)?.unwrap_or_else(|| default.clone());
+                Param::Named(ident, default, annotation) => {
+                    let value = match args.named(ident)? {
+                        Some(value) => typecheck(annotation.as_ref(), value)?,
+                        None => default.clone(),
+                    };
                     vm.define(ident.clone(), value);
                 }
             }
@@ -367,17 +453,88 @@ impl Closure {
 
         // Handle control flow.
         let result = closure.body.eval(&mut vm);
-        match vm.flow {
-            Some(Flow::Return(_, Some(explicit))) => return Ok(explicit),
-            Some(Flow::Return(_, None)) => {}
+        let output = match vm.flow {
+            Some(Flow::Return(span, Some(explicit))) => Spanned::new(explicit, span),
+            Some(Flow::Return(_, None)) | None => {
+                Spanned::new(result?, closure.body.span())
+            }
             Some(flow) => bail!(flow.forbidden()),
-            None => {}
+        };
+
+        typecheck(closure.returns.as_ref(), output)
+    }
+}
+
+/// Ensure that all types named in a type annotation exist.
+pub(super) fn validate_annotation(
+    vm: &Vm,
+    annotation: Option<&ast::TypeAnnotation>,
+) -> SourceResult<()> {
+    let Some(annotation) = annotation else { return Ok(()) };
+    for (ty, span) in annotation.spanned_types() {
+        if !type_exists(vm, &ty) {
+            bail!(span, "unknown type `{}`", ty);
         }
+    }
+    Ok(())
+}
+
+/// Whether a value can have the type with the given name.
+pub(super) fn type_exists(vm: &Vm, name: &str) -> bool {
+    Value::TYPE_NAMES.contains(&name) || vm.items.library_types.contains(&name)
+}
 
-        result
+/// Ensure that a value has one of the types listed in a type annotation.
+///
+/// Besides values of exactly the listed types, this accepts values that
+/// native functions would implicitly convert, like a length where a relative
+/// length is expected. Such values are converted to the first listed type
+/// that accepts them.
+pub(crate) fn typecheck(
+    annotation: Option<&ast::TypeAnnotation>,
+    value: Spanned<Value>,
+) -> SourceResult<Value> {
+    let Some(annotation) = annotation else { return Ok(value.v) };
+    let found = value.v.type_name();
+    let types: Vec<_> = annotation.types().collect();
+    if types.iter().any(|ty| ty == found) {
+        return Ok(value.v);
+    }
+
+    match types.iter().find(|ty| convertible(ty, &value.v)) {
+        Some(ty) => convert(ty, value.v).at(value.span),
+        None => bail!(
+            value.span,
+            "expected {}, found {}",
+            separated_list(&types, "or"),
+            found
+        ),
     }
 }
 
+/// Whether a value can be implicitly converted to the type named `expected`.
+fn convertible(expected: &str, value: &Value) -> bool {
+    match expected {
+        "float" => f64::is(value),
+        "relative length" => Rel::<Length>::is(value),
+        "string" => Str::is(value),
+        "content" => Content::is(value),
+        _ => false,
+    }
+}
+
+/// Convert a value to the type named `expected` with the same casts that
+/// native functions use.
+fn convert(expected: &str, value: Value) -> StrResult<Value> {
+    Ok(match expected {
+        "float" => Value::Float(value.cast()?),
+        "relative length" => Value::Relative(value.cast()?),
+        "string" => Value::Str(value.cast()?),
+        "content" => Value::Content(value.cast()?),
+        _ => value,
+    })
+}
+
 impl From<Closure> for Func {
     fn from(closure: Closure) -> Self {
         Repr::Closure(Arc::new(Prehashed::new(closure))).into()
@@ -491,13 +648,35 @@ impl<'a> CapturesVisitor<'a> {
                 self.internal.exit();
             }
 
+            // Each arm of a match expression binds the identifiers in its
+            // pattern. These are active in the arm's guard and body.
+            Some(ast::Expr::Match(expr)) => {
+                self.visit(expr.value().as_untyped());
+                for arm in expr.arms() {
+                    self.internal.enter();
+                    for ident in arm.pattern().idents() {
+                        self.bind(ident);
+                    }
+
+                    if let Some(guard) = arm.guard() {
+                        self.visit(guard.as_untyped());
+                    }
+
+                    self.visit(arm.body().as_untyped());
+                    self.internal.exit();
+                }
+            }
+
             // An import contains items, but these are active only after the
             // path is evaluated.
             Some(ast::Expr::Import(expr)) => {
                 self.visit(expr.source().as_untyped());
+                if let Some(new_name) = expr.new_name() {
+                    self.bind(new_name);
+                }
                 if let Some(ast::Imports::Items(items)) = expr.imports() {
                     for item in items {
-                        self.bind(item);
+                        self.bind(item.bound_name());
                     }
                 }
             }
@@ -575,6 +754,9 @@ mod tests {
         test("#((x, y: x + z) => x + y)", &["x", "z"]);
         test("#{x => x; x}", &["x"]);
 
+        // Closure with type annotations.
+        test("#((x as y, y as z: z) as x => y)", &["z"]);
+
         // Show rule.
         test("#show y: x => x", &["y"]);
         test("#show y: x => x + z", &["y", "z"]);
@@ -588,6 +770,8 @@ mod tests {
         // Import.
         test("#import z: x, y", &["z"]);
         test("#import x + y: x, y, z", &["x", "y"]);
+        test("#import z: x as y; #(x + y)", &["x", "z"]);
+        test("#import z as x: y; #(x + y)", &["z"]);
 
         // Blocks.
         test("#{ let x = 1; { let y = 2; y }; x + y }", &["y"]);
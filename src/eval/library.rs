@@ -103,6 +103,8 @@ pub struct LangItems {
     pub math_accent: fn(base: Content, accent: char) -> Content,
     /// A fraction in math: `x/2`.
     pub math_frac: fn(num: Content, denom: Content) -> Content,
+    /// The names of the types of dynamic values defined by the library.
+    pub library_types: &'static [&'static str],
     /// Dispatch a method on a library value.
     pub library_method: fn(
         vm: &mut Vm,
@@ -146,6 +148,7 @@ impl Hash for LangItems {
         self.math_attach.hash(state);
         self.math_accent.hash(state);
         self.math_frac.hash(state);
+        self.library_types.hash(state);
     }
 }
 
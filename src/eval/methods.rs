@@ -2,8 +2,9 @@
 
 use ecow::EcoString;
 
-use super::{Args, Str, Value, Vm};
+use super::{array, Args, Str, Value, Vm};
 use crate::diag::{At, SourceResult};
+use crate::geom::Ratio;
 use crate::model::{Location, Selector};
 use crate::syntax::Span;
 
@@ -26,6 +27,24 @@ pub fn call(
             _ => return missing(),
         },
 
+        Value::Gradient(gradient) => match method {
+            "kind" => Value::Str(gradient.kind().into()),
+            "stops" => Value::Array(
+                gradient
+                    .stops()
+                    .iter()
+                    .map(|&(color, offset)| Value::Array(array![color, offset]))
+                    .collect(),
+            ),
+            "angle" => gradient.angle().into(),
+            "relative" => gradient.relative().into(),
+            "sample" => {
+                let t: Ratio = args.expect("t")?;
+                Value::Color(gradient.sample(t.get()))
+            }
+            _ => return missing(),
+        },
+
         Value::Str(string) => match method {
             "len" => Value::Int(string.len()),
             "first" => Value::Str(string.first().at(span)?),
@@ -70,6 +89,20 @@ pub fn call(
             _ => return missing(),
         },
 
+        Value::Bytes(bytes) => match method {
+            "len" => Value::Int(bytes.len()),
+            "at" => Value::Int(bytes.at(args.expect("index")?).at(span)?),
+            "slice" => {
+                let start = args.expect("start")?;
+                let mut end = args.eat()?;
+                if end.is_none() {
+                    end = args.named("count")?.map(|c: i64| start + c);
+                }
+                Value::Bytes(bytes.slice(start, end).at(span)?)
+            }
+            _ => return missing(),
+        },
+
         Value::Content(content) => match method {
             "func" => content.func().into(),
             "has" => Value::Bool(content.has(&args.expect::<EcoString>("field")?)),
@@ -147,6 +180,28 @@ pub fn call(
             _ => return missing(),
         },
 
+        Value::Datetime(datetime) => match method {
+            "display" => Value::Str(datetime.display(args.eat()?).at(span)?.into()),
+            "year" => datetime.year().into(),
+            "month" => datetime.month().into(),
+            "weekday" => datetime.weekday().into(),
+            "day" => datetime.day().into(),
+            "ordinal" => datetime.ordinal().into(),
+            "hour" => datetime.hour().into(),
+            "minute" => datetime.minute().into(),
+            "second" => datetime.second().into(),
+            _ => return missing(),
+        },
+
+        Value::Duration(duration) => match method {
+            "seconds" => duration.seconds().into(),
+            "minutes" => duration.minutes().into(),
+            "hours" => duration.hours().into(),
+            "days" => duration.days().into(),
+            "weeks" => duration.weeks().into(),
+            _ => return missing(),
+        },
+
         Value::Args(args) => match method {
             "pos" => Value::Array(args.to_pos()),
             "named" => Value::Dict(args.to_named()),
@@ -276,6 +331,13 @@ fn missing_method(type_name: &str, method: &str) -> String {
 pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
     match type_name {
         "color" => &[("lighten", true), ("darken", true), ("negate", false)],
+        "gradient" => &[
+            ("angle", false),
+            ("kind", false),
+            ("relative", false),
+            ("sample", true),
+            ("stops", false),
+        ],
         "string" => &[
             ("len", false),
             ("at", true),
@@ -295,6 +357,7 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("starts-with", true),
             ("trim", true),
         ],
+        "bytes" => &[("len", false), ("at", true), ("slice", true)],
         "content" => &[("func", false), ("has", true), ("at", true), ("location", false)],
         "array" => &[
             ("all", true),
@@ -331,6 +394,24 @@ pub fn methods_on(type_name: &str) -> &[(&'static str, bool)] {
             ("remove", true),
             ("values", false),
         ],
+        "datetime" => &[
+            ("display", true),
+            ("year", false),
+            ("month", false),
+            ("weekday", false),
+            ("day", false),
+            ("ordinal", false),
+            ("hour", false),
+            ("minute", false),
+            ("second", false),
+        ],
+        "duration" => &[
+            ("seconds", false),
+            ("minutes", false),
+            ("hours", false),
+            ("days", false),
+            ("weeks", false),
+        ],
         "function" => &[("where", true), ("with", true)],
         "arguments" => &[("named", false), ("pos", false)],
         "location" => &[("page", false), ("position", false), ("page-numbering", false)],
@@ -13,10 +13,15 @@ mod str;
 #[macro_use]
 mod value;
 mod args;
+mod bytes;
+mod datetime;
+mod duration;
 mod func;
 mod methods;
 mod module;
 mod ops;
+mod package;
+mod plugin;
 mod scope;
 mod symbol;
 
@@ -25,11 +30,16 @@ pub use once_cell::sync::Lazy;
 
 pub use self::args::*;
 pub use self::array::*;
+pub use self::bytes::*;
 pub use self::cast::*;
+pub use self::datetime::*;
 pub use self::dict::*;
+pub use self::duration::*;
 pub use self::func::*;
 pub use self::library::*;
 pub use self::module::*;
+pub use self::package::*;
+pub use self::plugin::*;
 pub use self::scope::*;
 pub use self::str::*;
 pub use self::symbol::*;
@@ -37,7 +47,7 @@ pub use self::value::*;
 
 pub(crate) use self::methods::methods_on;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::mem;
 use std::path::{Path, PathBuf};
 
@@ -46,11 +56,11 @@ use ecow::EcoVec;
 use unicode_segmentation::UnicodeSegmentation;
 
 use crate::diag::{
-    bail, error, At, SourceError, SourceResult, StrResult, Trace, Tracepoint,
+    bail, error, warning, At, SourceError, SourceResult, StrResult, Trace, Tracepoint,
 };
 use crate::model::ShowableSelector;
 use crate::model::{
-    Content, Introspector, Label, Recipe, StabilityProvider, Styles, Transform,
+    Content, Introspector, Label, Location, Recipe, StabilityProvider, Styles, Transform,
     Unlabellable, Vt,
 };
 use crate::syntax::ast::AstNode;
@@ -64,12 +74,16 @@ const MAX_ITERATIONS: usize = 10_000;
 const MAX_CALL_DEPTH: usize = 64;
 
 /// Evaluate a source file and return the resulting module.
+///
+/// If the source file belongs to a package, `package` is the package's root
+/// directory.
 #[comemo::memoize]
 #[tracing::instrument(skip(world, route, tracer, source))]
 pub fn eval(
     world: Tracked<dyn World>,
     route: Tracked<Route>,
     tracer: TrackedMut<Tracer>,
+    package: Option<&Path>,
     source: &Source,
 ) -> SourceResult<Module> {
     // Prevent cyclic evaluation.
@@ -95,6 +109,7 @@ pub fn eval(
         introspector: introspector.track(),
     };
     let mut vm = Vm::new(vt, route.track(), id, scopes);
+    vm.package = package.map(Into::into);
     let root = match source.root().cast::<ast::Markup>() {
         Some(markup) if vm.traced.is_some() => markup,
         _ => source.ast()?,
@@ -167,6 +182,9 @@ pub struct Vm<'a> {
     route: Tracked<'a, Route>,
     /// The current location.
     location: SourceId,
+    /// The root directory of the package the current location belongs to,
+    /// if any. Files outside of it cannot be accessed.
+    package: Option<PathBuf>,
     /// A control flow event that is currently happening.
     flow: Option<Flow>,
     /// The stack of scopes.
@@ -192,6 +210,7 @@ impl<'a> Vm<'a> {
             items,
             route,
             location,
+            package: None,
             flow: None,
             scopes,
             depth: 0,
@@ -216,19 +235,31 @@ impl<'a> Vm<'a> {
 
     /// Resolve a user-entered path to be relative to the compilation
     /// environment's root.
+    ///
+    /// Within a package, absolute paths are relative to the package's root
+    /// and files outside of the package cannot be accessed.
     #[tracing::instrument(skip_all)]
     pub fn locate(&self, path: &str) -> StrResult<PathBuf> {
-        if !self.location.is_detached() {
-            if let Some(path) = path.strip_prefix('/') {
-                return Ok(self.world().root().join(path).normalize());
-            }
+        if self.location.is_detached() {
+            Err("cannot access file system from here")?;
+        }
+
+        let full = if let Some(path) = path.strip_prefix('/') {
+            let root = self.package.as_deref().unwrap_or(self.world().root());
+            root.join(path).normalize()
+        } else if let Some(dir) = self.world().source(self.location).path().parent() {
+            dir.join(path).normalize()
+        } else {
+            Err("cannot access file system from here")?
+        };
 
-            if let Some(dir) = self.world().source(self.location).path().parent() {
-                return Ok(dir.join(path).normalize());
+        if let Some(root) = &self.package {
+            if !full.starts_with(root) {
+                Err("cannot access file outside of package")?;
             }
         }
 
-        Err("cannot access file system from here".into())
+        Ok(full)
     }
 }
 
@@ -294,11 +325,26 @@ impl Route {
     }
 }
 
-/// Traces which values existed for the expression at a span.
+/// Traces which values existed for the expression at a span and collects
+/// warnings.
 #[derive(Default, Clone)]
 pub struct Tracer {
     span: Option<Span>,
     values: Vec<Value>,
+    warnings: Vec<SourceError>,
+    /// The introspections performed in the current layout iteration.
+    introspections: Vec<Introspection>,
+    /// The introspections performed in the previous layout iteration.
+    prev_introspections: Vec<Introspection>,
+}
+
+/// The output an introspecting element (like a `locate` call or a counter
+/// display) produced at a location in one layout iteration.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct Introspection {
+    location: Location,
+    span: Span,
+    output: u128,
 }
 
 impl Tracer {
@@ -307,13 +353,18 @@ impl Tracer {
 
     /// Create a new tracer, possibly with a span under inspection.
     pub fn new(span: Option<Span>) -> Self {
-        Self { span, values: vec![] }
+        Self { span, ..Self::default() }
     }
 
     /// Get the traced values.
     pub fn finish(self) -> Vec<Value> {
         self.values
     }
+
+    /// The warnings emitted so far.
+    pub fn warnings(&self) -> &[SourceError] {
+        &self.warnings
+    }
 }
 
 #[comemo::track]
@@ -333,6 +384,75 @@ impl Tracer {
             self.values.push(v);
         }
     }
+
+    /// Emit a warning.
+    ///
+    /// Since layout may run multiple times, the same warning is only kept
+    /// once.
+    pub fn warn(&mut self, warning: SourceError) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Record the hash of the output an introspecting element produced at its
+    /// location in the current layout iteration.
+    pub fn introspect(&mut self, location: Location, span: Span, output: u128) {
+        self.introspections.push(Introspection { location, span, output });
+    }
+
+    /// Start a new layout iteration.
+    pub fn relayout(&mut self) {
+        self.prev_introspections = mem::take(&mut self.introspections);
+    }
+
+    /// Warn that layout did not converge within the given number of attempts.
+    ///
+    /// The warnings point to the introspecting elements whose output changed
+    /// between the last two layout iterations. If there are none, a single
+    /// warning without a location is emitted.
+    pub fn warn_non_convergence(&mut self, attempts: usize) {
+        // If an element was laid out multiple times in one iteration, its last
+        // output counts.
+        let prev: HashMap<Location, u128> = self
+            .prev_introspections
+            .iter()
+            .map(|introspection| (introspection.location, introspection.output))
+            .collect();
+        let curr: HashMap<Location, u128> = self
+            .introspections
+            .iter()
+            .map(|introspection| (introspection.location, introspection.output))
+            .collect();
+
+        let mut spans = vec![];
+        for introspection in &self.introspections {
+            let span = introspection.span;
+            if prev.get(&introspection.location) != curr.get(&introspection.location)
+                && !span.is_detached()
+                && !spans.contains(&span)
+            {
+                spans.push(span);
+            }
+        }
+
+        if spans.is_empty() {
+            self.warn(warning!(
+                Span::detached(),
+                "layout did not converge within {} attempts",
+                attempts,
+            ));
+        }
+
+        for span in spans {
+            self.warn(warning!(
+                span,
+                "layout did not converge within {} attempts: \
+                 the result of this call kept changing",
+                attempts,
+            ));
+        }
+    }
 }
 
 /// Evaluate an expression.
@@ -461,6 +581,7 @@ impl Eval for ast::Expr {
             Self::Set(_) => bail!(forbidden("set")),
             Self::Show(_) => bail!(forbidden("show")),
             Self::Conditional(v) => v.eval(vm),
+            Self::Match(v) => v.eval(vm),
             Self::While(v) => v.eval(vm),
             Self::For(v) => v.eval(vm),
             Self::Import(v) => v.eval(vm),
@@ -1056,7 +1177,8 @@ impl Eval for ast::FuncCall {
         let args = self.args();
 
         // Try to evaluate as a method call. This is possible if the callee is a
-        // field access and does not evaluate to a module.
+        // field access and does not evaluate to a module or to a function with
+        // a matching field in its scope.
         let (callee, mut args) = if let ast::Expr::FieldAccess(access) = callee {
             let target = access.target();
             let field = access.field();
@@ -1077,7 +1199,7 @@ impl Eval for ast::FuncCall {
             } else {
                 let target = target.eval(vm)?;
                 let args = args.eval(vm)?;
-                if !matches!(target, Value::Symbol(_) | Value::Module(_)) {
+                if !is_field_call(&target, &field) {
                     return methods::call(vm, target, &field, args, span).trace(
                         vm.world(),
                         point,
@@ -1119,7 +1241,7 @@ impl Eval for ast::FuncCall {
             ));
         }
 
-        let callee = callee.cast::<Func>().at(callee_span)?;
+        let callee = callee.cast::<Func>().at(callee_span)?.spanned(callee_span);
         let point = || Tracepoint::Call(callee.name().map(Into::into));
         let f = || callee.call_vm(vm, args).trace(vm.world(), point, span);
 
@@ -1132,6 +1254,16 @@ impl Eval for ast::FuncCall {
     }
 }
 
+/// Whether a call on the field of the given target should call the field
+/// itself instead of a method.
+fn is_field_call(target: &Value, field: &str) -> bool {
+    match target {
+        Value::Symbol(_) | Value::Module(_) => true,
+        Value::Func(func) => func.get(field).is_ok(),
+        _ => false,
+    }
+}
+
 fn in_math(expr: &ast::Expr) -> bool {
     match expr {
         ast::Expr::MathIdent(_) => true,
@@ -1206,22 +1338,32 @@ impl Eval for ast::Closure {
 
         // Collect parameters and an optional sink parameter.
         let mut params = Vec::new();
-        for param in self.params().children() {
+        for (param, annotation) in self.params().annotated() {
+            validate_annotation(vm, annotation.as_ref())?;
             match param {
-                ast::Param::Pos(pattern) => params.push(Param::Pos(pattern)),
+                ast::Param::Pos(pattern) => params.push(Param::Pos(pattern, annotation)),
                 ast::Param::Named(named) => {
-                    params.push(Param::Named(named.name(), named.expr().eval(vm)?));
+                    // Check the default value right away.
+                    let expr = named.expr();
+                    let default = Spanned::new(expr.eval(vm)?, expr.span());
+                    let default = typecheck(annotation.as_ref(), default)?;
+                    params.push(Param::Named(named.name(), default, annotation));
                 }
                 ast::Param::Sink(spread) => params.push(Param::Sink(spread.name())),
             }
         }
 
+        let returns = self.returns();
+        validate_annotation(vm, returns.as_ref())?;
+
         // Define the closure.
         let closure = Closure {
             location: vm.location,
+            package: vm.package.clone(),
             name,
             captured,
             params,
+            returns,
             body: self.body(),
         };
 
@@ -1294,16 +1436,19 @@ impl ast::Pattern {
             match p {
                 ast::DestructuringKind::Normal(ast::Expr::Ident(ident)) => {
                     let Ok(v) = value.at(&ident) else {
-                                        bail!(ident.span(), "destructuring key not found in dictionary");
-                                    };
+                        bail!(ident.span(), "destructuring key not found in dictionary");
+                    };
                     f(vm, ast::Expr::Ident(ident.clone()), v.clone())?;
                     used.insert(ident.take());
                 }
                 ast::DestructuringKind::Sink(spread) => sink = spread.expr(),
                 ast::DestructuringKind::Named(named) => {
                     let Ok(v) = value.at(named.name().as_str()) else {
-                                        bail!(named.name().span(), "destructuring key not found in dictionary");
-                                    };
+                        bail!(
+                            named.name().span(),
+                            "destructuring key not found in dictionary"
+                        );
+                    };
                     f(vm, named.expr(), v.clone())?;
                     used.insert(named.name().take());
                 }
@@ -1366,6 +1511,138 @@ impl ast::Pattern {
             Ok(Value::None)
         })
     }
+
+    /// Whether the value matches the pattern of a match arm.
+    ///
+    /// Identifiers in the pattern are bound as the value is matched. Unlike
+    /// with [`define`](Self::define), a value of the wrong shape is not an
+    /// error, it just doesn't match.
+    pub fn matches(&self, vm: &mut Vm, value: &Value) -> SourceResult<bool> {
+        match self {
+            ast::Pattern::Normal(expr) => match_expr(vm, expr, value),
+            ast::Pattern::Placeholder(_) => Ok(true),
+            ast::Pattern::Destructuring(destruct) => match value {
+                Value::Array(array) => match_array(vm, destruct, array),
+                Value::Dict(dict) => match_dict(vm, destruct, dict),
+                _ => Ok(false),
+            },
+        }
+    }
+}
+
+/// Match a value against a binding, a type pattern or a literal.
+fn match_expr(vm: &mut Vm, expr: &ast::Expr, value: &Value) -> SourceResult<bool> {
+    match expr {
+        ast::Expr::Ident(ident) => {
+            vm.define(ident.clone(), value.clone());
+            Ok(true)
+        }
+        ast::Expr::FuncCall(call) if is_type_pattern(call) => {
+            let mut args = call.args().items();
+            let (Some(ast::Arg::Pos(ast::Expr::Str(string))), None) =
+                (args.next(), args.next())
+            else {
+                bail!(call.args().span(), "expected type name");
+            };
+            let name = string.get();
+            if !type_exists(vm, &name) {
+                bail!(string.span(), "unknown type `{}`", name);
+            }
+            Ok(value.type_name() == name.as_str())
+        }
+        expr => Ok(ops::equal(&expr.eval(vm)?, value)),
+    }
+}
+
+/// Whether a call is a type pattern: `type("string")`.
+fn is_type_pattern(call: &ast::FuncCall) -> bool {
+    matches!(call.callee(), ast::Expr::Ident(callee) if callee.as_str() == "type")
+}
+
+/// Match an array against a destructuring pattern.
+fn match_array(
+    vm: &mut Vm,
+    destruct: &ast::Destructuring,
+    array: &Array,
+) -> SourceResult<bool> {
+    let mut required = 0;
+    let mut sink = false;
+    for binding in destruct.bindings() {
+        match binding {
+            ast::DestructuringKind::Sink(_) => sink = true,
+            ast::DestructuringKind::Named(_) => return Ok(false),
+            _ => required += 1,
+        }
+    }
+
+    let len = array.len() as usize;
+    if len < required || (!sink && len > required) {
+        return Ok(false);
+    }
+
+    let mut items = array.iter();
+    for binding in destruct.bindings() {
+        match binding {
+            ast::DestructuringKind::Normal(expr) => {
+                if !match_expr(vm, &expr, items.next().unwrap())? {
+                    return Ok(false);
+                }
+            }
+            ast::DestructuringKind::Placeholder(_) => {
+                items.next();
+            }
+            ast::DestructuringKind::Sink(spread) => {
+                let rest: Array = items.by_ref().take(len - required).cloned().collect();
+                if let Some(ident) = spread.name() {
+                    vm.define(ident, rest);
+                }
+            }
+            ast::DestructuringKind::Named(_) => unreachable!(),
+        }
+    }
+
+    Ok(true)
+}
+
+/// Match a dictionary against a destructuring pattern.
+fn match_dict(
+    vm: &mut Vm,
+    destruct: &ast::Destructuring,
+    dict: &Dict,
+) -> SourceResult<bool> {
+    let mut sink = None;
+    let mut used = HashSet::new();
+    for binding in destruct.bindings() {
+        match binding {
+            ast::DestructuringKind::Normal(ast::Expr::Ident(ident)) => {
+                let Ok(v) = dict.at(&ident) else { return Ok(false) };
+                vm.define(ident.clone(), v.clone());
+                used.insert(ident.take());
+            }
+            ast::DestructuringKind::Named(named) => {
+                let Ok(v) = dict.at(named.name().as_str()) else { return Ok(false) };
+                if !named.pattern().matches(vm, v)? {
+                    return Ok(false);
+                }
+                used.insert(named.name().take());
+            }
+            ast::DestructuringKind::Sink(spread) => sink = spread.name(),
+            ast::DestructuringKind::Placeholder(_) => {}
+            ast::DestructuringKind::Normal(_) => return Ok(false),
+        }
+    }
+
+    if let Some(ident) = sink {
+        let mut rest = Dict::new();
+        for (key, value) in dict.iter() {
+            if !used.contains(key.as_str()) {
+                rest.insert(key.clone(), value.clone());
+            }
+        }
+        vm.define(ident, rest);
+    }
+
+    Ok(true)
 }
 
 impl Eval for ast::LetBinding {
@@ -1419,7 +1696,7 @@ impl Eval for ast::SetRule {
             })
             .at(target.span())?;
         let args = self.args().eval(vm)?;
-        Ok(target.set(args)?.spanned(self.span()))
+        Ok(target.set(vm, args)?.spanned(self.span()))
     }
 }
 
@@ -1461,6 +1738,33 @@ impl Eval for ast::Conditional {
     }
 }
 
+impl Eval for ast::MatchExpr {
+    type Output = Value;
+
+    #[tracing::instrument(name = "MatchExpr::eval", skip_all)]
+    fn eval(&self, vm: &mut Vm) -> SourceResult<Self::Output> {
+        let expr = self.value();
+        let value = expr.eval(vm)?;
+
+        for arm in self.arms() {
+            vm.scopes.enter();
+            let mut taken = arm.pattern().matches(vm, &value)?;
+            if let (true, Some(guard)) = (taken, arm.guard()) {
+                taken = guard.eval(vm)?.cast::<bool>().at(guard.span())?;
+            }
+
+            let output = if taken { Some(arm.body().eval(vm)) } else { None };
+            vm.scopes.exit();
+
+            if let Some(output) = output {
+                return output;
+            }
+        }
+
+        bail!(expr.span(), "no pattern matches the value");
+    }
+}
+
 impl Eval for ast::WhileLoop {
     type Output = Value;
 
@@ -1605,22 +1909,33 @@ impl Eval for ast::ModuleImport {
         let source = self.source().eval(vm)?;
         let module = import(vm, source, span)?;
 
+        if let Some(new_name) = self.new_name() {
+            vm.define(new_name, module.clone());
+        }
+
         match self.imports() {
             None => {
-                vm.scopes.top.define(module.name().clone(), module);
+                if self.new_name().is_none() {
+                    vm.scopes.top.define(module.name().clone(), module);
+                }
             }
             Some(ast::Imports::Wildcard) => {
-                for (var, value) in module.scope().iter() {
+                for (var, value) in module.exports() {
                     vm.scopes.top.define(var.clone(), value.clone());
                 }
             }
-            Some(ast::Imports::Items(idents)) => {
+            Some(ast::Imports::Items(items)) => {
                 let mut errors = vec![];
-                for ident in idents {
-                    if let Some(value) = module.scope().get(&ident) {
-                        vm.define(ident, value.clone());
-                    } else {
-                        errors.push(error!(ident.span(), "unresolved import"));
+                for item in items {
+                    let original = item.original_name();
+                    if module.scope().get(&original).is_none() {
+                        errors.push(error!(original.span(), "unresolved import"));
+                        continue;
+                    }
+
+                    match module.get(&original) {
+                        Ok(value) => vm.define(item.bound_name(), value.clone()),
+                        Err(message) => errors.push(error!(original.span(), message)),
                     }
                 }
                 if !errors.is_empty() {
@@ -1653,20 +1968,55 @@ fn import(vm: &mut Vm, source: Value, span: Span) -> SourceResult<Module> {
         v => bail!(span, "expected path or module, found {}", v.type_name()),
     };
 
-    // Load the source file.
+    // Handle package and file imports.
+    if path.starts_with('@') {
+        let spec = path.parse::<PackageSpec>().at(span)?;
+        import_package(vm, spec, span)
+    } else {
+        import_file(vm, &path, span)
+    }
+}
+
+/// Import an external package.
+fn import_package(vm: &mut Vm, spec: PackageSpec, span: Span) -> SourceResult<Module> {
+    // Find and validate the package.
     let world = vm.world();
-    let full = vm.locate(&path).at(span)?;
-    let id = world.resolve(&full).at(span)?;
+    let root = world.package(&spec).at(span)?;
+    let manifest = PackageManifest::load(world, &root).at(span)?;
+    manifest.validate(&spec).at(span)?;
+
+    // Evaluate its entrypoint.
+    let entrypoint = manifest.entrypoint(&root).at(span)?;
+    let id = world.resolve(&entrypoint).at(span)?;
+    let module = import_source(vm, id, Some(&root), span)?;
+    Ok(module.with_name(manifest.package.name))
+}
+
+/// Import a file from a path relative to the current location.
+fn import_file(vm: &mut Vm, path: &str, span: Span) -> SourceResult<Module> {
+    let full = vm.locate(path).at(span)?;
+    let id = vm.world().resolve(&full).at(span)?;
+    let package = vm.package.clone();
+    import_source(vm, id, package.as_deref(), span)
+}
 
+/// Evaluate an imported source file.
+fn import_source(
+    vm: &mut Vm,
+    id: SourceId,
+    package: Option<&Path>,
+    span: Span,
+) -> SourceResult<Module> {
     // Prevent cyclic importing.
     if vm.route.contains(id) {
         bail!(span, "cyclic import");
     }
 
     // Evaluate the file.
+    let world = vm.world();
     let source = world.source(id);
     let point = || Tracepoint::Import;
-    eval(world, vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), source)
+    eval(world, vm.route, TrackedMut::reborrow_mut(&mut vm.vt.tracer), package, source)
         .trace(world, point, span)
 }
 
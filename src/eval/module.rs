@@ -32,6 +32,12 @@ impl Module {
         }))
     }
 
+    /// Update the module's name.
+    pub fn with_name(mut self, name: impl Into<EcoString>) -> Self {
+        Arc::make_mut(&mut self.0).name = name.into();
+        self
+    }
+
     /// Update the module's scope.
     pub fn with_scope(mut self, scope: Scope) -> Self {
         Arc::make_mut(&mut self.0).scope = scope;
@@ -60,10 +66,23 @@ impl Module {
     }
 
     /// Try to access a definition in the module.
+    ///
+    /// Fails if the definition is private to the module.
     pub fn get(&self, name: &str) -> StrResult<&Value> {
-        self.scope().get(name).ok_or_else(|| {
+        let value = self.scope().get(name).ok_or_else(|| {
             eco_format!("module `{}` does not contain `{name}`", self.name())
-        })
+        })?;
+
+        if is_private(name) {
+            Err(eco_format!("`{name}` is private to module `{}`", self.name()))?;
+        }
+
+        Ok(value)
+    }
+
+    /// Iterate over the definitions that are visible outside of the module.
+    pub fn exports(&self) -> impl Iterator<Item = (&EcoString, &Value)> {
+        self.scope().iter().filter(|(name, _)| !is_private(name))
     }
 
     /// Extract the module's content.
@@ -86,3 +105,12 @@ impl PartialEq for Module {
         Arc::ptr_eq(&self.0, &other.0)
     }
 }
+
+/// Whether a definition is private to its module.
+///
+/// Definitions whose name starts with an underscore can be used freely within
+/// the module that defines them, but can't be accessed or imported from the
+/// outside.
+fn is_private(name: &str) -> bool {
+    name.starts_with('_')
+}
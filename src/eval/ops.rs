@@ -6,7 +6,9 @@ use ecow::eco_format;
 
 use super::{format_str, Regex, Value};
 use crate::diag::StrResult;
-use crate::geom::{Axes, Axis, GenAlign, Length, Numeric, PartialStroke, Rel, Smart};
+use crate::geom::{
+    Axes, Axis, GenAlign, Length, Numeric, Paint, PartialStroke, Rel, Smart,
+};
 use Value::*;
 
 /// Bail with a type mismatch error.
@@ -30,6 +32,7 @@ pub fn join(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Content(a), Str(b)) => Content(a + item!(text)(b.into())),
         (Str(a), Content(b)) => Content(item!(text)(a.into()) + b),
         (Symbol(a), Content(b)) => Content(item!(text)(a.get().into()) + b),
+        (Bytes(a), Bytes(b)) => Bytes(a + b),
         (Array(a), Array(b)) => Array(a + b),
         (Dict(a), Dict(b)) => Dict(a + b),
         (a, b) => mismatch!("cannot join {} with {}", a, b),
@@ -46,6 +49,7 @@ pub fn pos(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(v),
         Relative(v) => Relative(v),
         Fraction(v) => Fraction(v),
+        Duration(v) => Duration(v),
         v => mismatch!("cannot apply '+' to {}", v),
     })
 }
@@ -60,6 +64,7 @@ pub fn neg(value: Value) -> StrResult<Value> {
         Ratio(v) => Ratio(-v),
         Relative(v) => Relative(-v),
         Fraction(v) => Fraction(-v),
+        Duration(v) => Duration(v.checked_neg().ok_or("duration is out of range")?),
         v => mismatch!("cannot apply '-' to {}", v),
     })
 }
@@ -101,9 +106,17 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Str(a), Content(b)) => Content(item!(text)(a.into()) + b),
         (Symbol(a), Content(b)) => Content(item!(text)(a.get().into()) + b),
 
+        (Bytes(a), Bytes(b)) => Bytes(a + b),
         (Array(a), Array(b)) => Array(a + b),
         (Dict(a), Dict(b)) => Dict(a + b),
 
+        (Duration(a), Duration(b)) => {
+            Duration(a.checked_add(b).ok_or("duration is out of range")?)
+        }
+        (Datetime(a), Duration(b)) | (Duration(b), Datetime(a)) => {
+            Datetime(a.checked_add(b).ok_or("datetime is out of range")?)
+        }
+
         (Color(color), Length(thickness)) | (Length(thickness), Color(color)) => {
             Value::dynamic(PartialStroke {
                 paint: Smart::Custom(color.into()),
@@ -112,6 +125,13 @@ pub fn add(lhs: Value, rhs: Value) -> StrResult<Value> {
             })
         }
 
+        (Gradient(gradient), Length(thickness))
+        | (Length(thickness), Gradient(gradient)) => Value::dynamic(PartialStroke {
+            paint: Smart::Custom(Paint::Gradient(gradient)),
+            thickness: Smart::Custom(thickness),
+            ..PartialStroke::default()
+        }),
+
         (Dyn(a), Dyn(b)) => {
             // 1D alignments can be summed into 2D alignments.
             if let (Some(&a), Some(&b)) =
@@ -158,6 +178,19 @@ pub fn sub(lhs: Value, rhs: Value) -> StrResult<Value> {
 
         (Fraction(a), Fraction(b)) => Fraction(a - b),
 
+        (Duration(a), Duration(b)) => {
+            Duration(a.checked_sub(b).ok_or("duration is out of range")?)
+        }
+        (Datetime(a), Duration(b)) => Datetime(
+            b.checked_neg()
+                .and_then(|b| a.checked_add(b))
+                .ok_or("datetime is out of range")?,
+        ),
+        (Datetime(a), Datetime(b)) => Duration(
+            a.difference(b)
+                .ok_or("cannot subtract datetimes of different kinds")?,
+        ),
+
         (a, b) => mismatch!("cannot subtract {1} from {0}", a, b),
     })
 }
@@ -204,6 +237,13 @@ pub fn mul(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Float(a), Fraction(b)) => Fraction(a * b),
         (Ratio(a), Fraction(b)) => Fraction(a.get() * b),
 
+        (Duration(a), Int(b)) | (Int(b), Duration(a)) => {
+            Duration(a.checked_mul(b as f64).ok_or("duration is out of range")?)
+        }
+        (Duration(a), Float(b)) | (Float(b), Duration(a)) => {
+            Duration(a.checked_mul(b).ok_or("duration is out of range")?)
+        }
+
         (Str(a), Int(b)) => Str(a.repeat(b)?),
         (Int(a), Str(b)) => Str(b.repeat(a)?),
         (Array(a), Int(b)) => Array(a.repeat(b)?),
@@ -251,6 +291,14 @@ pub fn div(lhs: Value, rhs: Value) -> StrResult<Value> {
         (Fraction(a), Float(b)) => Fraction(a / b),
         (Fraction(a), Fraction(b)) => Float(a / b),
 
+        (Duration(a), Int(b)) => {
+            Duration(a.checked_div(b as f64).ok_or("duration is out of range")?)
+        }
+        (Duration(a), Float(b)) => {
+            Duration(a.checked_div(b).ok_or("duration is out of range")?)
+        }
+        (Duration(a), Duration(b)) => Float(a / b),
+
         (a, b) => mismatch!("cannot divide {} by {}", a, b),
     })
 }
@@ -265,6 +313,7 @@ fn is_zero(v: &Value) -> bool {
         Ratio(v) => v.is_zero(),
         Relative(v) => v.is_zero(),
         Fraction(v) => v.is_zero(),
+        Duration(v) => v.is_zero(),
         _ => false,
     }
 }
@@ -347,9 +396,13 @@ pub fn equal(lhs: &Value, rhs: &Value) -> bool {
         (Relative(a), Relative(b)) => a == b,
         (Fraction(a), Fraction(b)) => a == b,
         (Color(a), Color(b)) => a == b,
+        (Gradient(a), Gradient(b)) => a == b,
         (Symbol(a), Symbol(b)) => a == b,
         (Str(a), Str(b)) => a == b,
+        (Bytes(a), Bytes(b)) => a == b,
         (Label(a), Label(b)) => a == b,
+        (Datetime(a), Datetime(b)) => a == b,
+        (Duration(a), Duration(b)) => a == b,
         (Content(a), Content(b)) => a == b,
         (Array(a), Array(b)) => a == b,
         (Dict(a), Dict(b)) => a == b,
@@ -382,6 +435,8 @@ pub fn compare(lhs: &Value, rhs: &Value) -> Option<Ordering> {
         (Relative(a), Relative(b)) => a.partial_cmp(b),
         (Fraction(a), Fraction(b)) => a.partial_cmp(b),
         (Str(a), Str(b)) => a.partial_cmp(b),
+        (Datetime(a), Datetime(b)) => a.partial_cmp(b),
+        (Duration(a), Duration(b)) => a.partial_cmp(b),
 
         // Some technically different things should be comparable.
         (&Int(a), &Float(b)) => (a as f64).partial_cmp(&b),
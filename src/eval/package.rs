@@ -0,0 +1,183 @@
+//! Package specifications and manifests.
+
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use comemo::Tracked;
+use ecow::{eco_format, EcoString};
+use serde::Deserialize;
+
+use crate::diag::StrResult;
+use crate::util::PathExt;
+use crate::World;
+
+/// Identifies a package: `@namespace/name:version`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PackageSpec {
+    /// The namespace the package lives in.
+    pub namespace: EcoString,
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+}
+
+impl FromStr for PackageSpec {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(rest) = s.strip_prefix('@') else {
+            return Err("package specification must start with '@'".into());
+        };
+
+        let Some((namespace, rest)) = rest.split_once('/') else {
+            return Err("package specification is missing name".into());
+        };
+
+        if namespace.is_empty() {
+            Err("package specification is missing namespace")?;
+        } else if !is_ident(namespace) {
+            Err(eco_format!("`{namespace}` is not a valid package namespace"))?;
+        }
+
+        let Some((name, version)) = rest.split_once(':') else {
+            return Err("package specification is missing version".into());
+        };
+
+        if name.is_empty() {
+            Err("package specification is missing name")?;
+        } else if !is_ident(name) {
+            Err(eco_format!("`{name}` is not a valid package name"))?;
+        }
+
+        Ok(Self {
+            namespace: namespace.into(),
+            name: name.into(),
+            version: version.parse()?,
+        })
+    }
+}
+
+impl Display for PackageSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "@{}/{}:{}", self.namespace, self.name, self.version)
+    }
+}
+
+/// A package's version: `major.minor.patch`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Version {
+    /// The package's major version.
+    pub major: u32,
+    /// The package's minor version.
+    pub minor: u32,
+    /// The package's patch version.
+    pub patch: u32,
+}
+
+impl FromStr for Version {
+    type Err = EcoString;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split('.');
+        let mut next = |kind| {
+            let Some(part) = parts.next().filter(|part| !part.is_empty()) else {
+                return Err(eco_format!("version number is missing {kind} version"));
+            };
+            part.parse::<u32>()
+                .map_err(|_| eco_format!("`{part}` is not a valid {kind} version"))
+        };
+
+        let major = next("major")?;
+        let minor = next("minor")?;
+        let patch = next("patch")?;
+        if let Some(rest) = parts.next() {
+            Err(eco_format!("version number has unexpected fourth component: `{rest}`"))?;
+        }
+
+        Ok(Self { major, minor, patch })
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let string = EcoString::deserialize(d)?;
+        string.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// A parsed package manifest (`typst.toml`).
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+pub struct PackageManifest {
+    /// Details about the package itself.
+    pub package: PackageInfo,
+}
+
+/// The `[package]` key in the manifest.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Deserialize)]
+pub struct PackageInfo {
+    /// The name of the package within its namespace.
+    pub name: EcoString,
+    /// The package's version.
+    pub version: Version,
+    /// The path of the entrypoint into the package, relative to its root.
+    pub entrypoint: EcoString,
+}
+
+impl PackageManifest {
+    /// Load and parse the manifest of the package in the given directory.
+    pub fn load(world: Tracked<dyn World>, root: &Path) -> StrResult<Self> {
+        let path = root.join("typst.toml");
+        let buffer = world.file(&path)?;
+        let string = std::str::from_utf8(&buffer)
+            .map_err(|_| "package manifest is not valid utf-8")?;
+        toml::from_str(string).map_err(|err| {
+            eco_format!("package manifest is malformed: {}", err.message())
+        })
+    }
+
+    /// Ensure that the manifest describes the package with the given
+    /// specification.
+    pub fn validate(&self, spec: &PackageSpec) -> StrResult<()> {
+        if self.package.name != spec.name {
+            Err(eco_format!(
+                "package manifest contains mismatched name `{}`",
+                self.package.name
+            ))?;
+        }
+
+        if self.package.version != spec.version {
+            Err(eco_format!(
+                "package manifest contains mismatched version {}",
+                self.package.version
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// The full path to the package's entrypoint.
+    ///
+    /// Fails if the entrypoint lies outside of the package.
+    pub fn entrypoint(&self, root: &Path) -> StrResult<PathBuf> {
+        let path = root.join(self.package.entrypoint.as_str()).normalize();
+        if !path.starts_with(root) {
+            Err("package entrypoint must be inside of the package")?;
+        }
+        Ok(path)
+    }
+}
+
+/// Whether a string is a valid package namespace or name.
+fn is_ident(string: &str) -> bool {
+    let mut chars = string.chars();
+    chars.next().map_or(false, |c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
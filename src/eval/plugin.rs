@@ -0,0 +1,263 @@
+//! WebAssembly plugins.
+
+use std::fmt::{self, Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use ecow::{eco_format, EcoString};
+use wasmi::core::TrapCode;
+use wasmi::{AsContext, AsContextMut, Caller, Config, Engine, Linker, Module};
+
+use super::Bytes;
+use crate::diag::StrResult;
+
+/// The module from which plugins may import host functions.
+const MODULE_NAME: &str = "typst_env";
+
+/// How much fuel a plugin may consume during one call, including the start
+/// function. Executing an instruction consumes roughly one unit of fuel.
+const FUEL: u64 = 100_000_000;
+
+/// A WebAssembly plugin.
+///
+/// Plugins communicate with Typst through the minimal protocol: An exported
+/// function receives the lengths of its byte arguments as 32-bit integers,
+/// copies the concatenated arguments into its memory by calling
+/// `wasm_minimal_protocol_write_args_to_buffer` and hands its output back
+/// through `wasm_minimal_protocol_send_result_to_host`. It returns zero on
+/// success and any other value if the output is an error message.
+///
+/// Plugins are sandboxed: They can only import these two functions and
+/// therefore neither access the file system, nor the clock, nor any other
+/// source of non-determinism. Moreover, each call runs in a fresh instance of
+/// the module, so that calls can't influence each other through global state.
+/// This makes plugin calls pure and thus memoizable. Finally, each call may
+/// only consume a limited amount of fuel, so that a plugin that doesn't
+/// terminate fails instead of hanging the compiler.
+#[derive(Clone)]
+pub struct Plugin(Arc<Repr>);
+
+/// The internal representation of a plugin.
+struct Repr {
+    /// The raw WebAssembly bytes.
+    bytes: Bytes,
+    /// The engine the module was compiled with.
+    engine: Engine,
+    /// The compiled WebAssembly module.
+    module: Module,
+    /// The names of the functions the module exports.
+    functions: Vec<EcoString>,
+}
+
+/// The state available to host functions during a plugin call.
+#[derive(Default)]
+struct CallData {
+    /// The concatenated arguments of the call.
+    args: Vec<u8>,
+    /// The output the plugin sent back, if any.
+    output: Vec<u8>,
+    /// An error that occurred in a host function.
+    error: Option<EcoString>,
+}
+
+impl Plugin {
+    /// Compile and validate a WebAssembly module.
+    pub fn new(bytes: Bytes) -> StrResult<Self> {
+        let engine = Engine::new(Config::default().consume_fuel(true));
+        let module = Module::new(&engine, bytes.as_slice())
+            .map_err(|err| eco_format!("failed to load WebAssembly module ({err})"))?;
+
+        // Instantiate the module once so that modules with unsupported imports
+        // are rejected right away.
+        let mut store = store(&engine, CallData::default());
+        instantiate(&engine, &module, &mut store)?;
+
+        let functions = module
+            .exports()
+            .filter(|export| export.ty().func().is_some())
+            .map(|export| export.name().into())
+            .collect();
+
+        Ok(Self(Arc::new(Repr { bytes, engine, module, functions })))
+    }
+
+    /// The names of the functions exported by the plugin.
+    pub fn functions(&self) -> &[EcoString] {
+        &self.0.functions
+    }
+
+    /// Call the exported function with the given name.
+    pub fn call(&self, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+        call(self, name, args)
+    }
+}
+
+impl Debug for Plugin {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.pad("plugin(..)")
+    }
+}
+
+impl PartialEq for Plugin {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.bytes == other.0.bytes
+    }
+}
+
+impl Eq for Plugin {}
+
+impl Hash for Plugin {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.bytes.hash(state);
+    }
+}
+
+/// Call a plugin function in a fresh instance of the plugin.
+#[comemo::memoize]
+fn call(plugin: &Plugin, name: &str, args: Vec<Bytes>) -> StrResult<Bytes> {
+    let Repr { engine, module, .. } = &*plugin.0;
+    let data = CallData {
+        args: args.iter().flat_map(|arg| arg.iter().copied()).collect(),
+        ..CallData::default()
+    };
+
+    let mut store = store(engine, data);
+    let instance = instantiate(engine, module, &mut store)?;
+    let Some(func) = instance.get_func(&store, name) else {
+        return Err(eco_format!("plugin does not contain a function called `{name}`"));
+    };
+
+    let ty = func.ty(&store);
+    if ty.params().iter().any(|&ty| ty != wasmi::core::ValueType::I32)
+        || ty.results() != [wasmi::core::ValueType::I32]
+    {
+        Err(eco_format!(
+            "plugin function `{name}` must take 32-bit integers and return a \
+             single 32-bit integer"
+        ))?;
+    }
+
+    if ty.params().len() != args.len() {
+        Err(eco_format!(
+            "wrong number of arguments for plugin function `{name}` \
+             (expected {}, found {})",
+            ty.params().len(),
+            args.len(),
+        ))?;
+    }
+
+    let lengths = args
+        .iter()
+        .map(|arg| i32::try_from(arg.len()).map(wasmi::Value::I32))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| "plugin argument is too large")?;
+
+    let mut code = [wasmi::Value::I32(0)];
+    func.call(&mut store, &lengths, &mut code).map_err(|err| {
+        if out_of_fuel(&err) {
+            "plugin ran out of fuel".into()
+        } else {
+            eco_format!("plugin panicked: {err}")
+        }
+    })?;
+
+    let data = store.into_data();
+    if let Some(error) = data.error {
+        return Err(error);
+    }
+
+    match code[0] {
+        wasmi::Value::I32(0) => Ok(data.output.into()),
+        _ => match String::from_utf8(data.output) {
+            Ok(message) => Err(eco_format!("plugin errored with: {message}")),
+            Err(_) => Err("plugin errored and did not return valid utf-8".into()),
+        },
+    }
+}
+
+/// Instantiate a plugin module, providing the protocol's host functions.
+fn instantiate(
+    engine: &Engine,
+    module: &Module,
+    store: &mut wasmi::Store<CallData>,
+) -> StrResult<wasmi::Instance> {
+    let mut linker = Linker::new(engine);
+    linker
+        .func_wrap(
+            MODULE_NAME,
+            "wasm_minimal_protocol_write_args_to_buffer",
+            write_args_to_buffer,
+        )
+        .and_then(|linker| {
+            linker.func_wrap(
+                MODULE_NAME,
+                "wasm_minimal_protocol_send_result_to_host",
+                send_result_to_host,
+            )
+        })
+        .map_err(|err| eco_format!("failed to set up plugin environment ({err})"))?;
+
+    linker
+        .instantiate(&mut *store, module)
+        .and_then(|pre| pre.start(&mut *store))
+        .map_err(|err| {
+            if out_of_fuel(&err) {
+                "plugin ran out of fuel while starting".into()
+            } else {
+                eco_format!("failed to instantiate WebAssembly module ({err})")
+            }
+        })
+}
+
+/// Create a store for a plugin call, filled with the fuel for the call.
+fn store(engine: &Engine, data: CallData) -> wasmi::Store<CallData> {
+    let mut store = wasmi::Store::new(engine, data);
+    store
+        .add_fuel(FUEL)
+        .expect("fuel metering is enabled for plugin engines");
+    store
+}
+
+/// Whether a plugin failed because it consumed all of its fuel.
+fn out_of_fuel(err: &wasmi::Error) -> bool {
+    matches!(
+        err,
+        wasmi::Error::Trap(trap) if trap.trap_code() == Some(TrapCode::OutOfFuel)
+    )
+}
+
+/// Copy the concatenated call arguments into the plugin's memory.
+fn write_args_to_buffer(mut caller: Caller<CallData>, ptr: u32) {
+    let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) else {
+        caller.data_mut().error = Some("plugin does not export its memory".into());
+        return;
+    };
+
+    let args = std::mem::take(&mut caller.data_mut().args);
+    if memory.write(caller.as_context_mut(), ptr as usize, &args).is_err() {
+        caller.data_mut().error =
+            Some("plugin tried to write arguments out of bounds".into());
+    }
+    caller.data_mut().args = args;
+}
+
+/// Read the plugin's output from its memory.
+fn send_result_to_host(mut caller: Caller<CallData>, ptr: u32, len: u32) {
+    let Some(memory) = caller.get_export("memory").and_then(|e| e.into_memory()) else {
+        caller.data_mut().error = Some("plugin does not export its memory".into());
+        return;
+    };
+
+    // Check the bounds before copying, so that a bogus length can't make us
+    // allocate arbitrary amounts of memory.
+    let start = ptr as usize;
+    let Some(output) = start
+        .checked_add(len as usize)
+        .and_then(|end| memory.data(caller.as_context()).get(start..end))
+        .map(<[u8]>::to_vec)
+    else {
+        caller.data_mut().error = Some("plugin sent output out of bounds".into());
+        return;
+    };
+    caller.data_mut().output = output;
+}
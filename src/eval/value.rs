@@ -5,15 +5,20 @@ use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
 use ecow::eco_format;
+use serde::{Serialize, Serializer};
 use siphasher::sip128::{Hasher128, SipHasher13};
 
 use super::{
-    cast_to_value, format_str, ops, Args, Array, Cast, CastInfo, Content, Dict, Func,
-    Label, Module, Str, Symbol,
+    cast_to_value, format_str, ops, Args, Array, Bytes, Cast, CastInfo, Content,
+    Datetime, Dict, Duration, Func, Label, Module, Regex, Str, Symbol,
 };
 use crate::diag::StrResult;
-use crate::geom::{Abs, Angle, Color, Em, Fr, Length, Ratio, Rel};
-use crate::model::Styles;
+use crate::doc::Meta;
+use crate::geom::{
+    Abs, Angle, Axes, Color, DashLength, DashPattern, Dir, Em, Fr, GenAlign, Gradient,
+    Length, PartialStroke, Ratio, Rel,
+};
+use crate::model::{Location, Selector, Styles};
 use crate::syntax::{ast, Span};
 
 /// A computational value.
@@ -42,12 +47,20 @@ pub enum Value {
     Fraction(Fr),
     /// A color value: `#f79143ff`.
     Color(Color),
+    /// A gradient value: `gradient.linear(red, blue)`.
+    Gradient(Gradient),
     /// A symbol: `arrow.l`.
     Symbol(Symbol),
     /// A string: `"string"`.
     Str(Str),
+    /// Raw bytes: `bytes((1, 2, 3))`.
+    Bytes(Bytes),
     /// A label: `<intro>`.
     Label(Label),
+    /// A datetime: `datetime(year: 2023, month: 5, day: 21)`.
+    Datetime(Datetime),
+    /// A duration: `duration(days: 3, hours: 2)`.
+    Duration(Duration),
     /// A content value: `[*Hi* there]`.
     Content(Content),
     // Content styles.
@@ -101,9 +114,13 @@ impl Value {
             Self::Relative(_) => Rel::<Length>::TYPE_NAME,
             Self::Fraction(_) => Fr::TYPE_NAME,
             Self::Color(_) => Color::TYPE_NAME,
+            Self::Gradient(_) => Gradient::TYPE_NAME,
             Self::Symbol(_) => Symbol::TYPE_NAME,
             Self::Str(_) => Str::TYPE_NAME,
+            Self::Bytes(_) => Bytes::TYPE_NAME,
             Self::Label(_) => Label::TYPE_NAME,
+            Self::Datetime(_) => Datetime::TYPE_NAME,
+            Self::Duration(_) => Duration::TYPE_NAME,
             Self::Content(_) => Content::TYPE_NAME,
             Self::Styles(_) => Styles::TYPE_NAME,
             Self::Array(_) => Array::TYPE_NAME,
@@ -115,6 +132,48 @@ impl Value {
         }
     }
 
+    /// The names of all types that [`type_name`](Self::type_name) can return,
+    /// except for those of dynamic values defined by the standard library.
+    /// The standard library lists these in its `library_types` lang item.
+    pub const TYPE_NAMES: &'static [&'static str] = &[
+        "none",
+        "auto",
+        bool::TYPE_NAME,
+        i64::TYPE_NAME,
+        f64::TYPE_NAME,
+        Length::TYPE_NAME,
+        Angle::TYPE_NAME,
+        Ratio::TYPE_NAME,
+        Rel::<Length>::TYPE_NAME,
+        Fr::TYPE_NAME,
+        Color::TYPE_NAME,
+        Gradient::TYPE_NAME,
+        Symbol::TYPE_NAME,
+        Str::TYPE_NAME,
+        Bytes::TYPE_NAME,
+        Label::TYPE_NAME,
+        Datetime::TYPE_NAME,
+        Duration::TYPE_NAME,
+        Content::TYPE_NAME,
+        Styles::TYPE_NAME,
+        Array::TYPE_NAME,
+        Dict::TYPE_NAME,
+        Func::TYPE_NAME,
+        Args::TYPE_NAME,
+        Module::TYPE_NAME,
+        // Dynamic values.
+        GenAlign::TYPE_NAME,
+        Axes::<GenAlign>::TYPE_NAME,
+        Dir::TYPE_NAME,
+        PartialStroke::TYPE_NAME,
+        DashLength::TYPE_NAME,
+        DashPattern::TYPE_NAME,
+        Regex::TYPE_NAME,
+        Location::TYPE_NAME,
+        Selector::TYPE_NAME,
+        Meta::TYPE_NAME,
+    ];
+
     /// Try to cast the value into a specific type.
     pub fn cast<T: Cast>(self) -> StrResult<T> {
         T::cast(self)
@@ -127,6 +186,7 @@ impl Value {
             Self::Dict(dict) => dict.at(field).cloned(),
             Self::Content(content) => content.at(field),
             Self::Module(module) => module.get(field).cloned(),
+            Self::Func(func) => func.get(field).cloned(),
             v => Err(eco_format!("cannot access fields on type {}", v.type_name())),
         }
     }
@@ -183,9 +243,13 @@ impl Debug for Value {
             Self::Relative(v) => Debug::fmt(v, f),
             Self::Fraction(v) => Debug::fmt(v, f),
             Self::Color(v) => Debug::fmt(v, f),
+            Self::Gradient(v) => Debug::fmt(v, f),
             Self::Symbol(v) => Debug::fmt(v, f),
             Self::Str(v) => Debug::fmt(v, f),
+            Self::Bytes(v) => Debug::fmt(v, f),
             Self::Label(v) => Debug::fmt(v, f),
+            Self::Datetime(v) => Debug::fmt(v, f),
+            Self::Duration(v) => Debug::fmt(v, f),
             Self::Content(v) => Debug::fmt(v, f),
             Self::Styles(v) => Debug::fmt(v, f),
             Self::Array(v) => Debug::fmt(v, f),
@@ -225,9 +289,13 @@ impl Hash for Value {
             Self::Relative(v) => v.hash(state),
             Self::Fraction(v) => v.hash(state),
             Self::Color(v) => v.hash(state),
+            Self::Gradient(v) => v.hash(state),
             Self::Symbol(v) => v.hash(state),
             Self::Str(v) => v.hash(state),
+            Self::Bytes(v) => v.hash(state),
             Self::Label(v) => v.hash(state),
+            Self::Datetime(v) => v.hash(state),
+            Self::Duration(v) => v.hash(state),
             Self::Content(v) => v.hash(state),
             Self::Styles(v) => v.hash(state),
             Self::Array(v) => v.hash(state),
@@ -240,6 +308,25 @@ impl Hash for Value {
     }
 }
 
+impl Serialize for Value {
+    /// Serializes primitive and collection values as their natural
+    /// counterparts and all other values as their representation.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::None => serializer.serialize_none(),
+            Self::Bool(v) => serializer.serialize_bool(*v),
+            Self::Int(v) => serializer.serialize_i64(*v),
+            Self::Float(v) => serializer.serialize_f64(*v),
+            Self::Str(v) => v.serialize(serializer),
+            Self::Bytes(v) => v.serialize(serializer),
+            Self::Content(v) => v.serialize(serializer),
+            Self::Array(v) => v.serialize(serializer),
+            Self::Dict(v) => v.serialize(serializer),
+            _ => serializer.serialize_str(&eco_format!("{self:?}")),
+        }
+    }
+}
+
 /// A dynamic value.
 #[derive(Clone, Hash)]
 #[allow(clippy::derived_hash_with_manual_eq)]
@@ -390,13 +477,17 @@ primitive! { Rel<Length>:  "relative length",
 }
 primitive! { Fr: "fraction", Fraction }
 primitive! { Color: "color", Color }
+primitive! { Gradient: "gradient", Gradient }
 primitive! { Symbol: "symbol", Symbol }
 primitive! {
     Str: "string",
     Str,
     Symbol(symbol) => symbol.get().into()
 }
+primitive! { Bytes: "bytes", Bytes }
 primitive! { Label: "label", Label }
+primitive! { Datetime: "datetime", Datetime }
+primitive! { Duration: "duration", Duration }
 primitive! { Content: "content",
     Content,
     None => Content::empty(),
@@ -2,6 +2,8 @@
 
 mod pdf;
 mod render;
+mod svg;
 
 pub use self::pdf::pdf;
 pub use self::render::render;
+pub use self::svg::svg;
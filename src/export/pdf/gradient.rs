@@ -0,0 +1,201 @@
+use std::fmt::Write;
+
+use pdf_writer::types::FunctionShadingType;
+use pdf_writer::{Finish, Ref};
+
+use super::{PdfContext, RefExt};
+use crate::geom::{Color, ConicGradient, Gradient, Ratio, Transform};
+
+/// A gradient as it is placed on a page.
+///
+/// The same gradient needs a separate pattern for each placement since PDF
+/// patterns are positioned relative to the page.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PdfGradient {
+    /// The transform from the gradient's unit square to the page.
+    pub transform: Transform,
+    /// The aspect ratio (width divided by height) of the painted box.
+    pub aspect_ratio: Ratio,
+    /// The gradient itself.
+    pub gradient: Gradient,
+}
+
+/// Write all gradients as shading patterns.
+#[tracing::instrument(skip_all)]
+pub fn write_gradients(ctx: &mut PdfContext) {
+    for PdfGradient { transform, aspect_ratio, gradient } in
+        ctx.gradient_map.items().cloned().collect::<Vec<_>>()
+    {
+        let function = match &gradient {
+            Gradient::Conic(conic) => write_conic_function(ctx, conic, aspect_ratio),
+            _ => write_stitching_function(ctx, gradient.stops()),
+        };
+
+        let pattern_ref = ctx.alloc.bump();
+        ctx.gradient_refs.push(pattern_ref);
+
+        let mut pattern = ctx.writer.shading_pattern(pattern_ref);
+        pattern.matrix(transform_to_array(transform));
+
+        let mut shading = pattern.function_shading();
+        match &gradient {
+            Gradient::Linear(linear) => {
+                let axis = linear.axis(aspect_ratio.get());
+                shading.shading_type(FunctionShadingType::Axial);
+                shading.coords(axis.map(|v| v as f32));
+                shading.extend([true, true]);
+            }
+            Gradient::Radial(radial) => {
+                let x = radial.center.x.get() as f32;
+                let y = radial.center.y.get() as f32;
+                let r = radial.radius.get() as f32;
+                shading.shading_type(FunctionShadingType::Radial);
+                shading.coords([x, y, 0.0, x, y, r]);
+                shading.extend([true, true]);
+            }
+            Gradient::Conic(_) => {
+                shading.shading_type(FunctionShadingType::Function);
+                shading.domain([0.0, 1.0, 0.0, 1.0]);
+            }
+        }
+
+        shading.color_space().srgb();
+        shading.function(function);
+        shading.finish();
+        pattern.finish();
+    }
+}
+
+/// Write a function that interpolates linearly between the color stops.
+fn write_stitching_function(ctx: &mut PdfContext, stops: &[(Color, Ratio)]) -> Ref {
+    let mut functions = vec![];
+    let mut bounds = vec![];
+    let mut encode = vec![];
+
+    for window in stops.windows(2) {
+        let (first, second) = (window[0], window[1]);
+        let id = ctx.alloc.bump();
+        ctx.writer
+            .exponential_function(id)
+            .domain([0.0, 1.0])
+            .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+            .c0(to_rgb(first.0))
+            .c1(to_rgb(second.0))
+            .n(1.0);
+
+        functions.push(id);
+        bounds.push(second.1.get() as f32);
+        encode.extend([0.0, 1.0]);
+    }
+
+    // The last stop is always at 100% and thus not a bound.
+    bounds.pop();
+
+    let id = ctx.alloc.bump();
+    ctx.writer
+        .stitching_function(id)
+        .domain([0.0, 1.0])
+        .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0])
+        .functions(functions)
+        .bounds(bounds)
+        .encode(encode);
+
+    id
+}
+
+/// Write a PostScript function that computes the color of a conic gradient
+/// for a point in the unit square.
+///
+/// PDF has no native conic shadings, so the sweep angle is computed in the
+/// function itself.
+fn write_conic_function(
+    ctx: &mut PdfContext,
+    conic: &ConicGradient,
+    aspect_ratio: Ratio,
+) -> Ref {
+    let cx = conic.center.x.get();
+    let cy = conic.center.y.get();
+    let angle = conic.angle.to_deg().rem_euclid(360.0);
+
+    // Compute the angle around the center, corrected for the aspect ratio,
+    // and map it to an offset between zero and one. The tiny summand avoids
+    // an undefined result right at the center.
+    let mut code = String::from("{ ");
+    write!(
+        code,
+        "{cy} sub exch {cx} sub {} mul 0.000001 add atan {angle} sub \
+         dup 0 lt {{ 360 add }} if 360 div ",
+        aspect_ratio.get(),
+    )
+    .unwrap();
+
+    // Select the segment the offset falls into and interpolate its colors.
+    let segments = conic.stops.windows(2).collect::<Vec<_>>();
+    for (i, window) in segments.iter().enumerate() {
+        let last = i + 1 == segments.len();
+        if !last {
+            write!(code, "dup {} le {{ ", window[1].1.get()).unwrap();
+        }
+
+        let (start, a) = window[0];
+        let (end, b) = window[1];
+        let [r0, g0, b0] = to_rgb(start);
+        let [r1, g1, b1] = to_rgb(end);
+        let len = b.get() - a.get();
+        if len > 0.0 {
+            write!(
+                code,
+                "{} sub {len} div dup {} mul {r0} add exch dup {} mul {g0} add exch \
+                 {} mul {b0} add ",
+                a.get(),
+                r1 - r0,
+                g1 - g0,
+                b1 - b0,
+            )
+            .unwrap();
+        } else {
+            write!(code, "pop {r1} {g1} {b1} ").unwrap();
+        }
+
+        if !last {
+            code.push_str("} { ");
+        }
+    }
+
+    for _ in 1..segments.len() {
+        code.push_str("} ifelse ");
+    }
+
+    code.push('}');
+
+    let id = ctx.alloc.bump();
+    ctx.writer
+        .post_script_function(id, code.as_bytes())
+        .domain([0.0, 1.0, 0.0, 1.0])
+        .range([0.0, 1.0, 0.0, 1.0, 0.0, 1.0]);
+
+    id
+}
+
+/// Convert a color to its sRGB components.
+///
+/// The alpha component is dropped: Shadings are always opaque in PDF and
+/// transparent stops would need a separate soft mask, which is not supported
+/// yet. The raster and SVG backends do respect the alpha of stops.
+fn to_rgb(color: Color) -> [f32; 3] {
+    let c = color.to_rgba();
+    [c.r, c.g, c.b].map(|v| v as f32 / 255.0)
+}
+
+/// Convert a transform to a PDF matrix.
+fn transform_to_array(transform: Transform) -> [f32; 6] {
+    let Transform { sx, ky, kx, sy, tx, ty } = transform;
+    [
+        sx.get() as f32,
+        ky.get() as f32,
+        kx.get() as f32,
+        sy.get() as f32,
+        tx.to_pt() as f32,
+        ty.to_pt() as f32,
+    ]
+}
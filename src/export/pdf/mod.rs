@@ -1,6 +1,7 @@
 //! Exporting into PDF documents.
 
 mod font;
+mod gradient;
 mod image;
 mod outline;
 mod page;
@@ -13,8 +14,10 @@ use pdf_writer::types::Direction;
 use pdf_writer::{Finish, Name, PdfWriter, Ref, TextStr};
 use xmp_writer::{LangId, RenditionClass, XmpWriter};
 
+use self::gradient::PdfGradient;
 use self::page::Page;
 use crate::doc::{Document, Lang};
+use crate::eval::Datetime;
 use crate::font::Font;
 use crate::geom::{Abs, Dir, Em};
 use crate::image::Image;
@@ -29,6 +32,7 @@ pub fn pdf(document: &Document) -> Vec<u8> {
     page::construct_pages(&mut ctx, &document.pages);
     font::write_fonts(&mut ctx);
     image::write_images(&mut ctx);
+    gradient::write_gradients(&mut ctx);
     page::write_page_tree(&mut ctx);
     write_catalog(&mut ctx);
     ctx.writer.finish()
@@ -49,9 +53,11 @@ pub struct PdfContext<'a> {
     page_tree_ref: Ref,
     font_refs: Vec<Ref>,
     image_refs: Vec<Ref>,
+    gradient_refs: Vec<Ref>,
     page_refs: Vec<Ref>,
     font_map: Remapper<Font>,
     image_map: Remapper<Image>,
+    gradient_map: Remapper<PdfGradient>,
     glyph_sets: HashMap<Font, HashSet<u16>>,
     languages: HashMap<Lang, usize>,
 }
@@ -71,8 +77,10 @@ impl<'a> PdfContext<'a> {
             page_refs: vec![],
             font_refs: vec![],
             image_refs: vec![],
+            gradient_refs: vec![],
             font_map: Remapper::new(),
             image_map: Remapper::new(),
+            gradient_map: Remapper::new(),
             glyph_sets: HashMap::new(),
             languages: HashMap::new(),
         }
@@ -111,6 +119,10 @@ fn write_catalog(ctx: &mut PdfContext) {
         xmp.creator(authors.iter().map(|s| s.as_str()));
     }
     info.creator(TextStr("Typst"));
+    if let Some(date) = ctx.document.date.and_then(pdf_date) {
+        info.creation_date(date);
+        info.modified_date(date);
+    }
     info.finish();
     xmp.creator_tool("Typst");
     xmp.num_pages(ctx.document.pages.len() as u32);
@@ -226,3 +238,34 @@ impl RefExt for Ref {
         prev
     }
 }
+
+/// Convert a datetime to a PDF date.
+///
+/// Returns `None` for negative years, which PDF dates cannot represent.
+fn pdf_date(datetime: Datetime) -> Option<pdf_writer::Date> {
+    let year = datetime.year().filter(|&y| y >= 0)? as u16;
+
+    let mut pdf_date = pdf_writer::Date::new(year);
+
+    if let Some(month) = datetime.month() {
+        pdf_date = pdf_date.month(month);
+    }
+
+    if let Some(day) = datetime.day() {
+        pdf_date = pdf_date.day(day);
+    }
+
+    if let Some(hour) = datetime.hour() {
+        pdf_date = pdf_date.hour(hour);
+    }
+
+    if let Some(minute) = datetime.minute() {
+        pdf_date = pdf_date.minute(minute);
+    }
+
+    if let Some(second) = datetime.second() {
+        pdf_date = pdf_date.second(second);
+    }
+
+    Some(pdf_date)
+}
@@ -11,7 +11,7 @@ use crate::util::NonZeroExt;
 #[tracing::instrument(skip_all)]
 pub fn write_outline(ctx: &mut PdfContext) -> Option<Ref> {
     let mut tree: Vec<HeadingNode> = vec![];
-    for heading in ctx.introspector.query(&item!(heading_func).select()) {
+    for heading in ctx.introspector.query(&item!(heading_func).clone().select()) {
         let leaf = HeadingNode::leaf(heading);
         if let Some(last) = tree.last_mut() {
             if last.try_insert(leaf.clone(), NonZeroUsize::ONE) {
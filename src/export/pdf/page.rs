@@ -1,16 +1,17 @@
-use ecow::eco_format;
+use ecow::{eco_format, EcoString};
 use pdf_writer::types::{
     ActionType, AnnotationType, ColorSpaceOperand, LineCapStyle, LineJoinStyle,
 };
 use pdf_writer::writers::ColorSpace;
 use pdf_writer::{Content, Filter, Finish, Name, Rect, Ref, Str};
 
+use super::gradient::PdfGradient;
 use super::{deflate, AbsExt, EmExt, PdfContext, RefExt, D65_GRAY, SRGB};
 use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::font::Font;
 use crate::geom::{
-    self, Abs, Color, Em, Geometry, LineCap, LineJoin, Numeric, Paint, Point, Ratio,
-    Shape, Size, Stroke, Transform,
+    self, Abs, Color, Em, Geometry, Gradient, LineCap, LineJoin, Numeric, Paint, Point,
+    Ratio, Relative, Shape, Size, Stroke, Transform,
 };
 use crate::image::Image;
 
@@ -52,6 +53,10 @@ pub fn construct_page(ctx: &mut PdfContext, frame: &Frame) {
         ty: size.y,
     });
 
+    // The page is the outermost container for gradients.
+    ctx.state.container_transform = ctx.state.transform;
+    ctx.state.size = size;
+
     // Encode the page into the content stream.
     write_frame(&mut ctx, frame);
 
@@ -98,6 +103,14 @@ pub fn write_page_tree(ctx: &mut PdfContext) {
     }
 
     images.finish();
+
+    let mut patterns = resources.patterns();
+    for (gradient_ref, gr) in ctx.gradient_map.pdf_indices(&ctx.gradient_refs) {
+        let name = eco_format!("Gr{}", gr);
+        patterns.pair(Name(name.as_bytes()), gradient_ref);
+    }
+
+    patterns.finish();
     resources.finish();
     pages.finish();
 }
@@ -181,6 +194,11 @@ struct PageContext<'a, 'b> {
 #[derive(Debug, Default, Clone)]
 struct State {
     transform: Transform,
+    /// The transform of the closest container, for gradients relative to
+    /// their parent.
+    container_transform: Transform,
+    /// The size of the closest container.
+    size: Size,
     font: Option<(Font, Abs)>,
     fill: Option<Paint>,
     fill_space: Option<Name<'static>>,
@@ -221,23 +239,29 @@ impl PageContext<'_, '_> {
         }
     }
 
-    fn set_fill(&mut self, fill: &Paint) {
-        if self.state.fill.as_ref() != Some(fill) {
+    fn set_fill(&mut self, fill: &Paint, on_text: bool, bbox: (Point, Size)) {
+        // Gradients depend on the painted item, so they are always set anew.
+        if self.state.fill.as_ref() != Some(fill) || matches!(fill, Paint::Gradient(_)) {
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = fill;
-            match color {
-                Color::Luma(c) => {
+            match fill {
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_fill_color_space(D65_GRAY);
                     self.content.set_fill_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(Color::Rgba(c)) => {
                     self.set_fill_color_space(SRGB);
                     self.content.set_fill_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_fill_color_space();
                     self.content.set_fill_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
+                Paint::Gradient(gradient) => {
+                    let name = self.gradient_name(gradient, on_text, bbox);
+                    self.reset_fill_color_space();
+                    self.content.set_fill_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_fill_pattern(None, Name(name.as_bytes()));
+                }
             }
             self.state.fill = Some(fill.clone());
         }
@@ -254,8 +278,11 @@ impl PageContext<'_, '_> {
         self.state.fill_space = None;
     }
 
-    fn set_stroke(&mut self, stroke: &Stroke) {
-        if self.state.stroke.as_ref() != Some(stroke) {
+    fn set_stroke(&mut self, stroke: &Stroke, bbox: (Point, Size)) {
+        // Gradients depend on the painted item, so they are always set anew.
+        if self.state.stroke.as_ref() != Some(stroke)
+            || matches!(stroke.paint, Paint::Gradient(_))
+        {
             let Stroke {
                 paint,
                 thickness,
@@ -266,20 +293,25 @@ impl PageContext<'_, '_> {
             } = stroke;
 
             let f = |c| c as f32 / 255.0;
-            let Paint::Solid(color) = paint;
-            match color {
-                Color::Luma(c) => {
+            match paint {
+                Paint::Solid(Color::Luma(c)) => {
                     self.set_stroke_color_space(D65_GRAY);
                     self.content.set_stroke_gray(f(c.0));
                 }
-                Color::Rgba(c) => {
+                Paint::Solid(Color::Rgba(c)) => {
                     self.set_stroke_color_space(SRGB);
                     self.content.set_stroke_color([f(c.r), f(c.g), f(c.b)]);
                 }
-                Color::Cmyk(c) => {
+                Paint::Solid(Color::Cmyk(c)) => {
                     self.reset_stroke_color_space();
                     self.content.set_stroke_cmyk(f(c.c), f(c.m), f(c.y), f(c.k));
                 }
+                Paint::Gradient(gradient) => {
+                    let name = self.gradient_name(gradient, false, bbox);
+                    self.reset_stroke_color_space();
+                    self.content.set_stroke_color_space(ColorSpaceOperand::Pattern);
+                    self.content.set_stroke_pattern(None, Name(name.as_bytes()));
+                }
             }
 
             self.content.set_line_width(thickness.to_f32());
@@ -316,6 +348,37 @@ impl PageContext<'_, '_> {
     fn reset_stroke_color_space(&mut self) {
         self.state.stroke_space = None;
     }
+
+    /// Register a gradient as it is placed for the item with the given
+    /// bounding box and return the name of its pattern.
+    fn gradient_name(
+        &mut self,
+        gradient: &Gradient,
+        on_text: bool,
+        (pos, size): (Point, Size),
+    ) -> EcoString {
+        let (transform, size) = match gradient.unwrap_relative(on_text) {
+            Relative::Self_ => {
+                let translate = Transform::translate(pos.x, pos.y);
+                (self.state.transform.pre_concat(translate), size)
+            }
+            Relative::Parent => (self.state.container_transform, self.state.size),
+        };
+
+        // Avoid a degenerate pattern matrix for boxes without an extent.
+        let size = size.map(|v| v.max(Abs::pt(1.0)));
+        let scale =
+            Transform::scale(Ratio::new(size.x.to_pt()), Ratio::new(size.y.to_pt()));
+
+        let pdf_gradient = PdfGradient {
+            transform: transform.pre_concat(scale),
+            aspect_ratio: Ratio::new(size.x / size.y),
+            gradient: gradient.clone(),
+        };
+
+        self.parent.gradient_map.insert(pdf_gradient.clone());
+        eco_format!("Gr{}", self.parent.gradient_map.map(pdf_gradient))
+    }
 }
 
 /// Encode a frame into the content stream.
@@ -344,6 +407,8 @@ fn write_group(ctx: &mut PageContext, pos: Point, group: &GroupItem) {
 
     ctx.save_state();
     ctx.transform(translation.pre_concat(group.transform));
+    ctx.state.container_transform = ctx.state.transform;
+    ctx.state.size = group.frame.size();
 
     if group.clips {
         let size = group.frame.size();
@@ -370,7 +435,9 @@ fn write_text(ctx: &mut PageContext, x: f32, y: f32, text: &TextItem) {
         .or_default()
         .extend(text.glyphs.iter().map(|g| g.id));
 
-    ctx.set_fill(&text.fill);
+    let (pos, size) = text.bbox();
+    let origin = Point::new(Abs::pt(x.into()), Abs::pt(y.into()));
+    ctx.set_fill(&text.fill, true, (origin + pos, size));
     ctx.set_font(&text.font, text.size);
     ctx.content.begin_text();
 
@@ -421,12 +488,16 @@ fn write_shape(ctx: &mut PageContext, x: f32, y: f32, shape: &Shape) {
         return;
     }
 
+    let (pos, size) = shape.geometry.bbox();
+    let origin = Point::new(Abs::pt(x.into()), Abs::pt(y.into()));
+    let bbox = (origin + pos, size);
+
     if let Some(fill) = &shape.fill {
-        ctx.set_fill(fill);
+        ctx.set_fill(fill, false, bbox);
     }
 
     if let Some(stroke) = &shape.stroke {
-        ctx.set_stroke(stroke);
+        ctx.set_stroke(stroke, bbox);
     }
 
     match shape.geometry {
@@ -11,8 +11,8 @@ use usvg::{FitTo, NodeExt};
 
 use crate::doc::{Frame, FrameItem, GroupItem, Meta, TextItem};
 use crate::geom::{
-    self, Abs, Color, Geometry, LineCap, LineJoin, Paint, PathItem, Shape, Size, Stroke,
-    Transform,
+    self, Abs, Color, Geometry, Gradient, LineCap, LineJoin, Paint, PathItem, Point,
+    Ratio, Relative, Shape, Size, Stroke, Transform,
 };
 use crate::image::{DecodedImage, Image};
 
@@ -29,16 +29,28 @@ pub fn render(frame: &Frame, pixel_per_pt: f32, fill: Color) -> sk::Pixmap {
     canvas.fill(fill.into());
 
     let ts = sk::Transform::from_scale(pixel_per_pt, pixel_per_pt);
-    render_frame(&mut canvas, ts, None, frame);
+    let container = Container { ts, size };
+    render_frame(&mut canvas, ts, None, container, frame);
 
     canvas
 }
 
+/// The closest container of the rendered items, for gradients relative to
+/// their parent.
+#[derive(Copy, Clone)]
+struct Container {
+    /// The transform of the container.
+    ts: sk::Transform,
+    /// The size of the container.
+    size: Size,
+}
+
 /// Render a frame into the canvas.
 fn render_frame(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
+    container: Container,
     frame: &Frame,
 ) {
     for (pos, item) in frame.items() {
@@ -51,10 +63,10 @@ fn render_frame(
                 render_group(canvas, ts, mask, group);
             }
             FrameItem::Text(text) => {
-                render_text(canvas, ts, mask, text);
+                render_text(canvas, ts, mask, container, text);
             }
             FrameItem::Shape(shape, _) => {
-                render_shape(canvas, ts, mask, shape);
+                render_shape(canvas, ts, mask, container, shape);
             }
             FrameItem::Image(image, size, _) => {
                 render_image(canvas, ts, mask, image, *size);
@@ -108,7 +120,8 @@ fn render_group(
         }
     }
 
-    render_frame(canvas, ts, mask, &group.frame);
+    let container = Container { ts, size: group.frame.size() };
+    render_frame(canvas, ts, mask, container, &group.frame);
 }
 
 /// Render a text run into the canvas.
@@ -116,17 +129,24 @@ fn render_text(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
+    container: Container,
     text: &TextItem,
 ) {
+    let (pos, size) = text.bbox();
     let mut x = 0.0;
     for glyph in &text.glyphs {
         let id = GlyphId(glyph.id);
         let offset = x + glyph.x_offset.at(text.size).to_f32();
         let ts = ts.pre_translate(offset, 0.0);
 
+        // The bounding box of the whole run, as seen from the glyph.
+        let bbox = (pos - Point::with_x(Abs::pt(offset.into())), size);
+
         render_svg_glyph(canvas, ts, mask, text, id)
             .or_else(|| render_bitmap_glyph(canvas, ts, mask, text, id))
-            .or_else(|| render_outline_glyph(canvas, ts, mask, text, id));
+            .or_else(|| {
+                render_outline_glyph(canvas, ts, mask, container, text, id, bbox)
+            });
 
         x += glyph.x_advance.at(text.size).to_f32();
     }
@@ -256,32 +276,51 @@ fn render_outline_glyph(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
+    container: Container,
     text: &TextItem,
     id: GlyphId,
+    (pos, size): (Point, Size),
 ) -> Option<()> {
     let ppem = text.size.to_f32() * ts.sy;
 
     // Render a glyph directly as a path. This only happens when the fast glyph
-    // rasterization can't be used due to very large text size or weird
-    // scale/skewing transforms.
-    if ppem > 100.0 || ts.kx != 0.0 || ts.ky != 0.0 || ts.sx != ts.sy {
+    // rasterization can't be used due to very large text size, weird
+    // scale/skewing transforms or a gradient fill.
+    if ppem > 100.0
+        || ts.kx != 0.0
+        || ts.ky != 0.0
+        || ts.sx != ts.sy
+        || matches!(text.fill, Paint::Gradient(_))
+    {
         let path = {
             let mut builder = WrappedPathBuilder(sk::PathBuilder::new());
             text.font.ttf().outline_glyph(id, &mut builder)?;
             builder.0.finish()?
         };
 
-        let paint = (&text.fill).into();
-        let rule = sk::FillRule::default();
-
         // Flip vertically because font design coordinate
         // system is Y-up.
         let scale = text.size.to_f32() / text.font.units_per_em() as f32;
-        let ts = ts.pre_scale(scale, -scale);
-        canvas.fill_path(&path, &paint, rule, ts, mask)?;
+        let fill_ts = ts.pre_scale(scale, -scale);
+
+        let mut storage = None;
+        let bbox_ts = ts.pre_translate(pos.x.to_f32(), pos.y.to_f32());
+        let paint = to_sk_paint(
+            &text.fill,
+            fill_ts,
+            (bbox_ts, size),
+            container,
+            true,
+            &mut storage,
+        );
+
+        let rule = sk::FillRule::default();
+        canvas.fill_path(&path, &paint, rule, fill_ts, mask)?;
         return Some(());
     }
 
+    let Paint::Solid(color) = text.fill else { return None };
+
     // Rasterize the glyph with `pixglyph`.
     // Try to retrieve a prepared glyph or prepare it from scratch if it
     // doesn't exist, yet.
@@ -294,7 +333,6 @@ fn render_outline_glyph(
         let mw = bitmap.width;
         let mh = bitmap.height;
 
-        let Paint::Solid(color) = text.fill;
         let c = color.to_rgba();
 
         // Pad the pixmap with 1 pixel in each dimension so that we do
@@ -332,7 +370,6 @@ fn render_outline_glyph(
         let bottom = top + mh;
 
         // Premultiply the text color.
-        let Paint::Solid(color) = text.fill;
         let c = color.to_rgba();
         let color = sk::ColorU8::from_rgba(c.r, c.g, c.b, 255).premultiply().get();
 
@@ -366,6 +403,7 @@ fn render_shape(
     canvas: &mut sk::Pixmap,
     ts: sk::Transform,
     mask: Option<&sk::ClipMask>,
+    container: Container,
     shape: &Shape,
 ) -> Option<()> {
     let path = match shape.geometry {
@@ -383,8 +421,12 @@ fn render_shape(
         Geometry::Path(ref path) => convert_path(path)?,
     };
 
+    let (pos, size) = shape.geometry.bbox();
+    let bbox = (ts.pre_translate(pos.x.to_f32(), pos.y.to_f32()), size);
+    let mut storage = None;
+
     if let Some(fill) = &shape.fill {
-        let mut paint: sk::Paint = fill.into();
+        let mut paint = to_sk_paint(fill, ts, bbox, container, false, &mut storage);
         if matches!(shape.geometry, Geometry::Rect(_)) {
             paint.anti_alias = false;
         }
@@ -419,7 +461,7 @@ fn render_shape(
 
                 sk::StrokeDash::new(dash_array, pattern.phase.to_f32())
             });
-            let paint = paint.into();
+            let paint = to_sk_paint(paint, ts, bbox, container, false, &mut storage);
             let stroke = sk::Stroke {
                 width,
                 line_cap: line_cap.into(),
@@ -541,14 +583,114 @@ impl From<Transform> for sk::Transform {
     }
 }
 
-impl From<&Paint> for sk::Paint<'static> {
-    fn from(paint: &Paint) -> Self {
-        let mut sk_paint = sk::Paint::default();
-        let Paint::Solid(color) = *paint;
-        sk_paint.set_color(color.into());
-        sk_paint.anti_alias = true;
-        sk_paint
+/// Convert a Typst paint into a tiny-skia paint.
+///
+/// The paint is used to fill a path at `fill_ts`. The bounding box of the
+/// painted item is given by its transform and size. Gradients are placed
+/// relative to the bounding box or to the container.
+///
+/// Since tiny-skia has no conic gradients, they are rendered into a texture,
+/// which is kept alive in `storage` as the paint borrows it.
+fn to_sk_paint<'a>(
+    paint: &Paint,
+    fill_ts: sk::Transform,
+    (bbox_ts, size): (sk::Transform, Size),
+    container: Container,
+    on_text: bool,
+    storage: &'a mut Option<Arc<sk::Pixmap>>,
+) -> sk::Paint<'a> {
+    let mut sk_paint = sk::Paint::default();
+    sk_paint.anti_alias = true;
+
+    let gradient = match paint {
+        Paint::Solid(color) => {
+            sk_paint.set_color((*color).into());
+            return sk_paint;
+        }
+        Paint::Gradient(gradient) => gradient,
+    };
+
+    let (ts, size) = match gradient.unwrap_relative(on_text) {
+        Relative::Self_ => (bbox_ts, size),
+        Relative::Parent => (container.ts, container.size),
+    };
+
+    // Map the gradient's unit square into the coordinate system of the fill.
+    // Boxes without an extent are enlarged to avoid a degenerate transform.
+    let size = size.map(|v| v.max(Abs::pt(1.0)));
+    let unit_ts = fill_ts
+        .invert()
+        .unwrap_or_default()
+        .pre_concat(ts)
+        .pre_scale(size.x.to_f32(), size.y.to_f32());
+    let aspect_ratio = size.x / size.y;
+
+    let stops = gradient
+        .stops()
+        .iter()
+        .map(|&(color, offset)| sk::GradientStop::new(offset.get() as f32, color.into()))
+        .collect::<Vec<_>>();
+
+    let shader = match gradient {
+        Gradient::Linear(linear) => {
+            let [x0, y0, x1, y1] = linear.axis(aspect_ratio).map(|v| v as f32);
+            sk::LinearGradient::new(
+                sk::Point::from_xy(x0, y0),
+                sk::Point::from_xy(x1, y1),
+                stops,
+                sk::SpreadMode::Pad,
+                unit_ts,
+            )
+        }
+        Gradient::Radial(radial) => {
+            let x = radial.center.x.get() as f32;
+            let y = radial.center.y.get() as f32;
+            sk::RadialGradient::new(
+                sk::Point::from_xy(x, y),
+                sk::Point::from_xy(x, y),
+                radial.radius.get() as f32,
+                stops,
+                sk::SpreadMode::Pad,
+                unit_ts,
+            )
+        }
+        Gradient::Conic(_) => {
+            let texture: &Arc<sk::Pixmap> =
+                storage.insert(conic_texture(gradient, Ratio::new(aspect_ratio)));
+            let scale = 1.0 / CONIC_TEXTURE_SIZE as f32;
+            Some(sk::Pattern::new(
+                (**texture).as_ref(),
+                sk::SpreadMode::Pad,
+                sk::FilterQuality::Bilinear,
+                1.0,
+                unit_ts.pre_scale(scale, scale),
+            ))
+        }
+    };
+
+    match shader {
+        Some(shader) => sk_paint.shader = shader,
+        None => sk_paint.set_color(gradient.sample(0.0).into()),
+    }
+
+    sk_paint
+}
+
+/// The width and height of the texture a conic gradient is rendered into.
+const CONIC_TEXTURE_SIZE: u32 = 256;
+
+/// Render a conic gradient into a texture spanning its unit square.
+#[comemo::memoize]
+pub(super) fn conic_texture(gradient: &Gradient, aspect_ratio: Ratio) -> Arc<sk::Pixmap> {
+    let size = CONIC_TEXTURE_SIZE;
+    let mut pixmap = sk::Pixmap::new(size, size).unwrap();
+    for (i, pixel) in pixmap.pixels_mut().iter_mut().enumerate() {
+        let x = ((i as u32 % size) as f64 + 0.5) / size as f64;
+        let y = ((i as u32 / size) as f64 + 0.5) / size as f64;
+        let c = gradient.sample_at((x, y), aspect_ratio.get()).to_rgba();
+        *pixel = sk::ColorU8::from_rgba(c.r, c.g, c.b, c.a).premultiply();
     }
+    Arc::new(pixmap)
 }
 
 impl From<Color> for sk::Color {
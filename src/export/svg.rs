@@ -0,0 +1,831 @@
+//! Exporting into SVG images.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter, Write};
+use std::io::Read;
+
+use base64::Engine;
+use ecow::{eco_format, EcoString};
+use ttf_parser::{GlyphId, OutlineBuilder};
+use xmlwriter::XmlWriter;
+
+use super::render::conic_texture;
+use crate::doc::{Destination, Frame, FrameItem, GroupItem, Meta, TextItem};
+use crate::font::Font;
+use crate::geom::{
+    Abs, Color, Geometry, Gradient, LineCap, LineJoin, Paint, PathItem, Ratio, Relative,
+    Shape, Size, Stroke, Transform,
+};
+use crate::image::{Image, ImageFormat, RasterFormat, VectorFormat};
+use crate::util::hash128;
+
+/// Export a frame into an SVG file.
+///
+/// Returns the SVG document as a string.
+#[tracing::instrument(skip_all)]
+pub fn svg(frame: &Frame) -> String {
+    let mut renderer = SvgRenderer::new();
+    renderer.write_header(frame.size());
+    let container = Container { ts: Transform::identity(), size: frame.size() };
+    renderer.render_frame(frame, Transform::identity(), container);
+    renderer.finalize()
+}
+
+/// Renders a frame into an SVG document.
+struct SvgRenderer {
+    /// The internal XML writer.
+    xml: XmlWriter,
+    /// Glyphs that were used in the document. They are written once into the
+    /// `<defs>` section as `<symbol>`s and then referenced through `<use>`.
+    glyphs: Deduplicator<RenderedGlyph>,
+    /// Clip paths used by groups, in the format of an SVG path's `d`
+    /// attribute.
+    clip_paths: Deduplicator<EcoString>,
+    /// Gradients used by shapes and text, alongside the transform from their
+    /// unit square to the painted element's coordinate system and the aspect
+    /// ratio of the box they span.
+    gradients: Deduplicator<(Gradient, Transform, Ratio)>,
+}
+
+/// The closest container of the rendered items, for gradients relative to
+/// their parent.
+#[derive(Copy, Clone)]
+struct Container {
+    /// The transform from the container's coordinate system to the root.
+    ts: Transform,
+    /// The size of the container.
+    size: Size,
+}
+
+impl SvgRenderer {
+    /// Create a new renderer.
+    fn new() -> Self {
+        Self {
+            xml: XmlWriter::new(xmlwriter::Options::default()),
+            glyphs: Deduplicator::new('g'),
+            clip_paths: Deduplicator::new('c'),
+            gradients: Deduplicator::new('p'),
+        }
+    }
+
+    /// Write the SVG header including the `viewBox` and `width` and `height`
+    /// attributes.
+    fn write_header(&mut self, size: Size) {
+        self.xml.start_element("svg");
+        self.xml.write_attribute("class", "typst-doc");
+        self.xml.write_attribute_fmt(
+            "viewBox",
+            format_args!("0 0 {} {}", size.x.to_pt(), size.y.to_pt()),
+        );
+        self.xml
+            .write_attribute_fmt("width", format_args!("{}pt", size.x.to_pt()));
+        self.xml
+            .write_attribute_fmt("height", format_args!("{}pt", size.y.to_pt()));
+        self.xml.write_attribute("xmlns", "http://www.w3.org/2000/svg");
+        self.xml
+            .write_attribute("xmlns:xlink", "http://www.w3.org/1999/xlink");
+    }
+
+    /// Render a frame. The transform maps the frame's coordinate system to
+    /// the root and is used to position gradients.
+    fn render_frame(&mut self, frame: &Frame, ts: Transform, container: Container) {
+        for (pos, item) in frame.items() {
+            let x = pos.x.to_pt();
+            let y = pos.y.to_pt();
+            self.xml.start_element("g");
+            self.xml
+                .write_attribute_fmt("transform", format_args!("translate({x} {y})"));
+
+            let ts = ts.pre_concat(Transform::translate(pos.x, pos.y));
+            match item {
+                FrameItem::Group(group) => self.render_group(group, ts),
+                FrameItem::Text(text) => self.render_text(text, ts, container),
+                FrameItem::Shape(shape, _) => self.render_shape(shape, ts, container),
+                FrameItem::Image(image, size, _) => self.render_image(image, *size),
+                FrameItem::Meta(meta, size) => match meta {
+                    Meta::Link(dest) => self.render_link(dest, *size),
+                    Meta::Elem(_) => {}
+                    Meta::PageNumbering(_) => {}
+                    Meta::Hide => {}
+                },
+            }
+
+            self.xml.end_element();
+        }
+    }
+
+    /// Render a group. If the group has `clips` set to true, a clip path will
+    /// be created.
+    fn render_group(&mut self, group: &GroupItem, ts: Transform) {
+        self.xml.start_element("g");
+        self.xml.write_attribute("class", "typst-group");
+
+        if !group.transform.is_identity() {
+            self.xml.write_attribute("transform", &SvgMatrix(group.transform));
+        }
+
+        if group.clips {
+            let size = group.frame.size();
+            let path = Geometry::Rect(size);
+            let d = convert_geometry_to_path(&path);
+            let id = self.clip_paths.insert_with(hash128(&d), || d);
+            self.xml.write_attribute_fmt("clip-path", format_args!("url(#{id})"));
+        }
+
+        let ts = ts.pre_concat(group.transform);
+        let container = Container { ts, size: group.frame.size() };
+        self.render_frame(&group.frame, ts, container);
+        self.xml.end_element();
+    }
+
+    /// Render a text item. The text is rendered as a group of glyphs, each of
+    /// which references a glyph symbol in the `<defs>` section.
+    fn render_text(&mut self, text: &TextItem, ts: Transform, container: Container) {
+        let scale = text.size.to_pt() / text.font.units_per_em();
+        let inv_scale = text.font.units_per_em() / text.size.to_pt();
+
+        self.xml.start_element("g");
+        self.xml.write_attribute("class", "typst-text");
+        self.xml
+            .write_attribute_fmt("transform", format_args!("scale({scale} {})", -scale));
+
+        let fill_ts =
+            ts.pre_concat(Transform::scale(Ratio::new(scale), Ratio::new(-scale)));
+        let (pos, size) = text.bbox();
+        let bbox = (ts.pre_concat(Transform::translate(pos.x, pos.y)), size);
+        self.write_paint("fill", &text.fill, fill_ts, bbox, container, true);
+
+        let mut x = 0.0;
+        for glyph in &text.glyphs {
+            let id = GlyphId(glyph.id);
+            let offset = x + glyph.x_offset.at(text.size).to_pt();
+
+            let hash = hash128(&(&text.font, id));
+            if let Some(id) =
+                self.glyphs.get_or_try_insert(hash, || render_glyph(&text.font, id))
+            {
+                self.xml.start_element("use");
+                self.xml.write_attribute_fmt("xlink:href", format_args!("#{id}"));
+                self.xml.write_attribute("x", &(offset * inv_scale));
+                self.xml.end_element();
+            }
+
+            x += glyph.x_advance.at(text.size).to_pt();
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Render a shape element.
+    fn render_shape(&mut self, shape: &Shape, ts: Transform, container: Container) {
+        self.xml.start_element("path");
+        self.xml.write_attribute("class", "typst-shape");
+
+        let (pos, size) = shape.geometry.bbox();
+        let bbox = (ts.pre_concat(Transform::translate(pos.x, pos.y)), size);
+        if let Some(fill) = &shape.fill {
+            self.write_paint("fill", fill, ts, bbox, container, false);
+        } else {
+            self.xml.write_attribute("fill", "none");
+        }
+
+        if let Some(stroke) = &shape.stroke {
+            self.write_stroke(stroke, ts, bbox, container);
+        }
+
+        let path = convert_geometry_to_path(&shape.geometry);
+        self.xml.write_attribute("d", &path);
+        self.xml.end_element();
+    }
+
+    /// Write the stroke attributes of a shape.
+    fn write_stroke(
+        &mut self,
+        stroke: &Stroke,
+        ts: Transform,
+        bbox: (Transform, Size),
+        container: Container,
+    ) {
+        self.write_paint("stroke", &stroke.paint, ts, bbox, container, false);
+        self.xml.write_attribute("stroke-width", &stroke.thickness.to_pt());
+        self.xml.write_attribute(
+            "stroke-linecap",
+            match stroke.line_cap {
+                LineCap::Butt => "butt",
+                LineCap::Round => "round",
+                LineCap::Square => "square",
+            },
+        );
+        self.xml.write_attribute(
+            "stroke-linejoin",
+            match stroke.line_join {
+                LineJoin::Miter => "miter",
+                LineJoin::Round => "round",
+                LineJoin::Bevel => "bevel",
+            },
+        );
+        self.xml.write_attribute("stroke-miterlimit", &stroke.miter_limit.0);
+
+        if let Some(pattern) = &stroke.dash_pattern {
+            self.xml.write_attribute("stroke-dashoffset", &pattern.phase.to_pt());
+            self.xml.write_attribute(
+                "stroke-dasharray",
+                &pattern
+                    .array
+                    .iter()
+                    .map(|dash| dash.to_pt().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+    }
+
+    /// Write the `fill` or `stroke` attribute for a paint.
+    ///
+    /// The paint is used in the coordinate system given by `fill_ts`. The
+    /// bounding box of the painted item is given by its transform and size.
+    /// Gradients reference a definition in the `<defs>` section, which is
+    /// placed relative to the bounding box or to the container.
+    fn write_paint(
+        &mut self,
+        attr: &str,
+        paint: &Paint,
+        fill_ts: Transform,
+        (bbox_ts, size): (Transform, Size),
+        container: Container,
+        on_text: bool,
+    ) {
+        let gradient = match paint {
+            Paint::Solid(color) => {
+                let rgba = color.to_rgba();
+                self.xml.write_attribute(attr, &SvgColor(rgba.r, rgba.g, rgba.b));
+                if rgba.a != 255 {
+                    self.xml.write_attribute(
+                        &format!("{attr}-opacity"),
+                        &(rgba.a as f64 / 255.0),
+                    );
+                }
+                return;
+            }
+            Paint::Gradient(gradient) => gradient,
+        };
+
+        let (ts, size) = match gradient.unwrap_relative(on_text) {
+            Relative::Self_ => (bbox_ts, size),
+            Relative::Parent => (container.ts, container.size),
+        };
+
+        // Map the gradient's unit square into the coordinate system of the
+        // fill. Boxes without an extent are enlarged to avoid a degenerate
+        // transform.
+        let size = size.map(|v| v.max(Abs::pt(1.0)));
+        let unit_ts = fill_ts.invert().unwrap_or_default().pre_concat(ts).pre_concat(
+            Transform::scale(Ratio::new(size.x.to_pt()), Ratio::new(size.y.to_pt())),
+        );
+
+        let aspect_ratio = Ratio::new(size.x / size.y);
+        let value = (gradient.clone(), unit_ts, aspect_ratio);
+        let id = self.gradients.insert_with(hash128(&value), || value);
+        self.xml.write_attribute_fmt(attr, format_args!("url(#{id})"));
+    }
+
+    /// Render an image element.
+    fn render_image(&mut self, image: &Image, size: Size) {
+        let url = encode_image_to_url(image);
+        self.xml.start_element("image");
+        self.xml.write_attribute("xlink:href", &url);
+        self.xml.write_attribute("width", &size.x.to_pt());
+        self.xml.write_attribute("height", &size.y.to_pt());
+        self.xml.write_attribute("preserveAspectRatio", "none");
+        self.xml.end_element();
+    }
+
+    /// Render a link as an invisible, clickable area.
+    ///
+    /// Only links to URLs are exported since a single SVG file cannot refer to
+    /// positions in other pages of the document.
+    fn render_link(&mut self, dest: &Destination, size: Size) {
+        let Destination::Url(url) = dest else { return };
+        self.xml.start_element("a");
+        self.xml.write_attribute("xlink:href", url.as_str());
+        self.xml.start_element("rect");
+        self.xml.write_attribute("width", &size.x.to_pt());
+        self.xml.write_attribute("height", &size.y.to_pt());
+        self.xml.write_attribute("fill", "transparent");
+        self.xml.end_element();
+        self.xml.end_element();
+    }
+
+    /// Finalize the SVG file. This must be called after all rendering is done.
+    fn finalize(mut self) -> String {
+        self.write_glyph_defs();
+        self.write_clip_path_defs();
+        self.write_gradient_defs();
+        self.xml.end_document()
+    }
+
+    /// Build the glyph definitions.
+    fn write_glyph_defs(&mut self) {
+        if self.glyphs.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "glyph");
+
+        for (id, glyph) in self.glyphs.iter() {
+            self.xml.start_element("symbol");
+            self.xml.write_attribute("id", &id);
+            self.xml.write_attribute("overflow", "visible");
+
+            match glyph {
+                RenderedGlyph::Path(path) => {
+                    self.xml.start_element("path");
+                    self.xml.write_attribute("d", &path);
+                    self.xml.end_element();
+                }
+                RenderedGlyph::Image { url, width, height, ts } => {
+                    self.xml.start_element("image");
+                    self.xml.write_attribute("xlink:href", &url);
+                    self.xml.write_attribute("width", &width);
+                    self.xml.write_attribute("height", &height);
+                    if !ts.is_identity() {
+                        self.xml.write_attribute("transform", &SvgMatrix(*ts));
+                    }
+                    self.xml.write_attribute("preserveAspectRatio", "none");
+                    self.xml.end_element();
+                }
+            }
+
+            self.xml.end_element();
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Build the clip path definitions.
+    fn write_clip_path_defs(&mut self) {
+        if self.clip_paths.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "clip-path");
+
+        for (id, path) in self.clip_paths.iter() {
+            self.xml.start_element("clipPath");
+            self.xml.write_attribute("id", &id);
+            self.xml.start_element("path");
+            self.xml.write_attribute("d", &path);
+            self.xml.end_element();
+            self.xml.end_element();
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Build the gradient definitions.
+    fn write_gradient_defs(&mut self) {
+        if self.gradients.is_empty() {
+            return;
+        }
+
+        self.xml.start_element("defs");
+        self.xml.write_attribute("id", "gradients");
+
+        for (id, (gradient, ts, aspect_ratio)) in self.gradients.iter() {
+            // SVG has no conic gradients, so they are rendered into a
+            // texture spanning the unit square, which is used as a pattern.
+            if let Gradient::Conic(_) = gradient {
+                let texture = conic_texture(gradient, *aspect_ratio);
+                let data = texture.encode_png().unwrap_or_default();
+                self.xml.start_element("pattern");
+                self.xml.write_attribute("id", &id);
+                self.xml.write_attribute("patternUnits", "userSpaceOnUse");
+                self.xml.write_attribute("width", "1");
+                self.xml.write_attribute("height", "1");
+                self.xml.write_attribute("patternTransform", &SvgMatrix(*ts));
+                self.xml.start_element("image");
+                self.xml.write_attribute_fmt(
+                    "xlink:href",
+                    format_args!(
+                        "data:image/png;base64,{}",
+                        base64::engine::general_purpose::STANDARD.encode(data)
+                    ),
+                );
+                self.xml.write_attribute("width", "1");
+                self.xml.write_attribute("height", "1");
+                self.xml.write_attribute("preserveAspectRatio", "none");
+                self.xml.end_element();
+                self.xml.end_element();
+                continue;
+            }
+
+            match gradient {
+                Gradient::Linear(linear) => {
+                    let [x1, y1, x2, y2] = linear.axis(aspect_ratio.get());
+                    self.xml.start_element("linearGradient");
+                    self.xml.write_attribute("id", &id);
+                    self.xml.write_attribute("x1", &x1);
+                    self.xml.write_attribute("y1", &y1);
+                    self.xml.write_attribute("x2", &x2);
+                    self.xml.write_attribute("y2", &y2);
+                }
+                Gradient::Radial(radial) => {
+                    self.xml.start_element("radialGradient");
+                    self.xml.write_attribute("id", &id);
+                    self.xml.write_attribute("cx", &radial.center.x.get());
+                    self.xml.write_attribute("cy", &radial.center.y.get());
+                    self.xml.write_attribute("r", &radial.radius.get());
+                }
+                Gradient::Conic(_) => unreachable!(),
+            }
+
+            self.xml.write_attribute("gradientUnits", "userSpaceOnUse");
+            self.xml.write_attribute("gradientTransform", &SvgMatrix(*ts));
+
+            for &(color, offset) in gradient.stops() {
+                self.write_stop(color, offset);
+            }
+
+            self.xml.end_element();
+        }
+
+        self.xml.end_element();
+    }
+
+    /// Write a color stop of a gradient.
+    fn write_stop(&mut self, color: Color, offset: Ratio) {
+        let rgba = color.to_rgba();
+        self.xml.start_element("stop");
+        self.xml.write_attribute("offset", &offset.get());
+        self.xml
+            .write_attribute("stop-color", &SvgColor(rgba.r, rgba.g, rgba.b));
+        if rgba.a != 255 {
+            self.xml.write_attribute("stop-opacity", &(rgba.a as f64 / 255.0));
+        }
+        self.xml.end_element();
+    }
+}
+
+/// A glyph that was converted into its SVG representation.
+///
+/// Glyphs are defined in font units with the y-axis pointing upwards, they
+/// are flipped and scaled by the text group that uses them.
+#[derive(Debug, Clone)]
+enum RenderedGlyph {
+    /// An outline glyph, converted into an SVG path.
+    Path(EcoString),
+    /// A bitmap or SVG glyph, embedded as an image.
+    Image { url: EcoString, width: f64, height: f64, ts: Transform },
+}
+
+/// Convert a glyph into its SVG representation.
+fn render_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    render_svg_glyph(font, id)
+        .or_else(|| render_bitmap_glyph(font, id))
+        .or_else(|| render_outline_glyph(font, id))
+}
+
+/// Convert an outline glyph into an SVG path.
+fn render_outline_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    let mut builder = SvgPathBuilder::default();
+    font.ttf().outline_glyph(id, &mut builder)?;
+    Some(RenderedGlyph::Path(builder.0))
+}
+
+/// Convert a bitmap glyph into an embedded image.
+fn render_bitmap_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    let raster = font.ttf().glyph_raster_image(id, u16::MAX)?;
+    let image = Image::new(raster.data.into(), raster.format.into(), None).ok()?;
+
+    // The glyph is scaled to one em in height, analogous to the raster
+    // renderer. The image needs to be flipped back since the symbol's
+    // coordinate system is y-up.
+    let upem = font.units_per_em();
+    let height = upem;
+    let width = (image.width() as f64 / image.height() as f64) * height;
+    let dx = (raster.x as f64) / (image.width() as f64) * upem;
+    let dy = (raster.y as f64) / (image.height() as f64) * upem;
+    let ts = Transform::scale(Ratio::one(), -Ratio::one())
+        .pre_concat(Transform::translate(Abs::pt(dx), Abs::pt(-height - dy)));
+
+    Some(RenderedGlyph::Image {
+        url: encode_image_to_url(&image),
+        width,
+        height,
+        ts,
+    })
+}
+
+/// Convert an SVG glyph into an embedded image.
+fn render_svg_glyph(font: &Font, id: GlyphId) -> Option<RenderedGlyph> {
+    let mut data = font.ttf().glyph_svg_image(id)?;
+
+    // Decompress SVGZ.
+    let mut decoded = vec![];
+    if data.starts_with(&[0x1f, 0x8b]) {
+        let mut decoder = flate2::read::GzDecoder::new(data);
+        decoder.read_to_end(&mut decoded).ok()?;
+        data = &decoded;
+    }
+
+    // SVG glyphs are drawn in the em square of the font with the y-axis
+    // pointing downwards and the baseline at the top.
+    let upem = font.units_per_em();
+    let url = eco_format!(
+        "data:image/svg+xml;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(data)
+    );
+
+    Some(RenderedGlyph::Image {
+        url,
+        width: upem,
+        height: upem,
+        ts: Transform::scale(Ratio::one(), -Ratio::one()),
+    })
+}
+
+/// Encode an image into a data URL. The format of the URL is
+/// `data:image/{format};base64,`.
+fn encode_image_to_url(image: &Image) -> EcoString {
+    let format = match image.format() {
+        ImageFormat::Raster(f) => match f {
+            RasterFormat::Png => "png",
+            RasterFormat::Jpg => "jpeg",
+            RasterFormat::Gif => "gif",
+        },
+        ImageFormat::Vector(f) => match f {
+            VectorFormat::Svg => "svg+xml",
+        },
+    };
+
+    let mut url = eco_format!("data:image/{format};base64,");
+    let data = base64::engine::general_purpose::STANDARD.encode(image.data());
+    url.push_str(&data);
+    url
+}
+
+/// Convert a geometry into an SVG path.
+fn convert_geometry_to_path(geometry: &Geometry) -> EcoString {
+    let mut builder = SvgPathBuilder::default();
+    match geometry {
+        Geometry::Line(t) => {
+            builder.move_to(0.0, 0.0);
+            builder.line_to(t.x.to_pt() as f32, t.y.to_pt() as f32);
+        }
+        Geometry::Rect(rect) => {
+            let x = rect.x.to_pt() as f32;
+            let y = rect.y.to_pt() as f32;
+            builder.rect(x, y);
+        }
+        Geometry::Path(p) => {
+            for item in &p.0 {
+                match item {
+                    PathItem::MoveTo(m) => {
+                        builder.move_to(m.x.to_pt() as f32, m.y.to_pt() as f32)
+                    }
+                    PathItem::LineTo(l) => {
+                        builder.line_to(l.x.to_pt() as f32, l.y.to_pt() as f32)
+                    }
+                    PathItem::CubicTo(c1, c2, t) => builder.curve_to(
+                        c1.x.to_pt() as f32,
+                        c1.y.to_pt() as f32,
+                        c2.x.to_pt() as f32,
+                        c2.y.to_pt() as f32,
+                        t.x.to_pt() as f32,
+                        t.y.to_pt() as f32,
+                    ),
+                    PathItem::ClosePath => builder.close(),
+                }
+            }
+        }
+    };
+    builder.0
+}
+
+/// Builds the `d` attribute of an SVG path.
+#[derive(Debug, Default, Clone)]
+struct SvgPathBuilder(EcoString);
+
+impl SvgPathBuilder {
+    /// Create a rectangle path. The rectangle is created with the top-left
+    /// corner at (0, 0). The width and height are the size of the rectangle.
+    fn rect(&mut self, width: f32, height: f32) {
+        self.move_to(0.0, 0.0);
+        self.line_to(0.0, height);
+        self.line_to(width, height);
+        self.line_to(width, 0.0);
+        self.close();
+    }
+}
+
+/// A builder for SVG paths. Also used to convert glyph outlines.
+impl OutlineBuilder for SvgPathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        write!(&mut self.0, "M {x} {y} ").unwrap();
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        write!(&mut self.0, "L {x} {y} ").unwrap();
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        write!(&mut self.0, "Q {x1} {y1} {x} {y} ").unwrap();
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        write!(&mut self.0, "C {x1} {y1} {x2} {y2} {x} {y} ").unwrap();
+    }
+
+    fn close(&mut self) {
+        write!(&mut self.0, "Z ").unwrap();
+    }
+}
+
+/// Deduplicates its elements by hash. It is used to deduplicate glyphs, clip
+/// paths and gradients. The `kind` prefixes the generated ids to distinguish
+/// between them.
+#[derive(Debug, Clone)]
+struct Deduplicator<T> {
+    kind: char,
+    vec: Vec<T>,
+    present: HashMap<u128, Id>,
+}
+
+impl<T> Deduplicator<T> {
+    fn new(kind: char) -> Self {
+        Self { kind, vec: Vec::new(), present: HashMap::new() }
+    }
+
+    /// Inserts a value into the vector. If the hash is already present,
+    /// returns the index of the existing value and `f` will not be called.
+    /// Otherwise, inserts the value and returns the id of the inserted value.
+    #[must_use = "returns the index of the inserted value"]
+    fn insert_with<F>(&mut self, hash: u128, f: F) -> Id
+    where
+        F: FnOnce() -> T,
+    {
+        *self.present.entry(hash).or_insert_with(|| {
+            let index = self.vec.len();
+            self.vec.push(f());
+            Id(self.kind, index)
+        })
+    }
+
+    /// Like [`Self::insert_with`], but the value may fail to be produced, in
+    /// which case nothing is inserted. Failures are not cached.
+    fn get_or_try_insert<F>(&mut self, hash: u128, f: F) -> Option<Id>
+    where
+        F: FnOnce() -> Option<T>,
+    {
+        if let Some(&id) = self.present.get(&hash) {
+            return Some(id);
+        }
+
+        let value = f()?;
+        Some(self.insert_with(hash, || value))
+    }
+
+    /// Iterate over the elements alongside their ids.
+    fn iter(&self) -> impl Iterator<Item = (Id, &T)> {
+        self.vec.iter().enumerate().map(|(i, v)| (Id(self.kind, i), v))
+    }
+
+    /// Whether no elements were inserted.
+    fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+}
+
+/// Identifies a `<def>`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+struct Id(char, usize);
+
+impl Display for Id {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.0, self.1)
+    }
+}
+
+/// Displays as an SVG matrix.
+struct SvgMatrix(Transform);
+
+impl Display for SvgMatrix {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        // Convert a [`Transform`] into a SVG transform string.
+        // See https://developer.mozilla.org/en-US/docs/Web/SVG/Attribute/transform
+        write!(
+            f,
+            "matrix({} {} {} {} {} {})",
+            self.0.sx.get(),
+            self.0.ky.get(),
+            self.0.kx.get(),
+            self.0.sy.get(),
+            self.0.tx.to_pt(),
+            self.0.ty.to_pt()
+        )
+    }
+}
+
+/// Displays as an SVG hex color.
+struct SvgColor(u8, u8, u8);
+
+impl Display for SvgColor {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::geom::{
+        Angle, Axes, ConicGradient, LinearGradient, Point, RgbaColor, Smart,
+    };
+    use crate::syntax::Span;
+
+    #[test]
+    fn test_svg_export_shapes_and_links() {
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(50.0)));
+        let rect = Geometry::Rect(Size::new(Abs::pt(20.0), Abs::pt(10.0)))
+            .filled(RgbaColor::new(255, 0, 0, 128).into());
+        frame.push(
+            Point::new(Abs::pt(5.0), Abs::pt(5.0)),
+            FrameItem::Shape(rect, Span::detached()),
+        );
+        frame.push(
+            Point::zero(),
+            FrameItem::Meta(
+                Meta::Link(Destination::Url("https://typst.app".into())),
+                Size::new(Abs::pt(10.0), Abs::pt(10.0)),
+            ),
+        );
+
+        let svg = svg(&frame);
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(r#"viewBox="0 0 100 50""#));
+        assert!(svg.contains(r#"width="100pt""#));
+        assert!(svg.contains(r#"transform="translate(5 5)""#));
+        assert!(svg.contains(r#"fill="#ff0000""#));
+        assert!(svg.contains("fill-opacity"));
+        assert!(svg.contains(r#"d="M 0 0 L 0 10 L 20 10 L 20 0 Z ""#));
+        assert!(svg.contains(r#"xlink:href="https://typst.app""#));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn test_svg_export_gradients() {
+        let stops = vec![
+            (RgbaColor::new(255, 0, 0, 255).into(), Ratio::zero()),
+            (RgbaColor::new(0, 0, 255, 255).into(), Ratio::one()),
+        ];
+
+        let linear = Gradient::Linear(Arc::new(LinearGradient {
+            stops: stops.clone(),
+            angle: Angle::zero(),
+            relative: Smart::Custom(Relative::Parent),
+        }));
+
+        let conic = Gradient::Conic(Arc::new(ConicGradient {
+            stops,
+            angle: Angle::zero(),
+            center: Axes::splat(Ratio::new(0.5)),
+            relative: Smart::Auto,
+        }));
+
+        // A shape with a parent-relative gradient inside of a group and a
+        // shape with a conic gradient.
+        let size = Size::new(Abs::pt(10.0), Abs::pt(10.0));
+        let mut inner = Frame::new(Size::new(Abs::pt(40.0), Abs::pt(20.0)));
+        inner.push(
+            Point::new(Abs::pt(20.0), Abs::zero()),
+            FrameItem::Shape(
+                Geometry::Rect(size).filled(Paint::Gradient(linear)),
+                Span::detached(),
+            ),
+        );
+
+        let mut frame = Frame::new(Size::new(Abs::pt(100.0), Abs::pt(50.0)));
+        frame.push(
+            Point::new(Abs::pt(30.0), Abs::zero()),
+            FrameItem::Group(GroupItem::new(inner)),
+        );
+        frame.push(
+            Point::zero(),
+            FrameItem::Shape(
+                Geometry::Rect(size).filled(Paint::Gradient(conic)),
+                Span::detached(),
+            ),
+        );
+
+        let svg = svg(&frame);
+        assert!(svg.contains("<linearGradient"));
+        assert!(svg.contains(r#"gradientTransform="matrix(40 0 0 20 -20 0)""#));
+        assert!(svg.contains("<pattern"));
+        assert!(svg.contains(r#"patternTransform="matrix(10 0 0 10 0 0)""#));
+        assert!(svg.contains("data:image/png;base64,"));
+    }
+}
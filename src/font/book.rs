@@ -67,6 +67,13 @@ impl FontBook {
         self.find_best_variant(None, variant, ids.iter().copied())
     }
 
+    /// Whether the book contains any font from the given `family`.
+    ///
+    /// The `family` should be all lowercase.
+    pub fn contains_family(&self, family: &str) -> bool {
+        self.families.contains_key(family)
+    }
+
     /// Iterate over all variants of a family.
     pub fn select_family(&self, family: &str) -> impl Iterator<Item = usize> + '_ {
         self.families
@@ -289,6 +289,21 @@ cast_to_value! {
     v: Axes<Rel<Length>> => Value::Array(array![v.x, v.y])
 }
 
+cast_from_value! {
+    Axes<Ratio>,
+    array: Array => {
+        let mut iter = array.into_iter();
+        match (iter.next(), iter.next(), iter.next()) {
+            (Some(a), Some(b), None) => Axes::new(a.cast()?, b.cast()?),
+            _ => Err("ratio array must contain exactly two entries")?,
+        }
+    },
+}
+
+cast_to_value! {
+    v: Axes<Ratio> => Value::Array(array![v.x, v.y])
+}
+
 impl<T: Resolve> Resolve for Axes<T> {
     type Output = Axes<T::Output>;
 
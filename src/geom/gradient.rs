@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use super::*;
+
+/// A color gradient.
+///
+/// The geometry of a gradient is defined in a unit square that is stretched
+/// over the bounding box of the element itself or of its parent container,
+/// depending on what the gradient is [relative](Relative) to.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub enum Gradient {
+    /// A gradient along a straight line.
+    Linear(Arc<LinearGradient>),
+    /// A gradient radiating from a center point.
+    Radial(Arc<RadialGradient>),
+    /// A gradient sweeping around a center point.
+    Conic(Arc<ConicGradient>),
+}
+
+/// A gradient along a straight line.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct LinearGradient {
+    /// The color stops of the gradient.
+    pub stops: Vec<(Color, Ratio)>,
+    /// The direction of the gradient.
+    pub angle: Angle,
+    /// What the gradient is relative to.
+    pub relative: Smart<Relative>,
+}
+
+/// A gradient radiating from a center point.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct RadialGradient {
+    /// The color stops of the gradient.
+    pub stops: Vec<(Color, Ratio)>,
+    /// The center of the end circle.
+    pub center: Axes<Ratio>,
+    /// The radius of the end circle.
+    pub radius: Ratio,
+    /// What the gradient is relative to.
+    pub relative: Smart<Relative>,
+}
+
+/// A gradient sweeping around a center point.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ConicGradient {
+    /// The color stops of the gradient.
+    pub stops: Vec<(Color, Ratio)>,
+    /// The angle at which the gradient starts.
+    pub angle: Angle,
+    /// The center of the gradient.
+    pub center: Axes<Ratio>,
+    /// What the gradient is relative to.
+    pub relative: Smart<Relative>,
+}
+
+impl Gradient {
+    /// The color stops of the gradient.
+    ///
+    /// The offsets of the stops are sorted, the first one is always at `0%`
+    /// and the last one is always at `100%`.
+    pub fn stops(&self) -> &[(Color, Ratio)] {
+        match self {
+            Self::Linear(linear) => &linear.stops,
+            Self::Radial(radial) => &radial.stops,
+            Self::Conic(conic) => &conic.stops,
+        }
+    }
+
+    /// The name of the gradient's kind.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Linear(_) => "linear",
+            Self::Radial(_) => "radial",
+            Self::Conic(_) => "conic",
+        }
+    }
+
+    /// The angle of the gradient, if it has one.
+    pub fn angle(&self) -> Option<Angle> {
+        match self {
+            Self::Linear(linear) => Some(linear.angle),
+            Self::Radial(_) => None,
+            Self::Conic(conic) => Some(conic.angle),
+        }
+    }
+
+    /// What the gradient is relative to, as specified by the user.
+    pub fn relative(&self) -> Smart<Relative> {
+        match self {
+            Self::Linear(linear) => linear.relative,
+            Self::Radial(radial) => radial.relative,
+            Self::Conic(conic) => conic.relative,
+        }
+    }
+
+    /// What the gradient is relative to, with `auto` resolved.
+    ///
+    /// Text is painted relative to its parent by default since each text run
+    /// would otherwise restart the gradient.
+    pub fn unwrap_relative(&self, on_text: bool) -> Relative {
+        self.relative().unwrap_or_else(|| {
+            if on_text {
+                Relative::Parent
+            } else {
+                Relative::Self_
+            }
+        })
+    }
+
+    /// Sample the color of the gradient at an offset between zero and one.
+    pub fn sample(&self, t: f64) -> Color {
+        let stops = self.stops();
+        let t = t.clamp(0.0, 1.0);
+
+        let index = stops
+            .iter()
+            .position(|&(_, offset)| offset.get() >= t)
+            .unwrap_or(stops.len() - 1);
+
+        if index == 0 {
+            return stops[0].0;
+        }
+
+        let (start, a) = stops[index - 1];
+        let (end, b) = stops[index];
+        let len = b.get() - a.get();
+        if len <= 0.0 {
+            return end;
+        }
+
+        mix(start, end, (t - a.get()) / len)
+    }
+
+    /// Sample the color of the gradient at a point in the unit square.
+    ///
+    /// The aspect ratio is the width of the painted box divided by its
+    /// height.
+    pub fn sample_at(&self, (x, y): (f64, f64), aspect_ratio: f64) -> Color {
+        let t = match self {
+            Self::Linear(linear) => {
+                let [x0, y0, x1, y1] = linear.axis(aspect_ratio);
+                let (dx, dy) = (x1 - x0, y1 - y0);
+                ((x - x0) * dx + (y - y0) * dy) / (dx * dx + dy * dy)
+            }
+            Self::Radial(radial) => {
+                let dx = x - radial.center.x.get();
+                let dy = y - radial.center.y.get();
+                (dx * dx + dy * dy).sqrt() / radial.radius.get()
+            }
+            Self::Conic(conic) => {
+                let dx = (x - conic.center.x.get()) * aspect_ratio;
+                let dy = y - conic.center.y.get();
+                let angle = Angle::rad(dy.atan2(dx)) - conic.angle;
+                angle.to_deg().rem_euclid(360.0) / 360.0
+            }
+        };
+
+        self.sample(t)
+    }
+}
+
+impl LinearGradient {
+    /// The start and end points of the gradient's axis in the unit square.
+    ///
+    /// The axis is chosen such that the gradient spans the whole box with the
+    /// requested angle after the unit square was stretched to a box with the
+    /// given aspect ratio (width divided by height).
+    pub fn axis(&self, aspect_ratio: f64) -> [f64; 4] {
+        let a = aspect_ratio * self.angle.cos();
+        let b = self.angle.sin();
+        let scale = (a.abs() + b.abs()) / (a * a + b * b);
+        let (dx, dy) = (scale * a / 2.0, scale * b / 2.0);
+        [0.5 - dx, 0.5 - dy, 0.5 + dx, 0.5 + dy]
+    }
+}
+
+impl Debug for Gradient {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "gradient.{}(", self.kind())?;
+
+        for (i, (color, offset)) in self.stops().iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "({color:?}, {offset:?})")?;
+        }
+
+        match self {
+            Self::Linear(linear) => {
+                if linear.angle != Angle::zero() {
+                    write!(f, ", angle: {:?}", linear.angle)?;
+                }
+            }
+            Self::Radial(radial) => {
+                if radial.center != Axes::splat(Ratio::new(0.5)) {
+                    let Axes { x, y } = radial.center;
+                    write!(f, ", center: ({x:?}, {y:?})")?;
+                }
+                if radial.radius != Ratio::new(0.5) {
+                    write!(f, ", radius: {:?}", radial.radius)?;
+                }
+            }
+            Self::Conic(conic) => {
+                if conic.angle != Angle::zero() {
+                    write!(f, ", angle: {:?}", conic.angle)?;
+                }
+                if conic.center != Axes::splat(Ratio::new(0.5)) {
+                    let Axes { x, y } = conic.center;
+                    write!(f, ", center: ({x:?}, {y:?})")?;
+                }
+            }
+        }
+
+        match self.relative() {
+            Smart::Custom(Relative::Self_) => f.write_str(", relative: \"self\"")?,
+            Smart::Custom(Relative::Parent) => f.write_str(", relative: \"parent\"")?,
+            Smart::Auto => {}
+        }
+
+        f.write_str(")")
+    }
+}
+
+/// What a gradient is positioned and sized relative to.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Relative {
+    /// The bounding box of the painted element itself.
+    Self_,
+    /// The bounding box of the element's parent container.
+    Parent,
+}
+
+cast_from_value! {
+    Relative,
+    /// Relative to the bounding box of the element itself.
+    "self" => Self::Self_,
+    /// Relative to the bounding box of the element's parent container.
+    "parent" => Self::Parent,
+}
+
+cast_to_value! {
+    v: Relative => Value::from(match v {
+        Relative::Self_ => "self",
+        Relative::Parent => "parent",
+    })
+}
+
+/// Linearly interpolate between two colors in RGBA space.
+fn mix(start: Color, end: Color, t: f64) -> Color {
+    let a = start.to_rgba();
+    let b = end.to_rgba();
+    let lerp = |x: u8, y: u8| (x as f64 + t * (y as f64 - x as f64)).round() as u8;
+    RgbaColor::new(lerp(a.r, b.r), lerp(a.g, b.g), lerp(a.b, b.b), lerp(a.a, b.a)).into()
+}
@@ -7,6 +7,8 @@ use super::*;
 pub enum Paint {
     /// A solid color.
     Solid(Color),
+    /// A gradient.
+    Gradient(Gradient),
 }
 
 impl<T: Into<Color>> From<T> for Paint {
@@ -19,6 +21,7 @@ impl Debug for Paint {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
             Self::Solid(color) => color.fmt(f),
+            Self::Gradient(gradient) => gradient.fmt(f),
         }
     }
 }
@@ -26,10 +29,14 @@ impl Debug for Paint {
 cast_from_value! {
     Paint,
     color: Color => Self::Solid(color),
+    gradient: Gradient => Self::Gradient(gradient),
 }
 
 cast_to_value! {
-    Paint::Solid(color): Paint => Value::Color(color)
+    v: Paint => match v {
+        Paint::Solid(color) => Value::Color(color),
+        Paint::Gradient(gradient) => Value::Gradient(gradient),
+    }
 }
 
 /// A color in a dynamic format.
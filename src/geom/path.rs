@@ -51,4 +51,34 @@ impl Path {
     pub fn close_path(&mut self) {
         self.0.push(PathItem::ClosePath);
     }
+
+    /// The top-left corner and size of the path's bounding box.
+    ///
+    /// The bounding box includes the control points of bezier segments.
+    pub fn bbox(&self) -> (Point, Size) {
+        let mut min = Point::splat(Abs::inf());
+        let mut max = Point::splat(-Abs::inf());
+        let mut extend = |p: Point| {
+            min = min.min(p);
+            max = max.max(p);
+        };
+
+        for item in &self.0 {
+            match *item {
+                PathItem::MoveTo(p) | PathItem::LineTo(p) => extend(p),
+                PathItem::CubicTo(p1, p2, p3) => {
+                    extend(p1);
+                    extend(p2);
+                    extend(p3);
+                }
+                PathItem::ClosePath => {}
+            }
+        }
+
+        if min.x > max.x {
+            return (Point::zero(), Size::zero());
+        }
+
+        (min, (max - min).to_size())
+    }
 }
@@ -32,4 +32,17 @@ impl Geometry {
     pub fn stroked(self, stroke: Stroke) -> Shape {
         Shape { geometry: self, fill: None, stroke: Some(stroke) }
     }
+
+    /// The top-left corner and size of the geometry's bounding box, relative
+    /// to its origin.
+    pub fn bbox(&self) -> (Point, Size) {
+        match self {
+            Self::Line(target) => {
+                let size = Size::new(target.x.abs(), target.y.abs());
+                (target.min(Point::zero()), size)
+            }
+            Self::Rect(size) => (Point::zero(), *size),
+            Self::Path(path) => path.bbox(),
+        }
+    }
 }
@@ -68,6 +68,32 @@ impl Transform {
     pub fn post_concat(self, next: Self) -> Self {
         next.pre_concat(self)
     }
+
+    /// Invert the transformation.
+    ///
+    /// Returns `None` if the transformation is not invertible.
+    pub fn invert(self) -> Option<Self> {
+        if self.is_identity() {
+            return Some(self);
+        }
+
+        let (sx, ky, kx, sy) =
+            (self.sx.get(), self.ky.get(), self.kx.get(), self.sy.get());
+        let (tx, ty) = (self.tx.to_raw(), self.ty.to_raw());
+        let det = sx * sy - kx * ky;
+        if det.abs() < 1e-12 {
+            return None;
+        }
+
+        Some(Self {
+            sx: Ratio::new(sy / det),
+            ky: Ratio::new(-ky / det),
+            kx: Ratio::new(-kx / det),
+            sy: Ratio::new(sx / det),
+            tx: Abs::raw((kx * ty - sy * tx) / det),
+            ty: Abs::raw((ky * tx - sx * ty) / det),
+        })
+    }
 }
 
 impl Default for Transform {
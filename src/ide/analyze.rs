@@ -4,9 +4,9 @@ use comemo::Track;
 use ecow::EcoString;
 
 use crate::doc::Frame;
-use crate::eval::{eval, Module, Route, Tracer, Value};
+use crate::eval::{eval, Module, PackageManifest, PackageSpec, Route, Tracer, Value};
 use crate::model::{Introspector, Label};
-use crate::syntax::{ast, LinkedNode, Source, SyntaxKind};
+use crate::syntax::{ast, LinkedNode, Source, SourceId, SyntaxKind};
 use crate::util::PathExt;
 use crate::World;
 
@@ -42,6 +42,7 @@ pub fn analyze_expr(world: &(dyn World + 'static), node: &LinkedNode) -> Vec<Val
                 world.track(),
                 route.track(),
                 tracer.track_mut(),
+                None,
                 world.main(),
             )
             .and_then(|module| {
@@ -66,18 +67,38 @@ pub fn analyze_import(
     source: &Source,
     path: &str,
 ) -> Option<Module> {
-    let full: PathBuf = if let Some(path) = path.strip_prefix('/') {
-        world.root().join(path).normalize()
+    let (id, package) = resolve_import(world, source, path)?;
+    let route = Route::default();
+    let mut tracer = Tracer::default();
+    let source = world.source(id);
+    let package = package.as_deref();
+    eval(world.track(), route.track(), tracer.track_mut(), package, source).ok()
+}
+
+/// Try to find the source file a module import in the current source file
+/// refers to.
+///
+/// Returns the file's id and, if the file belongs to a package, the package's
+/// root directory.
+pub fn resolve_import(
+    world: &(dyn World + 'static),
+    source: &Source,
+    path: &str,
+) -> Option<(SourceId, Option<PathBuf>)> {
+    let (full, package): (PathBuf, _) = if path.starts_with('@') {
+        let spec = path.parse::<PackageSpec>().ok()?;
+        let root = world.package(&spec).ok()?;
+        let manifest = PackageManifest::load(world.track(), &root).ok()?;
+        (manifest.entrypoint(&root).ok()?, Some(root))
+    } else if let Some(path) = path.strip_prefix('/') {
+        (world.root().join(path).normalize(), None)
     } else if let Some(dir) = source.path().parent() {
-        dir.join(path).normalize()
+        (dir.join(path).normalize(), None)
     } else {
-        path.into()
+        (path.into(), None)
     };
-    let route = Route::default();
-    let mut tracer = Tracer::default();
     let id = world.resolve(&full).ok()?;
-    let source = world.source(id);
-    eval(world.track(), route.track(), tracer.track_mut(), source).ok()
+    Some((id, package))
 }
 
 /// Find all labels and details for them.
@@ -7,7 +7,7 @@ use unscanny::Scanner;
 use super::analyze::analyze_labels;
 use super::{analyze_expr, analyze_import, plain_docs_sentence, summarize_font_family};
 use crate::doc::Frame;
-use crate::eval::{methods_on, CastInfo, Library, Scope, Value};
+use crate::eval::{methods_on, CastInfo, Library, Param, Scope, Value};
 use crate::syntax::{
     ast, is_id_continue, is_id_start, is_ident, LinkedNode, Source, SyntaxKind,
 };
@@ -384,7 +384,7 @@ fn field_access_completions(ctx: &mut CompletionContext, value: &Value) {
             }
         }
         Value::Module(module) => {
-            for (name, value) in module.scope().iter() {
+            for (name, value) in module.exports() {
                 ctx.value_completion(Some(name.clone()), value, true, None);
             }
         }
@@ -434,7 +434,7 @@ fn complete_imports(ctx: &mut CompletionContext) -> bool {
 /// Add completions for all exports of a module.
 fn import_completions(
     ctx: &mut CompletionContext,
-    existing: &[ast::Ident],
+    existing: &[ast::ImportItem],
     value: &Value,
 ) {
     let module = match value {
@@ -450,8 +450,8 @@ fn import_completions(
         ctx.snippet_completion("*", "*", "Import everything.");
     }
 
-    for (name, value) in module.scope().iter() {
-        if existing.iter().all(|ident| ident.as_str() != name) {
+    for (name, value) in module.exports() {
+        if existing.iter().all(|item| item.original_name().as_str() != name) {
             ctx.value_completion(Some(name.clone()), value, false, None);
         }
     }
@@ -555,7 +555,7 @@ fn show_rule_recipe_completions(ctx: &mut CompletionContext) {
 /// Complete call and set rule parameters.
 fn complete_params(ctx: &mut CompletionContext) -> bool {
     // Ensure that we are in a function call or set rule's argument list.
-    let (callee, set, args) = if_chain! {
+    let (callee, callee_node, set, args) = if_chain! {
         if let Some(parent) = ctx.leaf.parent();
         if let Some(parent) = match parent.kind() {
             SyntaxKind::Named => parent.parent(),
@@ -570,8 +570,9 @@ fn complete_params(ctx: &mut CompletionContext) -> bool {
             ast::Expr::Set(set) => Some(set.target()),
             _ => None,
         };
+        let callee_node = grand.find(callee.span());
         then {
-            (callee, set, args)
+            (callee, callee_node, set, args)
         } else {
             return false;
         }
@@ -617,7 +618,7 @@ fn complete_params(ctx: &mut CompletionContext) -> bool {
                 _ => None,
             }).collect();
 
-            param_completions(ctx, &callee, set, &exclude);
+            param_completions(ctx, &callee, callee_node.as_ref(), set, &exclude);
             return true;
         }
     }
@@ -629,6 +630,7 @@ fn complete_params(ctx: &mut CompletionContext) -> bool {
 fn param_completions(
     ctx: &mut CompletionContext,
     callee: &ast::Ident,
+    callee_node: Option<&LinkedNode>,
     set: bool,
     exclude: &[ast::Ident],
 ) {
@@ -636,7 +638,12 @@ fn param_completions(
         if let Some(Value::Func(func)) = ctx.global.get(callee);
         if let Some(info) = func.info();
         then { info }
-        else { return; }
+        else {
+            if let (Some(node), false) = (callee_node, set) {
+                closure_param_completions(ctx, node, exclude);
+            }
+            return;
+        }
     };
 
     for param in &info.params {
@@ -651,7 +658,7 @@ fn param_completions(
         if param.named {
             ctx.completions.push(Completion {
                 kind: CompletionKind::Param,
-                label: param.name.into(),
+                label: param.name.clone(),
                 apply: Some(eco_format!("{}: ${{}}", param.name)),
                 detail: Some(plain_docs_sentence(param.docs)),
             });
@@ -667,6 +674,42 @@ fn param_completions(
     }
 }
 
+/// Add completions for the named parameters of a user-defined function.
+fn closure_param_completions(
+    ctx: &mut CompletionContext,
+    callee: &LinkedNode,
+    exclude: &[ast::Ident],
+) {
+    let func = match analyze_expr(ctx.world, callee).into_iter().next() {
+        Some(Value::Func(func)) => func,
+        _ => return,
+    };
+
+    for param in func.params().into_iter().flatten() {
+        let Param::Named(name, default, annotation) = param else { continue };
+        if exclude.iter().any(|ident| ident.as_str() == name.as_str()) {
+            continue;
+        }
+
+        let mut detail = eco_format!("Defaults to `{}`.", default.repr());
+        if let Some(annotation) = annotation {
+            let types: Vec<_> = annotation.types().collect();
+            detail = eco_format!("Must be {}. {detail}", separated_list(&types, "or"));
+        }
+
+        ctx.completions.push(Completion {
+            kind: CompletionKind::Param,
+            label: name.as_str().into(),
+            apply: Some(eco_format!("{}: ${{}}", name.as_str())),
+            detail: Some(detail),
+        });
+    }
+
+    if ctx.before.ends_with(',') {
+        ctx.enrich(" ", "");
+    }
+}
+
 /// Add completions for the values of a named function parameter.
 fn named_param_value_completions(
     ctx: &mut CompletionContext,
@@ -793,6 +836,12 @@ fn code_completions(ctx: &mut CompletionContext, hashtag: bool) {
         "Computes or inserts different things based on a condition.",
     );
 
+    ctx.snippet_completion(
+        "match expression",
+        "match ${value} {\n\t${pattern} => ${output},\n\t_ => ${output},\n}",
+        "Computes or inserts something based on the shape of a value.",
+    );
+
     ctx.snippet_completion(
         "while loop",
         "while ${1 < 2} {\n\t${}\n}",
@@ -0,0 +1,476 @@
+use std::ops::Range;
+
+use ecow::EcoString;
+
+use super::analyze::resolve_import;
+use crate::syntax::ast::{self, AstNode};
+use crate::syntax::{LinkedNode, Source, SourceId, SyntaxKind};
+use crate::World;
+
+/// How many imports to follow at most when looking for a definition.
+const MAX_DEPTH: usize = 16;
+
+/// Where an identifier or label is defined.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Definition {
+    /// The source file containing the definition.
+    pub source: SourceId,
+    /// The byte range of the defining identifier or label in the file. Empty
+    /// if the identifier refers to a whole module.
+    pub range: Range<usize>,
+}
+
+/// An occurrence of an identifier or label.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Reference {
+    /// The source file containing the occurrence.
+    pub source: SourceId,
+    /// The byte range of the occurrence in the file.
+    pub range: Range<usize>,
+    /// Whether this occurrence is the definition itself.
+    pub definition: bool,
+}
+
+/// Find the definition of the identifier or label at the cursor.
+///
+/// Resolves an identifier to the `let` binding, closure parameter or loop
+/// variable that defines it. Identifiers bound by a module import are followed
+/// into the imported file. A reference or label resolves to the label in
+/// markup that marks the labelled element.
+pub fn definition(
+    world: &(dyn World + 'static),
+    source: &Source,
+    cursor: usize,
+) -> Option<Definition> {
+    let root = LinkedNode::new(source.root());
+    let leaf = navigable_leaf(&root, cursor)?;
+    match label_name(&leaf) {
+        Some(label) => {
+            let mut found = None;
+            for id in sources(world, source) {
+                let source = world.source(id);
+                visit(&LinkedNode::new(source.root()), &mut |node| {
+                    if found.is_none() && is_label_definition(node, &label) {
+                        found = Some(Definition { source: id, range: node.range() });
+                    }
+                });
+            }
+            found
+        }
+        None => resolve(world, source, &leaf),
+    }
+}
+
+/// Find all occurrences of the identifier or label at the cursor.
+///
+/// Searches the file at the cursor and all files reachable from it or from
+/// the main file through imports and includes. The definition is part of the
+/// result if it was found.
+pub fn references(
+    world: &(dyn World + 'static),
+    source: &Source,
+    cursor: usize,
+) -> Vec<Reference> {
+    let root = LinkedNode::new(source.root());
+    let Some(leaf) = navigable_leaf(&root, cursor) else { return vec![] };
+    let sources = sources(world, source);
+    let mut references = vec![];
+
+    if let Some(label) = label_name(&leaf) {
+        for id in sources {
+            let source = world.source(id);
+            visit(&LinkedNode::new(source.root()), &mut |node| {
+                if label_name(node).as_ref() == Some(&label) {
+                    references.push(Reference {
+                        source: id,
+                        range: node.range(),
+                        definition: is_label_definition(node, &label),
+                    });
+                }
+            });
+        }
+        return references;
+    }
+
+    let Some(target) = resolve(world, source, &leaf) else { return vec![] };
+
+    // Imports may rename the identifier, so we also need to look at all names
+    // it is imported as.
+    let mut names = vec![leaf.get().text().clone()];
+    if let Some(name) = world.source(target.source).get(target.range.clone()) {
+        names.push(name.into());
+    }
+
+    let mut renames = vec![];
+    for &id in &sources {
+        visit(&LinkedNode::new(world.source(id).root()), &mut |node| {
+            if let Some(renamed) = node.cast::<ast::RenamedImportItem>() {
+                renames.push((
+                    renamed.original_name().get().clone(),
+                    renamed.new_name().get().clone(),
+                ));
+            }
+        });
+    }
+
+    while let Some(i) = renames
+        .iter()
+        .position(|(original, new)| names.contains(original) && !names.contains(new))
+    {
+        names.push(renames[i].1.clone());
+    }
+
+    for id in sources {
+        let source = world.source(id);
+        visit(&LinkedNode::new(source.root()), &mut |node| {
+            if matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
+                && names.contains(node.get().text())
+                && resolve(world, source, node).as_ref() == Some(&target)
+            {
+                references.push(Reference {
+                    source: id,
+                    range: node.range(),
+                    definition: id == target.source && node.range() == target.range,
+                });
+            }
+        });
+    }
+
+    references
+}
+
+/// Find the identifier, label or reference at the cursor.
+pub(super) fn navigable_leaf<'a>(
+    root: &LinkedNode<'a>,
+    cursor: usize,
+) -> Option<LinkedNode<'a>> {
+    [cursor, cursor + 1]
+        .into_iter()
+        .filter_map(|cursor| root.leaf_at(cursor))
+        .find(|leaf| {
+            matches!(
+                leaf.kind(),
+                SyntaxKind::Ident
+                    | SyntaxKind::MathIdent
+                    | SyntaxKind::Label
+                    | SyntaxKind::RefMarker
+            )
+        })
+}
+
+/// The name of the label a label or reference refers to.
+pub(super) fn label_name(node: &LinkedNode) -> Option<EcoString> {
+    match node.kind() {
+        SyntaxKind::Label => node.cast::<ast::Label>().map(|label| label.get().into()),
+        SyntaxKind::RefMarker => Some(node.text().trim_start_matches('@').into()),
+        _ => None,
+    }
+}
+
+/// Whether the node is a label in markup that marks an element, as opposed to
+/// a label used as a value in code.
+pub(super) fn is_label_definition(node: &LinkedNode, label: &str) -> bool {
+    node.kind() == SyntaxKind::Label
+        && node.parent_kind() == Some(SyntaxKind::Markup)
+        && label_name(node).as_deref() == Some(label)
+}
+
+/// Find the definition of an identifier leaf.
+pub(super) fn resolve(
+    world: &(dyn World + 'static),
+    source: &Source,
+    leaf: &LinkedNode,
+) -> Option<Definition> {
+    lookup(world, source, leaf, leaf.get().text())
+}
+
+/// Find the definition a name would refer to at the position of an identifier
+/// leaf.
+pub(super) fn lookup(
+    world: &(dyn World + 'static),
+    source: &Source,
+    leaf: &LinkedNode,
+    name: &str,
+) -> Option<Definition> {
+    // Fields, named arguments and dictionary keys are not bound in any scope.
+    match leaf.parent_kind() {
+        Some(SyntaxKind::FieldAccess) if leaf.index() > 0 => return None,
+        Some(SyntaxKind::Named)
+            if leaf.index() == 0
+                && leaf.parent().and_then(|parent| parent.parent_kind())
+                    != Some(SyntaxKind::Params) =>
+        {
+            return None
+        }
+        _ => {}
+    }
+
+    let mut node = leaf.clone();
+    while let Some(parent) = node.parent() {
+        match parent.kind() {
+            // The identifier is the one being bound.
+            SyntaxKind::LetBinding => {
+                if let Some(found) = binding(world, source, parent, name, 0) {
+                    if found.range == leaf.range() {
+                        return Some(found);
+                    }
+                }
+            }
+            // The identifier is an imported item. Renamed items are looked up
+            // by the name they are bound to.
+            SyntaxKind::ModuleImport => {
+                let name = match leaf
+                    .parent()
+                    .and_then(|parent| parent.cast::<ast::RenamedImportItem>())
+                {
+                    Some(renamed) if leaf.text() == name => renamed.new_name().take(),
+                    _ => name.into(),
+                };
+                return binding(world, source, parent, &name, 0);
+            }
+            SyntaxKind::Closure | SyntaxKind::ForLoop => {
+                if let Some(found) = scoped(source, parent, &node, leaf, name) {
+                    return Some(found);
+                }
+            }
+            _ => {}
+        }
+        node = parent.clone();
+    }
+
+    // Search the scopes visible from the identifier, innermost first.
+    let mut node = leaf.clone();
+    loop {
+        let mut sibling = node.clone();
+        while let Some(prev) = sibling.prev_sibling() {
+            if let Some(found) = binding(world, source, &prev, name, 0) {
+                return Some(found);
+            }
+            sibling = prev;
+        }
+        node = node.parent()?.clone();
+    }
+}
+
+/// Find the definition of a name that a closure or for loop binds.
+///
+/// The parameters and the name of a closure are visible in its body and the
+/// variables of a loop are visible in its body. Apart from that, a bound
+/// identifier only resolves to itself.
+fn scoped(
+    source: &Source,
+    parent: &LinkedNode,
+    child: &LinkedNode,
+    leaf: &LinkedNode,
+    name: &str,
+) -> Option<Definition> {
+    let (idents, head) = if let Some(closure) = parent.cast::<ast::Closure>() {
+        let params = closure.params();
+        let mut idents: Vec<_> = params
+            .children()
+            .flat_map(|param| match param {
+                ast::Param::Pos(pattern) => pattern.idents(),
+                ast::Param::Named(named) => vec![named.name()],
+                ast::Param::Sink(spread) => spread.name().into_iter().collect(),
+            })
+            .collect();
+        idents.extend(closure.name());
+        (idents, params.span())
+    } else {
+        let for_loop = parent.cast::<ast::ForLoop>()?;
+        (for_loop.pattern().idents(), for_loop.iter().span())
+    };
+
+    let ident = idents.into_iter().find(|ident| ident.as_str() == name)?;
+    let found = Definition {
+        source: source.id(),
+        range: source.range(ident.span()),
+    };
+
+    (found.range == leaf.range() || child.span() != head).then_some(found)
+}
+
+/// Find the definition of a name that a `let` binding or module import binds.
+fn binding(
+    world: &(dyn World + 'static),
+    source: &Source,
+    node: &LinkedNode,
+    name: &str,
+    depth: usize,
+) -> Option<Definition> {
+    if let Some(binding) = node.cast::<ast::LetBinding>() {
+        let ident = binding
+            .kind()
+            .idents()
+            .into_iter()
+            .find(|ident| ident.as_str() == name)?;
+        return Some(Definition {
+            source: source.id(),
+            range: source.range(ident.span()),
+        });
+    }
+
+    let import = node.cast::<ast::ModuleImport>()?;
+    let target = || {
+        let ast::Expr::Str(path) = import.source() else { return None };
+        let (id, _) = resolve_import(world, source, &path.get())?;
+        Some(world.source(id))
+    };
+
+    if import.new_name().map_or(false, |ident| ident.as_str() == name) {
+        return Some(Definition { source: target()?.id(), range: 0..0 });
+    }
+
+    match import.imports()? {
+        ast::Imports::Items(items) => {
+            let item =
+                items.into_iter().find(|item| item.bound_name().as_str() == name)?;
+            target()
+                .filter(|_| depth < MAX_DEPTH)
+                .and_then(|target| {
+                    exported(world, target, item.original_name().as_str(), depth)
+                })
+                .or_else(|| {
+                    let ident = item.bound_name();
+                    Some(Definition {
+                        source: source.id(),
+                        range: source.range(ident.span()),
+                    })
+                })
+        }
+        ast::Imports::Wildcard => {
+            let target = target().filter(|_| depth < MAX_DEPTH)?;
+            exported(world, target, name, depth)
+        }
+    }
+}
+
+/// Find the definition of a name exported by a source file.
+fn exported(
+    world: &(dyn World + 'static),
+    source: &Source,
+    name: &str,
+    depth: usize,
+) -> Option<Definition> {
+    // Later definitions shadow earlier ones.
+    LinkedNode::new(source.root())
+        .children()
+        .rev()
+        .find_map(|child| binding(world, source, &child, name, depth + 1))
+}
+
+/// The files reachable through imports and includes from the main file and
+/// the given one.
+pub(super) fn sources(world: &(dyn World + 'static), source: &Source) -> Vec<SourceId> {
+    let mut ids = vec![world.main().id()];
+    if !ids.contains(&source.id()) {
+        ids.push(source.id());
+    }
+
+    let mut i = 0;
+    while i < ids.len() {
+        let source = world.source(ids[i]);
+        visit(&LinkedNode::new(source.root()), &mut |node| {
+            let path = match node.cast::<ast::Expr>() {
+                Some(ast::Expr::Import(import)) => import.source(),
+                Some(ast::Expr::Include(include)) => include.source(),
+                _ => return,
+            };
+
+            let ast::Expr::Str(path) = path else { return };
+            if let Some((id, _)) = resolve_import(world, source, &path.get()) {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        });
+        i += 1;
+    }
+
+    ids
+}
+
+/// Call a function for a node and all its descendants.
+pub(super) fn visit<'a>(node: &LinkedNode<'a>, f: &mut impl FnMut(&LinkedNode<'a>)) {
+    f(node);
+    for child in node.children() {
+        visit(&child, f);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ide::tests::TestWorld;
+
+    /// Find the definition of the `nth` occurrence of the needle in a file.
+    #[track_caller]
+    fn test(
+        world: &TestWorld,
+        file: u16,
+        needle: &str,
+        nth: usize,
+    ) -> Option<(u16, Range<usize>)> {
+        let source = world.source(SourceId::from_u16(file));
+        let cursor = world.find(file, needle, nth).start;
+        definition(world, source, cursor).map(|def| (def.source.into_u16(), def.range))
+    }
+
+    #[test]
+    fn test_definition_let_binding() {
+        let world =
+            TestWorld::new(&[("/main.typ", "#let x = 1\n#let x = x + 1\n#x\n#y")]);
+        assert_eq!(test(&world, 0, "x", 0), Some((0, world.find(0, "x", 0))));
+        assert_eq!(test(&world, 0, "x", 1), Some((0, world.find(0, "x", 1))));
+        assert_eq!(test(&world, 0, "x", 2), Some((0, world.find(0, "x", 0))));
+        assert_eq!(test(&world, 0, "x", 3), Some((0, world.find(0, "x", 1))));
+        assert_eq!(test(&world, 0, "y", 0), None);
+    }
+
+    #[test]
+    fn test_definition_closure_params() {
+        let world = TestWorld::new(&[(
+            "/main.typ",
+            "#let f(a, b: 2, ..c) = a + b + c.len()\n#f(1)\n#a",
+        )]);
+        assert_eq!(test(&world, 0, "a", 1), Some((0, world.find(0, "a", 0))));
+        assert_eq!(test(&world, 0, "b", 1), Some((0, world.find(0, "b", 0))));
+        assert_eq!(test(&world, 0, "c", 1), Some((0, world.find(0, "c", 0))));
+        assert_eq!(test(&world, 0, "f", 1), Some((0, world.find(0, "f", 0))));
+        assert_eq!(test(&world, 0, "a", 2), None);
+    }
+
+    #[test]
+    fn test_definition_import_items() {
+        let world = TestWorld::new(&[
+            (
+                "/main.typ",
+                "#import \"other.typ\": alpha, beta as gamma\n\
+                 #import \"other.typ\" as lib\n\
+                 #alpha #gamma #lib",
+            ),
+            ("/other.typ", "#let alpha = 1\n#let beta = 2"),
+        ]);
+        assert_eq!(test(&world, 0, "alpha", 0), Some((1, world.find(1, "alpha", 0))));
+        assert_eq!(test(&world, 0, "alpha", 1), Some((1, world.find(1, "alpha", 0))));
+        assert_eq!(test(&world, 0, "beta", 0), Some((1, world.find(1, "beta", 0))));
+        assert_eq!(test(&world, 0, "gamma", 0), Some((1, world.find(1, "beta", 0))));
+        assert_eq!(test(&world, 0, "gamma", 1), Some((1, world.find(1, "beta", 0))));
+        assert_eq!(test(&world, 0, "lib", 1), Some((1, 0..0)));
+    }
+
+    #[test]
+    fn test_definition_labels() {
+        let world = TestWorld::new(&[
+            (
+                "/main.typ",
+                "#include \"chapter.typ\"\nSee @intro and @outro.\n= Outro <outro>",
+            ),
+            ("/chapter.typ", "= Introduction <intro>"),
+        ]);
+        assert_eq!(test(&world, 0, "@intro", 0), Some((1, world.find(1, "<intro>", 0))));
+        assert_eq!(test(&world, 0, "@outro", 0), Some((0, world.find(0, "<outro>", 0))));
+        assert_eq!(test(&world, 0, "<outro>", 0), Some((0, world.find(0, "<outro>", 0))));
+        assert_eq!(test(&world, 1, "<intro>", 0), Some((1, world.find(1, "<intro>", 0))));
+    }
+}
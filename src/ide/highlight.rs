@@ -201,6 +201,7 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Show => Some(Tag::Keyword),
         SyntaxKind::If => Some(Tag::Keyword),
         SyntaxKind::Else => Some(Tag::Keyword),
+        SyntaxKind::Match => Some(Tag::Keyword),
         SyntaxKind::For => Some(Tag::Keyword),
         SyntaxKind::In => Some(Tag::Keyword),
         SyntaxKind::While => Some(Tag::Keyword),
@@ -233,14 +234,18 @@ pub fn highlight(node: &LinkedNode) -> Option<Tag> {
         SyntaxKind::Spread => None,
         SyntaxKind::Closure => None,
         SyntaxKind::Params => None,
+        SyntaxKind::TypeAnnotation => None,
         SyntaxKind::LetBinding => None,
         SyntaxKind::SetRule => None,
         SyntaxKind::ShowRule => None,
         SyntaxKind::Conditional => None,
+        SyntaxKind::MatchExpr => None,
+        SyntaxKind::MatchArm => None,
         SyntaxKind::WhileLoop => None,
         SyntaxKind::ForLoop => None,
         SyntaxKind::ModuleImport => None,
         SyntaxKind::ImportItems => None,
+        SyntaxKind::RenamedImportItem => None,
         SyntaxKind::ModuleInclude => None,
         SyntaxKind::LoopBreak => None,
         SyntaxKind::LoopContinue => None,
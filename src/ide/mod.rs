@@ -2,14 +2,18 @@
 
 mod analyze;
 mod complete;
+mod definition;
 mod highlight;
 mod jump;
+mod rename;
 mod tooltip;
 
 pub use self::analyze::analyze_labels;
 pub use self::complete::*;
+pub use self::definition::*;
 pub use self::highlight::*;
 pub use self::jump::*;
+pub use self::rename::*;
 pub use self::tooltip::*;
 
 use std::fmt::Write;
@@ -95,3 +99,147 @@ fn summarize_font_family<'a>(variants: impl Iterator<Item = &'a FontInfo>) -> Ec
 
     detail
 }
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+    use std::path::{Path, PathBuf};
+
+    use comemo::Prehashed;
+
+    use crate::diag::{At, FileError, FileResult, PackageError, PackageResult};
+    use crate::doc::Document;
+    use crate::eval::{Datetime, LangItems, Library, Module, PackageSpec};
+    use crate::font::{Font, FontBook};
+    use crate::geom::{Abs, Dir};
+    use crate::model::{Content, Styles};
+    use crate::syntax::{Source, SourceId};
+    use crate::util::Buffer;
+    use crate::World;
+
+    /// A world of in-memory source files without fonts or a standard library.
+    ///
+    /// The first file is the main file.
+    pub struct TestWorld {
+        sources: Vec<Source>,
+        library: Prehashed<Library>,
+        book: Prehashed<FontBook>,
+    }
+
+    impl TestWorld {
+        /// Create a world from pairs of paths and source texts.
+        pub fn new(files: &[(&str, &str)]) -> Self {
+            let sources = files
+                .iter()
+                .enumerate()
+                .map(|(i, &(path, text))| {
+                    Source::new(
+                        SourceId::from_u16(i as u16),
+                        Path::new(path),
+                        text.into(),
+                    )
+                })
+                .collect();
+            Self {
+                sources,
+                library: Prehashed::new(library()),
+                book: Prehashed::new(FontBook::new()),
+            }
+        }
+
+        /// The byte range of the `nth` occurrence of the needle in a file.
+        pub fn find(&self, file: u16, needle: &str, nth: usize) -> Range<usize> {
+            let text = self.source(SourceId::from_u16(file)).text();
+            let start = text.match_indices(needle).nth(nth).unwrap().0;
+            start..start + needle.len()
+        }
+    }
+
+    impl World for TestWorld {
+        fn library(&self) -> &Prehashed<Library> {
+            &self.library
+        }
+
+        fn main(&self) -> &Source {
+            &self.sources[0]
+        }
+
+        fn resolve(&self, path: &Path) -> FileResult<SourceId> {
+            self.sources
+                .iter()
+                .find(|source| source.path() == path)
+                .map(Source::id)
+                .ok_or_else(|| FileError::NotFound(path.into()))
+        }
+
+        fn source(&self, id: SourceId) -> &Source {
+            &self.sources[usize::from(id.into_u16())]
+        }
+
+        fn book(&self) -> &Prehashed<FontBook> {
+            &self.book
+        }
+
+        fn font(&self, _: usize) -> Option<Font> {
+            None
+        }
+
+        fn file(&self, path: &Path) -> FileResult<Buffer> {
+            Err(FileError::NotFound(path.into()))
+        }
+
+        fn package(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
+            Err(PackageError::NotFound(spec.clone()))
+        }
+
+        fn today(&self, _: Option<i64>) -> Option<Datetime> {
+            None
+        }
+    }
+    /// A library without any definitions whose lang items produce empty
+    /// content.
+    fn library() -> Library {
+        let items = LangItems {
+            layout: |_, _, _| Ok(Document::default()),
+            em: |_| Abs::zero(),
+            dir: |_| Dir::LTR,
+            space: Content::empty,
+            linebreak: Content::empty,
+            text: |_| Content::empty(),
+            text_func: Content::empty().func(),
+            text_str: |_| None,
+            smart_quote: |_| Content::empty(),
+            parbreak: Content::empty,
+            strong: |_| Content::empty(),
+            emph: |_| Content::empty(),
+            raw: |_, _, _| Content::empty(),
+            raw_languages: Vec::new,
+            link: |_| Content::empty(),
+            reference: |_, _| Content::empty(),
+            bibliography_keys: |_, _| vec![],
+            heading: |_, _| Content::empty(),
+            heading_func: Content::empty().func(),
+            list_item: |_| Content::empty(),
+            enum_item: |_, _| Content::empty(),
+            term_item: |_, _| Content::empty(),
+            equation: |_, _| Content::empty(),
+            math_align_point: Content::empty,
+            math_delimited: |_, _, _| Content::empty(),
+            math_attach: |_, _, _, _, _, _, _| Content::empty(),
+            math_accent: |_, _| Content::empty(),
+            math_frac: |_, _| Content::empty(),
+            library_types: &[],
+            library_method: |_, dynamic, method, _, span| {
+                Err(format!("type {} has no method `{method}`", dynamic.type_name()))
+                    .at(span)
+            },
+        };
+
+        Library {
+            global: Module::new("global"),
+            math: Module::new("math"),
+            styles: Styles::new(),
+            items,
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::ops::Range;
+
+use ecow::{eco_format, EcoString};
+
+use super::definition::{
+    label_name, lookup, navigable_leaf, resolve, sources, visit, Definition,
+};
+use crate::diag::StrResult;
+use crate::syntax::{is_ident, parse, LinkedNode, Source, SourceId, SyntaxKind};
+use crate::World;
+
+/// A replacement of a range of text in a source file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Edit {
+    /// The source file to edit.
+    pub source: SourceId,
+    /// The byte range of the text to replace.
+    pub range: Range<usize>,
+    /// The replacement text.
+    pub text: EcoString,
+}
+
+/// Rename the identifier or label at the cursor.
+///
+/// Returns the edits to apply to all files that are reachable through imports
+/// and includes, sorted by file and position. Renaming an identifier renames
+/// its definition and all uses it is visible to. Renaming a label also renames
+/// all references to it.
+///
+/// Fails if the new name is invalid or if renaming would change what some
+/// identifier or reference refers to.
+pub fn rename(
+    world: &(dyn World + 'static),
+    source: &Source,
+    cursor: usize,
+    new_name: &str,
+) -> StrResult<Vec<Edit>> {
+    let root = LinkedNode::new(source.root());
+    let Some(leaf) = navigable_leaf(&root, cursor) else {
+        return Err("expected identifier or label".into());
+    };
+
+    let sources = sources(world, source);
+    let edits = match label_name(&leaf) {
+        Some(label) if label == new_name => return Ok(vec![]),
+        Some(label) => rename_label(world, &sources, &label, new_name)?,
+        None if leaf.text() == new_name => return Ok(vec![]),
+        None => rename_ident(world, source, &sources, &leaf, new_name)?,
+    };
+
+    check_syntax(world, &edits, new_name)?;
+    Ok(edits)
+}
+
+/// Rename a label and all references to it.
+fn rename_label(
+    world: &(dyn World + 'static),
+    sources: &[SourceId],
+    label: &str,
+    new_name: &str,
+) -> StrResult<Vec<Edit>> {
+    let mut edits = vec![];
+    let mut taken = false;
+    for &id in sources {
+        visit(&LinkedNode::new(world.source(id).root()), &mut |node| {
+            let Some(name) = label_name(node) else { return };
+            if name == new_name {
+                taken = true;
+            } else if name == label {
+                let text = if node.kind() == SyntaxKind::Label {
+                    eco_format!("<{new_name}>")
+                } else {
+                    eco_format!("@{new_name}")
+                };
+                edits.push(Edit { source: id, range: node.range(), text });
+            }
+        });
+    }
+
+    if taken {
+        return Err(eco_format!("label <{new_name}> is already in use"));
+    }
+
+    Ok(edits)
+}
+
+/// Rename an identifier along with its definition and all uses.
+fn rename_ident(
+    world: &(dyn World + 'static),
+    source: &Source,
+    sources: &[SourceId],
+    leaf: &LinkedNode,
+    new_name: &str,
+) -> StrResult<Vec<Edit>> {
+    if !is_ident(new_name) {
+        return Err(eco_format!("{new_name} is not a valid identifier"));
+    }
+
+    let name = leaf.get().text().clone();
+    let Some(target) = resolve(world, source, leaf) else {
+        return Err(eco_format!("unknown variable: {name}"));
+    };
+
+    if target.range.is_empty() {
+        return Err("cannot rename a whole module".into());
+    }
+
+    let mut edits = vec![];
+    let mut result = Ok(());
+    for &id in sources {
+        let source = world.source(id);
+        visit(&LinkedNode::new(source.root()), &mut |node| {
+            if result.is_err()
+                || !matches!(node.kind(), SyntaxKind::Ident | SyntaxKind::MathIdent)
+            {
+                return;
+            }
+
+            let text = node.get().text();
+            if *text == name && resolve(world, source, node).as_ref() == Some(&target) {
+                // A renamed use must not be captured by another definition.
+                if lookup(world, source, node, new_name).is_some() {
+                    result = Err(eco_format!("{new_name} is already defined"));
+                }
+                edits.push(Edit {
+                    source: id,
+                    range: node.range(),
+                    text: new_name.into(),
+                });
+            } else if text == new_name && captures(world, source, node, &name, &target) {
+                // An existing use must not be captured by the renamed
+                // definition.
+                result =
+                    Err(eco_format!("{name} would shadow existing uses of {new_name}"));
+            }
+        });
+    }
+
+    result.map(|_| edits)
+}
+
+/// Whether an identifier would refer to the target definition if it were
+/// renamed to the identifier's name.
+///
+/// This is the case if the target is visible from the identifier and the
+/// identifier is currently unbound or bound to a definition that the target
+/// shadows, that is, one that is also visible from the target.
+fn captures(
+    world: &(dyn World + 'static),
+    source: &Source,
+    node: &LinkedNode,
+    name: &str,
+    target: &Definition,
+) -> bool {
+    if lookup(world, source, node, name).as_ref() != Some(target) {
+        return false;
+    }
+
+    let Some(current) = resolve(world, source, node) else { return true };
+    let target_source = world.source(target.source);
+    let root = LinkedNode::new(target_source.root());
+    root.leaf_at(target.range.start + 1)
+        .and_then(|leaf| lookup(world, target_source, &leaf, node.text()))
+        == Some(current)
+}
+
+/// Check that the edits produce the intended syntax: Each replacement must
+/// parse into the same kind of node as before and no new syntax errors, for
+/// example due to duplicate bindings, may appear.
+fn check_syntax(
+    world: &(dyn World + 'static),
+    edits: &[Edit],
+    new_name: &str,
+) -> StrResult<()> {
+    let mut i = 0;
+    while i < edits.len() {
+        let source = world.source(edits[i].source);
+        let root = LinkedNode::new(source.root());
+
+        let mut text = String::new();
+        let mut replaced = vec![];
+        let mut last = 0;
+        while let Some(edit) = edits.get(i).filter(|edit| edit.source == source.id()) {
+            let kind = root.leaf_at(edit.range.start + 1).map(|leaf| leaf.kind());
+            text.push_str(&source.text()[last..edit.range.start]);
+            let start = text.len();
+            text.push_str(&edit.text);
+            replaced.push((kind, start..text.len()));
+            last = edit.range.end;
+            i += 1;
+        }
+        text.push_str(&source.text()[last..]);
+
+        let node = parse(&text);
+        if node.errors().len() > source.root().errors().len() {
+            return Err(eco_format!(
+                "renaming to {new_name} would result in a syntax error"
+            ));
+        }
+
+        let root = LinkedNode::new(&node);
+        for (kind, range) in replaced {
+            let leaf = root.leaf_at(range.start + 1);
+            if leaf.map(|leaf| (leaf.kind(), leaf.range()))
+                != kind.map(|kind| (kind, range))
+            {
+                return Err(eco_format!("{new_name} is not a valid name here"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ide::tests::TestWorld;
+
+    /// Rename the `nth` occurrence of the needle in a file.
+    #[track_caller]
+    fn test(
+        world: &TestWorld,
+        file: u16,
+        needle: &str,
+        nth: usize,
+        new_name: &str,
+    ) -> StrResult<Vec<(u16, Range<usize>, EcoString)>> {
+        let source = world.source(SourceId::from_u16(file));
+        let cursor = world.find(file, needle, nth).start;
+        let edits = rename(world, source, cursor, new_name)?;
+        Ok(edits
+            .into_iter()
+            .map(|edit| (edit.source.into_u16(), edit.range, edit.text))
+            .collect())
+    }
+
+    #[test]
+    fn test_rename_across_files() {
+        let world = TestWorld::new(&[
+            ("/main.typ", "#import \"other.typ\": value\n#value"),
+            ("/other.typ", "#let value = 1\n#value"),
+        ]);
+        assert_eq!(
+            test(&world, 0, "value", 1, "amount"),
+            Ok(vec![
+                (0, world.find(0, "value", 0), "amount".into()),
+                (0, world.find(0, "value", 1), "amount".into()),
+                (1, world.find(1, "value", 0), "amount".into()),
+                (1, world.find(1, "value", 1), "amount".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rename_keeps_inner_bindings() {
+        let world = TestWorld::new(&[("/main.typ", "#let x = 1\n#let f(y) = y\n#x")]);
+        assert_eq!(
+            test(&world, 0, "x", 1, "y"),
+            Ok(vec![
+                (0, world.find(0, "x", 0), "y".into()),
+                (0, world.find(0, "x", 1), "y".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rename_label() {
+        let world = TestWorld::new(&[
+            ("/main.typ", "#include \"chapter.typ\"\n= Intro <intro>"),
+            ("/chapter.typ", "See @intro."),
+        ]);
+        assert_eq!(
+            test(&world, 1, "@intro", 0, "start"),
+            Ok(vec![
+                (0, world.find(0, "<intro>", 0), "<start>".into()),
+                (1, world.find(1, "@intro", 0), "@start".into()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_rename_already_defined() {
+        let world = TestWorld::new(&[("/main.typ", "#let x = 1\n#let f(y) = x + y")]);
+        assert_eq!(test(&world, 0, "x", 0, "y"), Err("y is already defined".into()));
+    }
+
+    #[test]
+    fn test_rename_would_shadow() {
+        let world = TestWorld::new(&[("/main.typ", "#let x = 1\n#text(fill: red)[#x]")]);
+        assert_eq!(
+            test(&world, 0, "x", 0, "red"),
+            Err("x would shadow existing uses of red".into())
+        );
+    }
+
+    #[test]
+    fn test_rename_invalid_identifier() {
+        let world = TestWorld::new(&[("/main.typ", "#let x = 1\n#x")]);
+        assert_eq!(
+            test(&world, 0, "x", 0, "1x"),
+            Err("1x is not a valid identifier".into())
+        );
+    }
+
+    #[test]
+    fn test_rename_label_already_in_use() {
+        let world = TestWorld::new(&[("/main.typ", "= A <a>\n= B <b>\nSee @a.")]);
+        assert_eq!(
+            test(&world, 0, "<a>", 0, "b"),
+            Err("label <b> is already in use".into())
+        );
+    }
+}
@@ -7,7 +7,7 @@ use if_chain::if_chain;
 use super::analyze::analyze_labels;
 use super::{analyze_expr, plain_docs_sentence, summarize_font_family};
 use crate::doc::Frame;
-use crate::eval::{CastInfo, Tracer, Value};
+use crate::eval::{CastInfo, Func, Param, Tracer, Value};
 use crate::geom::{round_2, Length, Numeric};
 use crate::syntax::{ast, LinkedNode, Source, SyntaxKind};
 use crate::util::pretty_comma_list;
@@ -59,6 +59,12 @@ fn expr_tooltip(world: &(dyn World + 'static), leaf: &LinkedNode) -> Option<Tool
             return Some(Tooltip::Text(plain_docs_sentence(docs)));
         }
 
+        if let Value::Func(func) = value {
+            if let Some(signature) = closure_signature(func) {
+                return Some(Tooltip::Code(signature));
+            }
+        }
+
         if let &Value::Length(length) = value {
             if let Some(tooltip) = length_tooltip(length) {
                 return Some(tooltip);
@@ -100,6 +106,46 @@ fn expr_tooltip(world: &(dyn World + 'static), leaf: &LinkedNode) -> Option<Tool
     (!tooltip.is_empty()).then(|| Tooltip::Code(tooltip.into()))
 }
 
+/// The signature of a user-defined function, written like its definition:
+/// `f(x as length, gap: 1pt) as content`.
+fn closure_signature(func: &Func) -> Option<EcoString> {
+    let params = func.params()?;
+    let annotate = |buf: &mut EcoString, annotation: Option<&ast::TypeAnnotation>| {
+        if let Some(annotation) = annotation {
+            buf.push(' ');
+            buf.push_str(&annotation.as_untyped().clone().into_text());
+        }
+    };
+
+    let mut signature = EcoString::from(func.name().unwrap_or_default());
+    signature.push('(');
+    for (i, param) in params.iter().enumerate() {
+        if i > 0 {
+            signature.push_str(", ");
+        }
+
+        match param {
+            Param::Pos(pattern, annotation) => {
+                signature.push_str(&pattern.as_untyped().clone().into_text());
+                annotate(&mut signature, annotation.as_ref());
+            }
+            Param::Named(name, default, annotation) => {
+                signature.push_str(name);
+                annotate(&mut signature, annotation.as_ref());
+                write!(signature, ": {}", default.repr()).unwrap();
+            }
+            Param::Sink(name) => {
+                signature.push_str("..");
+                signature.push_str(name.as_ref().map_or("", |name| name.as_str()));
+            }
+        }
+    }
+    signature.push(')');
+    annotate(&mut signature, func.returns());
+
+    Some(signature)
+}
+
 /// Tooltip text for a hovered length.
 fn length_tooltip(length: Length) -> Option<Tooltip> {
     length.em.is_zero().then(|| {
@@ -17,7 +17,7 @@
 //!   per page with items at fixed positions.
 //! - **Exporting:**
 //!   These frames can finally be exported into an output format (currently
-//!   supported are [PDF] and [raster images]).
+//!   supported are [PDF], [raster images], and [SVG]).
 //!
 //! [tokens]: syntax::SyntaxKind
 //! [parsed]: syntax::parse
@@ -31,6 +31,7 @@
 //! [frame]: doc::Frame
 //! [PDF]: export::pdf
 //! [raster images]: export::render
+//! [SVG]: export::svg
 
 #![recursion_limit = "1000"]
 #![allow(clippy::comparison_chain)]
@@ -52,25 +53,30 @@ pub mod image;
 pub mod model;
 pub mod syntax;
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use comemo::{Prehashed, Track};
 
-use crate::diag::{FileResult, SourceResult};
+use crate::diag::{FileResult, PackageResult, SourceResult};
 use crate::doc::Document;
-use crate::eval::{Library, Route, Tracer};
+use crate::eval::{Datetime, Library, PackageSpec, Route, Tracer};
 use crate::font::{Font, FontBook};
 use crate::syntax::{Source, SourceId};
 use crate::util::Buffer;
 
 /// Compile a source file into a fully layouted document.
-#[tracing::instrument(skip(world))]
-pub fn compile(world: &(dyn World + 'static)) -> SourceResult<Document> {
+///
+/// Warnings that occur during compilation are collected on the `tracer`, both
+/// if compilation succeeds and if it fails.
+#[tracing::instrument(skip(world, tracer))]
+pub fn compile(
+    world: &(dyn World + 'static),
+    tracer: &mut Tracer,
+) -> SourceResult<Document> {
     // Evaluate the source file into a module.
     let route = Route::default();
-    let mut tracer = Tracer::default();
     let module =
-        eval::eval(world.track(), route.track(), tracer.track_mut(), world.main())?;
+        eval::eval(world.track(), route.track(), tracer.track_mut(), None, world.main())?;
 
     tracing::info!("Evaluation successful");
 
@@ -108,4 +114,20 @@ pub trait World {
 
     /// Try to access a file at a path.
     fn file(&self, path: &Path) -> FileResult<Buffer>;
+
+    /// Try to resolve the root directory of a package.
+    ///
+    /// The directory must contain the package's `typst.toml` manifest. Files
+    /// within it are then accessed through [`resolve`](Self::resolve) and
+    /// [`file`](Self::file) like any other files.
+    fn package(&self, spec: &PackageSpec) -> PackageResult<PathBuf>;
+
+    /// Get the current date.
+    ///
+    /// If no offset is specified, the local date should be chosen. Otherwise,
+    /// the UTC date should be chosen with the corresponding offset in hours.
+    ///
+    /// If this function returns `None`, Typst's `datetime.today()` will return
+    /// an error.
+    fn today(&self, offset: Option<i64>) -> Option<Datetime>;
 }
@@ -5,6 +5,7 @@ use std::ops::{Add, AddAssign};
 
 use comemo::Prehashed;
 use ecow::{eco_format, EcoString, EcoVec};
+use serde::{Serialize, Serializer};
 
 use super::{
     element, Behave, Behaviour, ElemFunc, Element, Fold, Guard, Label, Locatable,
@@ -67,7 +68,7 @@ impl Content {
 
     /// The element function of the contained content.
     pub fn func(&self) -> ElemFunc {
-        self.func
+        self.func.clone()
     }
 
     /// Whether the content is an empty sequence.
@@ -109,13 +110,13 @@ impl Content {
     where
         C: ?Sized + 'static,
     {
-        (self.func.0.vtable)(TypeId::of::<C>()).is_some()
+        (self.func.vtable())(TypeId::of::<C>()).is_some()
     }
 
     /// Whether the contained element has the given capability.
     /// Where the capability is given by a `TypeId`.
     pub fn can_type_id(&self, type_id: TypeId) -> bool {
-        (self.func.0.vtable)(type_id).is_some()
+        (self.func.vtable())(type_id).is_some()
     }
 
     /// Cast to a trait object if the contained element has the given
@@ -125,7 +126,7 @@ impl Content {
     where
         C: ?Sized + 'static,
     {
-        let vtable = (self.func.0.vtable)(TypeId::of::<C>())?;
+        let vtable = (self.func.vtable())(TypeId::of::<C>())?;
         let data = self as *const Self as *const ();
         Some(unsafe { &*crate::util::fat::from_raw_parts(data, vtable) })
     }
@@ -137,7 +138,7 @@ impl Content {
     where
         C: ?Sized + 'static,
     {
-        let vtable = (self.func.0.vtable)(TypeId::of::<C>())?;
+        let vtable = (self.func.vtable())(TypeId::of::<C>())?;
         let data = self as *mut Self as *mut ();
         Some(unsafe { &mut *crate::util::fat::from_raw_parts_mut(data, vtable) })
     }
@@ -324,8 +325,10 @@ impl Content {
     }
 
     /// Check whether a show rule recipe is disabled.
-    pub fn is_guarded(&self, guard: Guard) -> bool {
-        self.attrs.contains(&Attr::Guard(guard))
+    pub fn is_guarded(&self, guard: &Guard) -> bool {
+        self.attrs
+            .iter()
+            .any(|attr| matches!(attr, Attr::Guard(own) if own == guard))
     }
 
     /// Whether no show rule was executed for this content so far.
@@ -448,6 +451,17 @@ impl Debug for Content {
     }
 }
 
+impl Serialize for Content {
+    /// Serializes the content as a map of its fields, plus the name of its
+    /// element function under `func`.
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            std::iter::once(("func", Value::Str(self.func().name().into())))
+                .chain(self.fields().map(|(name, value)| (name.as_str(), value))),
+        )
+    }
+}
+
 impl Default for Content {
     fn default() -> Self {
         Self::empty()
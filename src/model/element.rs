@@ -1,15 +1,20 @@
 use std::any::TypeId;
 use std::fmt::{self, Debug, Formatter};
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use ecow::EcoString;
 use once_cell::sync::Lazy;
 
-use super::{Content, Selector, Styles};
-use crate::diag::SourceResult;
+use super::{
+    Content, Locatable, Property, Selector, Show, StyleChain, Styles, Synthesize, Vt,
+};
+use crate::diag::{SourceResult, StrResult};
 use crate::eval::{
-    cast_from_value, cast_to_value, Args, Dict, Func, FuncInfo, Value, Vm,
+    cast_from_value, cast_to_value, typecheck, Arg, Args, CastInfo, Dict, Func, FuncInfo,
+    Param, ParamInfo, Scope, Value, Vm,
 };
+use crate::syntax::{ast, Spanned};
 
 /// A document element.
 pub trait Element: Construct + Set + Sized + 'static {
@@ -35,17 +40,39 @@ pub trait Construct {
 /// An element's set rule.
 pub trait Set {
     /// Parse relevant arguments into style properties for this element.
-    fn set(args: &mut Args) -> SourceResult<Styles>;
+    fn set(vm: &mut Vm, args: &mut Args) -> SourceResult<Styles>;
 }
 
 /// An element's function.
-#[derive(Copy, Clone)]
-pub struct ElemFunc(pub(super) &'static NativeElemFunc);
+#[derive(Clone)]
+pub struct ElemFunc(pub(super) Repr);
+
+/// The different kinds of element functions.
+#[derive(Clone)]
+pub(super) enum Repr {
+    /// An element backed by a Rust type.
+    Native(&'static NativeElemFunc),
+    /// An element defined in Typst code.
+    User(Arc<UserElemFunc>),
+}
 
 impl ElemFunc {
+    /// Define a new element whose fields are the parameters of the given
+    /// closure.
+    ///
+    /// Defining the same element twice yields an equal element function.
+    pub fn define(name: EcoString, func: Func) -> StrResult<Self> {
+        let fields = UserElemFunc::fields(&func)?;
+        let info = UserElemFunc::info(name, &fields);
+        Ok(Self(Repr::User(Arc::new(UserElemFunc { func, fields, info }))))
+    }
+
     /// The function's name.
-    pub fn name(self) -> &'static str {
-        self.0.name
+    pub fn name(&self) -> &str {
+        match &self.0 {
+            Repr::Native(native) => native.name,
+            Repr::User(user) => user.info.name.as_str(),
+        }
     }
 
     /// Apply the given arguments to the function.
@@ -54,13 +81,19 @@ impl ElemFunc {
     }
 
     /// Extract details about the function.
-    pub fn info(&self) -> &'static FuncInfo {
-        &self.0.info
+    pub fn info(&self) -> &FuncInfo {
+        match &self.0 {
+            Repr::Native(native) => &native.info,
+            Repr::User(user) => &user.info,
+        }
     }
 
     /// Construct an element.
-    pub fn construct(self, vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
-        (self.0.construct)(vm, args)
+    pub fn construct(&self, vm: &mut Vm, args: &mut Args) -> SourceResult<Content> {
+        match &self.0 {
+            Repr::Native(native) => (native.construct)(vm, args),
+            Repr::User(user) => user.construct(self, args),
+        }
     }
 
     /// Whether the contained element has the given capability.
@@ -68,7 +101,7 @@ impl ElemFunc {
     where
         C: ?Sized + 'static,
     {
-        (self.0.vtable)(TypeId::of::<C>()).is_some()
+        (self.vtable())(TypeId::of::<C>()).is_some()
     }
 
     /// Create a selector for elements of this function.
@@ -83,11 +116,22 @@ impl ElemFunc {
     }
 
     /// Execute the set rule for the element and return the resulting style map.
-    pub fn set(self, mut args: Args) -> SourceResult<Styles> {
-        let styles = (self.0.set)(&mut args)?;
+    pub fn set(&self, vm: &mut Vm, mut args: Args) -> SourceResult<Styles> {
+        let styles = match &self.0 {
+            Repr::Native(native) => (native.set)(vm, &mut args)?,
+            Repr::User(user) => user.set(self, &mut args)?,
+        };
         args.finish()?;
         Ok(styles)
     }
+
+    /// The element's vtable for capability dispatch.
+    pub(super) fn vtable(&self) -> fn(of: TypeId) -> Option<*const ()> {
+        match &self.0 {
+            Repr::Native(native) => native.vtable,
+            Repr::User(_) => UserElem::vtable,
+        }
+    }
 }
 
 impl Debug for ElemFunc {
@@ -100,13 +144,25 @@ impl Eq for ElemFunc {}
 
 impl PartialEq for ElemFunc {
     fn eq(&self, other: &Self) -> bool {
-        std::ptr::eq(self.0, other.0)
+        match (&self.0, &other.0) {
+            (Repr::Native(a), Repr::Native(b)) => std::ptr::eq(*a, *b),
+            (Repr::User(a), Repr::User(b)) => {
+                Arc::ptr_eq(a, b) || (a.info.name == b.info.name && a.func == b.func)
+            }
+            _ => false,
+        }
     }
 }
 
 impl Hash for ElemFunc {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        state.write_usize(self.0 as *const _ as usize);
+        match &self.0 {
+            Repr::Native(native) => state.write_usize(*native as *const _ as usize),
+            Repr::User(user) => {
+                user.info.name.hash(state);
+                user.func.hash(state);
+            }
+        }
     }
 }
 
@@ -121,7 +177,7 @@ cast_to_value! {
 
 impl From<&'static NativeElemFunc> for ElemFunc {
     fn from(native: &'static NativeElemFunc) -> Self {
-        Self(native)
+        Self(Repr::Native(native))
     }
 }
 
@@ -134,11 +190,211 @@ pub struct NativeElemFunc {
     /// The element's constructor.
     pub construct: fn(&mut Vm, &mut Args) -> SourceResult<Content>,
     /// The element's set rule.
-    pub set: fn(&mut Args) -> SourceResult<Styles>,
+    pub set: fn(&mut Vm, &mut Args) -> SourceResult<Styles>,
     /// Details about the function.
     pub info: Lazy<FuncInfo>,
 }
 
+/// An element function defined in Typst code.
+///
+/// The parameters of the defining closure become the element's fields:
+/// Positional parameters are required fields and named parameters are
+/// settable fields with a default. The closure itself is the element's base
+/// show rule and receives the fields as arguments.
+pub struct UserElemFunc {
+    /// The closure that defines the element.
+    func: Func,
+    /// The element's fields.
+    fields: Vec<UserField>,
+    /// Details about the function.
+    info: FuncInfo,
+}
+
+/// A field of a user-defined element.
+struct UserField {
+    /// The field's name.
+    name: EcoString,
+    /// The field's default value, if it is settable.
+    default: Option<Value>,
+    /// The type annotation the field's values are checked against.
+    annotation: Option<ast::TypeAnnotation>,
+}
+
+impl UserElemFunc {
+    /// Determine the fields of an element from the parameters of its closure.
+    fn fields(func: &Func) -> StrResult<Vec<UserField>> {
+        let Some(params) = func.params() else {
+            return Err("expected a closure defining the element's fields".into());
+        };
+
+        let mut fields = vec![];
+        for param in params {
+            fields.push(match param {
+                Param::Pos(ast::Pattern::Normal(ast::Expr::Ident(ident)), annotation) => {
+                    UserField {
+                        name: ident.get().clone(),
+                        default: None,
+                        annotation: annotation.clone(),
+                    }
+                }
+                Param::Pos(..) => Err("element fields must be plain identifiers")?,
+                Param::Named(ident, default, annotation) => UserField {
+                    name: ident.get().clone(),
+                    default: Some(default.clone()),
+                    annotation: annotation.clone(),
+                },
+                Param::Sink(_) => Err("elements cannot have an argument sink")?,
+            });
+        }
+
+        Ok(fields)
+    }
+
+    /// Describe the element for autocompletion and documentation.
+    fn info(name: EcoString, fields: &[UserField]) -> FuncInfo {
+        let params = fields
+            .iter()
+            .map(|field| ParamInfo {
+                name: field.name.clone(),
+                docs: "",
+                cast: match &field.annotation {
+                    Some(annotation) => CastInfo::Union(
+                        annotation
+                            .types()
+                            .filter_map(|ty| known_type_name(&ty))
+                            .map(CastInfo::Type)
+                            .collect(),
+                    ),
+                    None => CastInfo::Any,
+                },
+                positional: field.default.is_none(),
+                named: field.default.is_some(),
+                variadic: false,
+                required: field.default.is_none(),
+                settable: field.default.is_some(),
+            })
+            .collect();
+
+        FuncInfo {
+            name: name.clone(),
+            display: name,
+            docs: "",
+            params,
+            returns: vec!["content"],
+            category: "",
+            scope: Scope::new(),
+        }
+    }
+
+    /// Construct an instance of the element.
+    fn construct(&self, func: &ElemFunc, args: &mut Args) -> SourceResult<Content> {
+        let mut content = Content::new(func.clone());
+        for field in &self.fields {
+            let value = match field.default {
+                Some(_) => args.named::<Spanned<Value>>(&field.name)?,
+                None => Some(args.expect::<Spanned<Value>>(&field.name)?),
+            };
+
+            if let Some(value) = value {
+                let value = typecheck(field.annotation.as_ref(), value)?;
+                content.push_field(field.name.clone(), value);
+            }
+        }
+        Ok(content)
+    }
+
+    /// Execute the element's set rule.
+    fn set(&self, func: &ElemFunc, args: &mut Args) -> SourceResult<Styles> {
+        let mut styles = Styles::new();
+        for field in self.fields.iter().filter(|field| field.default.is_some()) {
+            if let Some(value) = args.named::<Spanned<Value>>(&field.name)? {
+                let value = typecheck(field.annotation.as_ref(), value)?;
+                styles.set(Property::new(func.clone(), field.name.clone(), value));
+            }
+        }
+        Ok(styles)
+    }
+}
+
+/// Find the static name of a type named in a type annotation.
+///
+/// Closures reject unknown types when they are defined, so this finds the
+/// names of all types in an element's annotations.
+fn known_type_name(name: &str) -> Option<&'static str> {
+    Value::TYPE_NAMES
+        .iter()
+        .chain(item!(library_types))
+        .find(|&&known| known == name)
+        .copied()
+}
+
+/// Type-erased content of a user-defined element.
+///
+/// All user-defined elements share this type's capabilities. It looks up
+/// the element's definition through the content's element function.
+#[repr(transparent)]
+struct UserElem(Content);
+
+impl UserElem {
+    /// The element's definition.
+    fn def(&self) -> Arc<UserElemFunc> {
+        match self.0.func().0 {
+            Repr::User(user) => user,
+            Repr::Native(_) => unreachable!("native element with user-defined vtable"),
+        }
+    }
+
+    /// The vtable shared by all user-defined elements.
+    fn vtable(id: TypeId) -> Option<*const ()> {
+        let null = Self(Content::empty());
+        if id == TypeId::of::<dyn Locatable>() {
+            return Some(unsafe { crate::util::fat::vtable(&null as &dyn Locatable) });
+        }
+        if id == TypeId::of::<dyn Synthesize>() {
+            return Some(unsafe { crate::util::fat::vtable(&null as &dyn Synthesize) });
+        }
+        if id == TypeId::of::<dyn Show>() {
+            return Some(unsafe { crate::util::fat::vtable(&null as &dyn Show) });
+        }
+        None
+    }
+}
+
+impl Locatable for UserElem {}
+
+impl Synthesize for UserElem {
+    fn synthesize(&mut self, _: &mut Vt, styles: StyleChain) -> SourceResult<()> {
+        let func = self.0.func();
+        for field in &self.def().fields {
+            if let Some(default) = &field.default {
+                if !self.0.has(&field.name) {
+                    let value =
+                        styles.get(func.clone(), &field.name, None, || default.clone());
+                    self.0.push_field(field.name.clone(), value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Show for UserElem {
+    fn show(&self, vt: &mut Vt, _: StyleChain) -> SourceResult<Content> {
+        let def = self.def();
+        let span = self.0.span();
+        let mut args = Args::new(span, []);
+        for field in &def.fields {
+            let Some(value) = self.0.field(&field.name) else { continue };
+            args.items.push(Arg {
+                span,
+                name: field.default.is_some().then(|| field.name.clone().into()),
+                value: Spanned::new(value, span),
+            });
+        }
+        Ok(def.func.call_vt_args(vt, args)?.display())
+    }
+}
+
 /// A label for an element.
 #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Label(pub EcoString);
@@ -38,9 +38,11 @@ pub fn typeset(
     let mut introspector = Introspector::new(&[]);
 
     // Relayout until all introspections stabilize.
-    // If that doesn't happen within five attempts, we give up.
+    // If that doesn't happen within five attempts, we give up with warnings
+    // pointing to the introspections that kept changing.
     loop {
         tracing::info!("Layout iteration {iter}");
+        tracer.relayout();
 
         let constraint = Constraint::new();
         let mut provider = StabilityProvider::new();
@@ -56,7 +58,12 @@ pub fn typeset(
 
         introspector = Introspector::new(&document.pages);
 
-        if iter >= 5 || introspector.valid(&constraint) {
+        if introspector.valid(&constraint) {
+            break;
+        }
+
+        if iter >= 5 {
+            tracer.warn_non_convergence(iter);
             break;
         }
     }
@@ -18,7 +18,7 @@ pub fn applicable(target: &Content, styles: StyleChain) -> bool {
 
     // Find out whether any recipe matches and is unguarded.
     for recipe in styles.recipes() {
-        if recipe.applicable(target) && !target.is_guarded(Guard::Nth(n)) {
+        if recipe.applicable(target) && !target.is_guarded(&Guard::Nth(n)) {
             return true;
         }
         n -= 1;
@@ -66,7 +66,7 @@ pub fn realize(
     let mut realized = None;
     for recipe in styles.recipes() {
         let guard = Guard::Nth(n);
-        if recipe.applicable(target) && !target.is_guarded(guard) {
+        if recipe.applicable(target) && !target.is_guarded(&guard) {
             if let Some(content) = try_apply(vt, target, recipe, guard)? {
                 realized = Some(content);
                 break;
@@ -78,7 +78,7 @@ pub fn realize(
     // Realize if there was no matching recipe.
     if let Some(showable) = target.with::<dyn Show>() {
         let guard = Guard::Base(target.func());
-        if realized.is_none() && !target.is_guarded(guard) {
+        if realized.is_none() && !target.is_guarded(&guard) {
             realized = Some(showable.show(vt, styles)?);
         }
     }
@@ -134,7 +134,7 @@ fn try_apply(
                     result.push(make(&text[cursor..start]));
                 }
 
-                let piece = make(m.as_str()).guarded(guard);
+                let piece = make(m.as_str()).guarded(guard.clone());
                 let transformed = recipe.apply_vt(vt, piece)?;
                 result.push(transformed);
                 cursor = m.end();
@@ -219,7 +219,7 @@ pub enum Behaviour {
 }
 
 /// Guards content against being affected by the same show rule multiple times.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Guard {
     /// The nth recipe from the top of the chain.
     Nth(usize),
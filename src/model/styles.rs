@@ -78,8 +78,8 @@ impl Styles {
     pub fn interruption<T: Element>(&self) -> Option<Option<Span>> {
         let func = T::func();
         self.0.iter().find_map(|entry| match &**entry {
-            Style::Property(property) => property.is_of(func).then_some(property.span),
-            Style::Recipe(recipe) => recipe.is_of(func).then_some(Some(recipe.span)),
+            Style::Property(property) => property.is_of(&func).then_some(property.span),
+            Style::Recipe(recipe) => recipe.is_of(&func).then_some(Some(recipe.span)),
         })
     }
 }
@@ -164,13 +164,13 @@ impl Property {
     }
 
     /// Whether this property is the given one.
-    pub fn is(&self, element: ElemFunc, name: &str) -> bool {
-        self.element == element && self.name == name
+    pub fn is(&self, element: &ElemFunc, name: &str) -> bool {
+        self.element == *element && self.name == name
     }
 
     /// Whether this property belongs to the given element.
-    pub fn is_of(&self, element: ElemFunc) -> bool {
-        self.element == element
+    pub fn is_of(&self, element: &ElemFunc) -> bool {
+        self.element == *element
     }
 }
 
@@ -194,8 +194,8 @@ pub struct Recipe {
 
 impl Recipe {
     /// Whether this recipe is for the given type of element.
-    pub fn is_of(&self, element: ElemFunc) -> bool {
-        match self.selector {
+    pub fn is_of(&self, element: &ElemFunc) -> bool {
+        match &self.selector {
             Some(Selector::Elem(own, _)) => own == element,
             _ => false,
         }
@@ -734,17 +734,18 @@ impl<'a> StyleChain<'a> {
         name: &'a str,
         inherent: Option<Value>,
     ) -> impl Iterator<Item = T> + '_ {
+        let elem = func.clone();
         inherent
             .into_iter()
             .chain(
                 self.entries()
                     .filter_map(Style::property)
-                    .filter(move |property| property.is(func, name))
+                    .filter(move |property| property.is(&func, name))
                     .map(|property| property.value.clone()),
             )
             .map(move |value| {
                 value.cast().unwrap_or_else(|err| {
-                    panic!("{} (for {}.{})", err, func.name(), name)
+                    panic!("{} (for {}.{})", err, elem.name(), name)
                 })
             })
     }
@@ -172,6 +172,8 @@ pub enum Expr {
     Show(ShowRule),
     /// An if-else conditional: `if x { y } else { z }`.
     Conditional(Conditional),
+    /// A match expression: `match x { 1 => y, _ => z }`.
+    Match(MatchExpr),
     /// A while loop: `while x { y }`.
     While(WhileLoop),
     /// A for loop: `for x in y { z }`.
@@ -246,6 +248,7 @@ impl AstNode for Expr {
             SyntaxKind::SetRule => node.cast().map(Self::Set),
             SyntaxKind::ShowRule => node.cast().map(Self::Show),
             SyntaxKind::Conditional => node.cast().map(Self::Conditional),
+            SyntaxKind::MatchExpr => node.cast().map(Self::Match),
             SyntaxKind::WhileLoop => node.cast().map(Self::While),
             SyntaxKind::ForLoop => node.cast().map(Self::For),
             SyntaxKind::ModuleImport => node.cast().map(Self::Import),
@@ -306,6 +309,7 @@ impl AstNode for Expr {
             Self::Set(v) => v.as_untyped(),
             Self::Show(v) => v.as_untyped(),
             Self::Conditional(v) => v.as_untyped(),
+            Self::Match(v) => v.as_untyped(),
             Self::While(v) => v.as_untyped(),
             Self::For(v) => v.as_untyped(),
             Self::Import(v) => v.as_untyped(),
@@ -341,6 +345,7 @@ impl Expr {
                 | Self::Set(_)
                 | Self::Show(_)
                 | Self::Conditional(_)
+                | Self::Match(_)
                 | Self::While(_)
                 | Self::For(_)
                 | Self::Import(_)
@@ -1188,6 +1193,17 @@ impl Named {
     pub fn expr_ident(&self) -> Option<Ident> {
         self.0.cast_last_match()
     }
+
+    /// The right-hand side of the pair as a pattern.
+    pub fn pattern(&self) -> Pattern {
+        self.0.cast_last_match().unwrap_or_default()
+    }
+
+    /// The type annotation of a named parameter: `as length` in
+    /// `gap as length: 1pt`.
+    pub fn annotation(&self) -> Option<TypeAnnotation> {
+        self.0.cast_first_match()
+    }
 }
 
 node! {
@@ -1550,6 +1566,11 @@ impl Closure {
         self.0.cast_first_match().unwrap_or_default()
     }
 
+    /// The type annotation of the return value.
+    pub fn returns(&self) -> Option<TypeAnnotation> {
+        self.0.cast_first_match()
+    }
+
     /// The body of the closure.
     pub fn body(&self) -> Expr {
         self.0.cast_last_match().unwrap_or_default()
@@ -1566,6 +1587,52 @@ impl Params {
     pub fn children(&self) -> impl DoubleEndedIterator<Item = Param> + '_ {
         self.0.children().filter_map(SyntaxNode::cast)
     }
+
+    /// The parameter bindings along with their type annotations.
+    pub fn annotated(
+        &self,
+    ) -> impl Iterator<Item = (Param, Option<TypeAnnotation>)> + '_ {
+        let mut children = self.0.children();
+        std::iter::from_fn(move || loop {
+            let Some(param) = children.next()?.cast::<Param>() else { continue };
+            let annotation = match &param {
+                Param::Pos(_) => children
+                    .clone()
+                    .find(|node| !node.kind().is_trivia())
+                    .and_then(SyntaxNode::cast),
+                Param::Named(named) => named.annotation(),
+                Param::Sink(_) => Option::None,
+            };
+            return Some((param, annotation));
+        })
+    }
+}
+
+node! {
+    /// A type annotation on a parameter or return value: `as length or none`.
+    TypeAnnotation
+}
+
+impl TypeAnnotation {
+    /// The names of the types the annotated value may have.
+    pub fn types(&self) -> impl DoubleEndedIterator<Item = EcoString> + '_ {
+        self.spanned_types().map(|(ty, _)| ty)
+    }
+
+    /// The names of the types along with the spans at which they are written.
+    pub fn spanned_types(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = (EcoString, Span)> + '_ {
+        self.0.children().filter_map(|node| match node.kind() {
+            SyntaxKind::Ident | SyntaxKind::None | SyntaxKind::Auto => {
+                Some((node.text().clone(), node.span()))
+            }
+            SyntaxKind::Str => {
+                node.cast::<Str>().map(|string| (string.get(), node.span()))
+            }
+            _ => Option::None,
+        })
+    }
 }
 
 node! {
@@ -1857,6 +1924,53 @@ impl Conditional {
     }
 }
 
+node! {
+    /// A match expression: `match x { 1 => y, _ => z }`.
+    MatchExpr
+}
+
+impl MatchExpr {
+    /// The value to match against the arms' patterns.
+    pub fn value(&self) -> Expr {
+        self.0.cast_first_match().unwrap_or_default()
+    }
+
+    /// The arms of the match expression.
+    pub fn arms(&self) -> impl DoubleEndedIterator<Item = MatchArm> + '_ {
+        self.0.children().filter_map(SyntaxNode::cast)
+    }
+}
+
+node! {
+    /// An arm of a match expression: `(a, b) if a > b => z`.
+    MatchArm
+}
+
+impl MatchArm {
+    /// The pattern the value must match.
+    pub fn pattern(&self) -> Pattern {
+        self.0.cast_first_match().unwrap_or_default()
+    }
+
+    /// The guard that must hold for the arm to be taken.
+    pub fn guard(&self) -> Option<Expr> {
+        self.0
+            .children()
+            .skip_while(|node| node.kind() != SyntaxKind::If)
+            .take_while(|node| node.kind() != SyntaxKind::Arrow)
+            .find_map(SyntaxNode::cast)
+    }
+
+    /// The expression to evaluate if the arm is taken.
+    pub fn body(&self) -> Expr {
+        self.0
+            .children()
+            .skip_while(|node| node.kind() != SyntaxKind::Arrow)
+            .find_map(SyntaxNode::cast)
+            .unwrap_or_default()
+    }
+}
+
 node! {
     /// A while loop: `while x { y }`.
     WhileLoop
@@ -1911,6 +2025,15 @@ impl ModuleImport {
         self.0.cast_first_match().unwrap_or_default()
     }
 
+    /// The name the module itself should be bound to: `m` in
+    /// `import "utils.typ" as m`.
+    pub fn new_name(&self) -> Option<Ident> {
+        self.0
+            .children()
+            .skip_while(|node| node.kind() != SyntaxKind::As)
+            .find_map(SyntaxNode::cast)
+    }
+
     /// The items to be imported.
     pub fn imports(&self) -> Option<Imports> {
         self.0.children().find_map(|node| match node.kind() {
@@ -1930,7 +2053,68 @@ pub enum Imports {
     /// All items in the scope of the file should be imported.
     Wildcard,
     /// The specified items from the file should be imported.
-    Items(Vec<Ident>),
+    Items(Vec<ImportItem>),
+}
+
+/// An item that ought to be imported from a file.
+#[derive(Debug, Clone, Hash)]
+pub enum ImportItem {
+    /// A definition imported under its own name: `a`.
+    Simple(Ident),
+    /// A definition imported under a different name: `a as b`.
+    Renamed(RenamedImportItem),
+}
+
+impl ImportItem {
+    /// The name of the definition in the imported module.
+    pub fn original_name(&self) -> Ident {
+        match self {
+            Self::Simple(ident) => ident.clone(),
+            Self::Renamed(renamed) => renamed.original_name(),
+        }
+    }
+
+    /// The name the definition is bound to in the importing scope.
+    pub fn bound_name(&self) -> Ident {
+        match self {
+            Self::Simple(ident) => ident.clone(),
+            Self::Renamed(renamed) => renamed.new_name(),
+        }
+    }
+}
+
+impl AstNode for ImportItem {
+    fn from_untyped(node: &SyntaxNode) -> Option<Self> {
+        match node.kind() {
+            SyntaxKind::Ident => node.cast().map(Self::Simple),
+            SyntaxKind::RenamedImportItem => node.cast().map(Self::Renamed),
+            _ => Option::None,
+        }
+    }
+
+    fn as_untyped(&self) -> &SyntaxNode {
+        match self {
+            Self::Simple(v) => v.as_untyped(),
+            Self::Renamed(v) => v.as_untyped(),
+        }
+    }
+}
+
+node! {
+    /// A renamed import item: `a as d`.
+    RenamedImportItem
+}
+
+impl RenamedImportItem {
+    /// The name of the definition in the imported module.
+    pub fn original_name(&self) -> Ident {
+        self.0.cast_first_match().unwrap_or_default()
+    }
+
+    /// The name the definition is bound to in the importing scope.
+    pub fn new_name(&self) -> Ident {
+        self.0.cast_last_match().unwrap_or_default()
+    }
 }
 
 node! {
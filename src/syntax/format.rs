@@ -0,0 +1,733 @@
+//! Formatting of Typst source code.
+
+use super::ast::BinOp;
+use super::{split_newlines, SyntaxKind, SyntaxNode};
+
+/// The number of spaces per indentation level.
+const INDENT: usize = 2;
+
+/// Format a syntax tree.
+///
+/// Code blocks, argument lists, arrays and dictionaries are laid out to fit
+/// into the given line width where possible. Markup is only reindented: Its
+/// line breaks are kept as they are since they are meaningful. Comments are
+/// preserved.
+///
+/// Returns `None` if the tree contains syntax errors, as the meaning of
+/// erroneous code can't be preserved reliably.
+pub fn format(root: &SyntaxNode, width: usize) -> Option<String> {
+    if root.erroneous() {
+        return None;
+    }
+
+    let mut p = Printer::new(width);
+    match root.kind() {
+        SyntaxKind::Markup => p.markup(root),
+        SyntaxKind::Code => {
+            let (items, dangling) = items(root.children());
+            p.lines(&items, &dangling, None);
+            p.buf = p.buf.trim_start_matches('\n').into();
+        }
+        _ => p.verbatim(root),
+    }
+
+    Some(p.buf)
+}
+
+/// Prints a syntax tree.
+struct Printer {
+    /// The formatted text.
+    buf: String,
+    /// The maximum line width.
+    width: usize,
+    /// The indentation of subsequent lines.
+    indent: usize,
+    /// Whether the indentation for the current line is yet to be written.
+    pending: bool,
+    /// Whether everything must be printed on a single line.
+    flat: bool,
+    /// Whether a line break was requested while printing flat.
+    failed: bool,
+}
+
+/// The state of a printer from which it can be restored.
+#[derive(Copy, Clone)]
+struct Snapshot {
+    len: usize,
+    indent: usize,
+    pending: bool,
+}
+
+impl Printer {
+    /// Create a new printer for the given line width.
+    fn new(width: usize) -> Self {
+        Self {
+            buf: String::new(),
+            width,
+            indent: 0,
+            pending: false,
+            flat: false,
+            failed: false,
+        }
+    }
+
+    /// Append text, indenting it if it starts a new line.
+    fn push(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        if self.flat && split_newlines(text).len() > 1 {
+            self.failed = true;
+        }
+
+        if self.pending {
+            self.pending = false;
+            self.buf.push_str(&" ".repeat(self.indent));
+        }
+
+        self.buf.push_str(text);
+    }
+
+    /// Start a new line.
+    fn newline(&mut self) {
+        if self.flat {
+            self.failed = true;
+            return;
+        }
+
+        self.buf.push('\n');
+        self.pending = true;
+    }
+
+    /// The column at which the next text will be written.
+    fn column(&self) -> usize {
+        if self.pending {
+            return self.indent;
+        }
+
+        let start = self.buf.rfind('\n').map_or(0, |i| i + 1);
+        self.buf[start..].chars().count()
+    }
+
+    /// Save the current state.
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            len: self.buf.len(),
+            indent: self.indent,
+            pending: self.pending,
+        }
+    }
+
+    /// Discard everything printed since the snapshot was taken.
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.buf.truncate(snapshot.len);
+        self.indent = snapshot.indent;
+        self.pending = snapshot.pending;
+    }
+
+    /// Try to print something on the current line.
+    ///
+    /// Returns `false` and prints nothing if it contains line breaks or
+    /// exceeds the line width.
+    fn try_flat(&mut self, f: impl FnOnce(&mut Self)) -> bool {
+        if self.flat {
+            f(self);
+            return true;
+        }
+
+        let snapshot = self.snapshot();
+        self.flat = true;
+        f(self);
+        self.flat = false;
+
+        if !std::mem::take(&mut self.failed) && self.column() <= self.width {
+            return true;
+        }
+
+        self.restore(snapshot);
+        false
+    }
+
+    /// Print a node verbatim.
+    fn verbatim(&mut self, node: &SyntaxNode) {
+        if node.children().len() == 0 {
+            self.push(node.text());
+        } else {
+            self.push(&node.clone().into_text());
+        }
+    }
+
+    /// Print markup, whose line breaks must be preserved.
+    fn markup(&mut self, node: &SyntaxNode) {
+        let mut embedded = false;
+        for child in node.children() {
+            if std::mem::take(&mut embedded) {
+                self.code(child);
+                continue;
+            }
+
+            match child.kind() {
+                SyntaxKind::Space => {
+                    if newlines(child) > 0 {
+                        self.newline();
+                    } else {
+                        self.push(" ");
+                    }
+                }
+                SyntaxKind::Parbreak => {
+                    self.newline();
+                    self.newline();
+                }
+                SyntaxKind::Hashtag => {
+                    self.push(child.text());
+                    embedded = true;
+                }
+                SyntaxKind::ListItem | SyntaxKind::EnumItem | SyntaxKind::TermItem => {
+                    self.list_item(child)
+                }
+                SyntaxKind::Markup
+                | SyntaxKind::Heading
+                | SyntaxKind::Strong
+                | SyntaxKind::Emph
+                | SyntaxKind::Ref => self.markup(child),
+                SyntaxKind::ContentBlock => self.content_block(child),
+                _ => self.verbatim(child),
+            }
+        }
+    }
+
+    /// Print a list, enum or term item.
+    ///
+    /// The item's body is indented relative to its marker. This keeps the
+    /// nesting intact, as the parser determines it based on indentation.
+    fn list_item(&mut self, node: &SyntaxNode) {
+        let column = self.column();
+        let prev = self.indent;
+        for child in node.children() {
+            match child.kind() {
+                SyntaxKind::ListMarker
+                | SyntaxKind::EnumMarker
+                | SyntaxKind::TermMarker => {
+                    self.push(child.text());
+                    self.indent = column + child.text().chars().count().max(INDENT);
+                }
+                SyntaxKind::Space if newlines(child) > 0 => self.newline(),
+                SyntaxKind::Space => self.push(" "),
+                SyntaxKind::Markup => self.markup(child),
+                _ => self.verbatim(child),
+            }
+        }
+        self.indent = prev;
+    }
+
+    /// Print a content block.
+    fn content_block(&mut self, node: &SyntaxNode) {
+        self.push("[");
+        self.indent += INDENT;
+        for child in node.children() {
+            if child.kind() == SyntaxKind::Markup {
+                self.markup(child);
+            }
+        }
+        self.indent -= INDENT;
+        self.push("]");
+    }
+
+    /// Print code.
+    fn code(&mut self, node: &SyntaxNode) {
+        match node.kind() {
+            SyntaxKind::CodeBlock => self.code_block(node),
+            SyntaxKind::ContentBlock => self.content_block(node),
+            SyntaxKind::Args => self.args(node),
+            SyntaxKind::Array
+            | SyntaxKind::Dict
+            | SyntaxKind::Params
+            | SyntaxKind::Destructuring => self.collection(node),
+            SyntaxKind::MatchExpr => self.match_expr(node),
+            SyntaxKind::Equation => self.verbatim(node),
+            _ if node.children().len() == 0 => self.verbatim(node),
+            _ => self.generic(node),
+        }
+    }
+
+    /// Print a code block, with one statement per line.
+    fn code_block(&mut self, node: &SyntaxNode) {
+        let children = node.children().flat_map(|child| match child.kind() {
+            SyntaxKind::Code => child.children().collect(),
+            _ => vec![child],
+        });
+
+        let (items, dangling) = items(children);
+        if items.is_empty() && dangling.is_empty() {
+            self.push("{}");
+            return;
+        }
+
+        // Keep a block with just one statement on one line if it was written
+        // that way and still fits.
+        if items.len() == 1
+            && !has_comments(&items, &dangling)
+            && !has_newline(node)
+            && self.try_flat(|p| {
+                p.push("{ ");
+                p.item(&items[0]);
+                p.push(" }");
+            })
+        {
+            return;
+        }
+
+        self.push("{");
+        self.indent += INDENT;
+        self.lines(&items, &dangling, None);
+        self.indent -= INDENT;
+        self.newline();
+        self.push("}");
+    }
+
+    /// Print an argument list, including trailing content blocks.
+    fn args(&mut self, node: &SyntaxNode) {
+        let children: Vec<_> = node.children().collect();
+        let split = children
+            .iter()
+            .position(|child| child.kind() == SyntaxKind::RightParen)
+            .map_or(0, |i| i + 1);
+
+        if split > 0 {
+            let (items, dangling) = items(children[..split].iter().copied());
+            let hug =
+                split == children.len() && items.len() == 1 && is_huggable(items[0].node);
+            self.list("(", ")", &items, &dangling, false, hug);
+        }
+
+        for child in &children[split..] {
+            self.code(child);
+        }
+    }
+
+    /// Print an array, dictionary, parameter list or destructuring pattern.
+    fn collection(&mut self, node: &SyntaxNode) {
+        if node.children().next().map(SyntaxNode::kind) != Some(SyntaxKind::LeftParen) {
+            self.generic(node);
+            return;
+        }
+
+        let kind = node.kind();
+        let (items, dangling) = items(node.children());
+
+        // A single item needs a trailing comma to not be mistaken for a
+        // parenthesized expression.
+        let comma = items.len() == 1
+            && matches!(kind, SyntaxKind::Array | SyntaxKind::Destructuring)
+            && items[0].node.kind() != SyntaxKind::Spread;
+
+        // A dictionary without any pairs needs a colon to not be mistaken for
+        // an array.
+        let open = if kind == SyntaxKind::Dict
+            && items.iter().all(|item| item.node.kind() == SyntaxKind::Spread)
+        {
+            "(:"
+        } else {
+            "("
+        };
+        self.list(open, ")", &items, &dangling, comma, false);
+    }
+
+    /// Print a match expression, with one arm per line.
+    fn match_expr(&mut self, node: &SyntaxNode) {
+        let children: Vec<_> = node.children().collect();
+        let Some(brace) = children
+            .iter()
+            .position(|child| child.kind() == SyntaxKind::LeftBrace)
+        else {
+            self.generic(node);
+            return;
+        };
+
+        if children[..brace].iter().any(|child| is_comment(child.kind())) {
+            self.generic(node);
+            return;
+        }
+
+        for (i, child) in
+            children[..brace].iter().filter(|c| !c.kind().is_trivia()).enumerate()
+        {
+            if i > 0 {
+                self.push(" ");
+            }
+            self.code(child);
+        }
+
+        let (items, dangling) = items(children[brace..].iter().copied());
+        if items.is_empty() && dangling.is_empty() {
+            self.push(" {}");
+            return;
+        }
+
+        self.push(" ");
+        if !has_comments(&items, &dangling)
+            && !has_newline(node)
+            && self.try_flat(|p| {
+                p.push("{ ");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        p.push(", ");
+                    }
+                    p.item(item);
+                }
+                p.push(" }");
+            })
+        {
+            return;
+        }
+
+        self.push("{");
+        self.indent += INDENT;
+        self.lines(&items, &dangling, Some(","));
+        self.indent -= INDENT;
+        self.newline();
+        self.push("}");
+    }
+
+    /// Print a parenthesized, comma-separated list.
+    ///
+    /// The list is kept on one line if it fits. Otherwise, a list with a
+    /// single item may hug it, so that only the item itself spans multiple
+    /// lines. Failing that, every item is put on its own line.
+    fn list(
+        &mut self,
+        open: &str,
+        close: &str,
+        items: &[Item],
+        dangling: &[&SyntaxNode],
+        comma: bool,
+        hug: bool,
+    ) {
+        if items.is_empty() && dangling.is_empty() {
+            self.push(open);
+            self.push(close);
+            return;
+        }
+
+        if !has_comments(items, dangling) {
+            let flat = self.try_flat(|p| {
+                p.push(open);
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        p.push(", ");
+                    }
+                    p.item(item);
+                }
+                if comma {
+                    p.push(",");
+                }
+                p.push(close);
+            });
+
+            if flat || (hug && self.try_hug(open, close, items[0].node)) {
+                return;
+            }
+        }
+
+        self.push(open);
+        self.indent += INDENT;
+        self.lines(items, dangling, Some(","));
+        self.indent -= INDENT;
+        self.newline();
+        self.push(close);
+    }
+
+    /// Try to print a single item directly within the delimiters.
+    ///
+    /// Only succeeds if the item spans multiple lines and its first line fits.
+    fn try_hug(&mut self, open: &str, close: &str, item: &SyntaxNode) -> bool {
+        let snapshot = self.snapshot();
+        let column = self.column();
+        self.push(open);
+        self.code(item);
+        self.push(close);
+
+        let printed = &self.buf[snapshot.len..];
+        if let Some((first, _)) = printed.split_once('\n') {
+            if column + first.trim_start().chars().count() <= self.width {
+                return true;
+            }
+        }
+
+        self.restore(snapshot);
+        false
+    }
+
+    /// Print items on separate lines, each with its comments.
+    fn lines(&mut self, items: &[Item], dangling: &[&SyntaxNode], sep: Option<&str>) {
+        for item in items {
+            self.newline();
+            if item.blank {
+                self.newline();
+            }
+
+            for comment in &item.leading {
+                self.verbatim(comment);
+                self.newline();
+            }
+
+            self.item(item);
+            if let Some(sep) = sep {
+                self.push(sep);
+            }
+
+            if let Some(comment) = item.trailing {
+                self.push(" ");
+                self.verbatim(comment);
+            }
+        }
+
+        for comment in dangling {
+            self.newline();
+            self.verbatim(comment);
+        }
+    }
+
+    /// Print an item of a list or block.
+    fn item(&mut self, item: &Item) {
+        self.code(item.node);
+        if let Some(annotation) = item.annotation {
+            self.push(" ");
+            self.code(annotation);
+        }
+    }
+
+    /// Print any other code node, normalizing the whitespace between its
+    /// children.
+    fn generic(&mut self, node: &SyntaxNode) {
+        let parent = node.kind();
+        let mut prev = None;
+        let mut space = false;
+        for child in node.children() {
+            let kind = child.kind();
+            if matches!(kind, SyntaxKind::Space | SyntaxKind::Parbreak) {
+                space = true;
+                continue;
+            }
+
+            match prev {
+                Some(SyntaxKind::LineComment) => self.newline(),
+                Some(prev) if spaced(parent, prev, kind, space) => self.push(" "),
+                _ => {}
+            }
+
+            self.code(child);
+            prev = Some(kind);
+            space = false;
+        }
+    }
+}
+
+/// Whether to put a space between two children of a code node.
+///
+/// Spaces are only removed or added where that's certainly safe. Otherwise,
+/// whitespace in the source is collapsed into a single space.
+fn spaced(parent: SyntaxKind, prev: SyntaxKind, next: SyntaxKind, space: bool) -> bool {
+    let tight_before = |kind| match parent {
+        SyntaxKind::Named
+        | SyntaxKind::Keyed
+        | SyntaxKind::ShowRule
+        | SyntaxKind::ModuleImport => kind == SyntaxKind::Colon,
+        SyntaxKind::ImportItems => kind == SyntaxKind::Comma,
+        SyntaxKind::Parenthesized => kind == SyntaxKind::RightParen,
+        _ => false,
+    };
+
+    let tight_after =
+        |kind| parent == SyntaxKind::Parenthesized && kind == SyntaxKind::LeftParen;
+
+    let loose_before = |kind| match parent {
+        SyntaxKind::Binary => is_operator(kind),
+        SyntaxKind::LetBinding | SyntaxKind::DestructAssignment => kind == SyntaxKind::Eq,
+        SyntaxKind::Closure => matches!(kind, SyntaxKind::Eq | SyntaxKind::Arrow),
+        SyntaxKind::MatchArm => kind == SyntaxKind::Arrow,
+        _ => false,
+    };
+
+    let loose_after = |kind| {
+        loose_before(kind)
+            || tight_before(kind) && !matches!(kind, SyntaxKind::RightParen)
+    };
+
+    if is_comment(prev) || is_comment(next) {
+        return space;
+    }
+
+    if tight_before(next) || tight_after(prev) {
+        return false;
+    }
+
+    space || loose_after(prev) || loose_before(next)
+}
+
+/// Whether a token is a binary operator.
+fn is_operator(kind: SyntaxKind) -> bool {
+    BinOp::from_kind(kind).is_some() || kind == SyntaxKind::Not
+}
+
+/// Whether a token is a comment.
+fn is_comment(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::LineComment | SyntaxKind::BlockComment)
+}
+
+/// Whether an argument may hug the parentheses around it.
+fn is_huggable(node: &SyntaxNode) -> bool {
+    let node = match node.kind() {
+        SyntaxKind::Named => match node.children().last() {
+            Some(value) => value,
+            None => return false,
+        },
+        _ => node,
+    };
+
+    matches!(
+        node.kind(),
+        SyntaxKind::Closure
+            | SyntaxKind::CodeBlock
+            | SyntaxKind::ContentBlock
+            | SyntaxKind::Array
+            | SyntaxKind::Dict
+    )
+}
+
+/// The number of line breaks in a whitespace node.
+fn newlines(node: &SyntaxNode) -> usize {
+    split_newlines(node.text()).len() - 1
+}
+
+/// Whether a node spans multiple lines in the source.
+fn has_newline(node: &SyntaxNode) -> bool {
+    split_newlines(&node.clone().into_text()).len() > 1
+}
+
+/// An item in a delimited list or block, along with its comments.
+struct Item<'a> {
+    /// Comments on the lines before the item.
+    leading: Vec<&'a SyntaxNode>,
+    /// The item itself.
+    node: &'a SyntaxNode,
+    /// The type annotation of a parameter.
+    annotation: Option<&'a SyntaxNode>,
+    /// A comment on the same line after the item.
+    trailing: Option<&'a SyntaxNode>,
+    /// Whether the item is preceded by an empty line.
+    blank: bool,
+}
+
+/// Split the children of a delimited list or block into items and the
+/// comments after the last item.
+fn items<'a>(
+    children: impl IntoIterator<Item = &'a SyntaxNode>,
+) -> (Vec<Item<'a>>, Vec<&'a SyntaxNode>) {
+    let mut items: Vec<Item> = vec![];
+    let mut comments = vec![];
+    let mut newline = false;
+    let mut blank = false;
+
+    for child in children {
+        match child.kind() {
+            SyntaxKind::Space | SyntaxKind::Parbreak => {
+                let n = newlines(child);
+                newline |= n > 0;
+                blank |= n > 1;
+            }
+            SyntaxKind::LineComment | SyntaxKind::BlockComment => {
+                match items.last_mut() {
+                    Some(last)
+                        if !newline && comments.is_empty() && last.trailing.is_none() =>
+                    {
+                        last.trailing = Some(child);
+                    }
+                    _ => comments.push(child),
+                }
+            }
+            SyntaxKind::TypeAnnotation if !items.is_empty() => {
+                items.last_mut().unwrap().annotation = Some(child);
+            }
+            SyntaxKind::LeftParen
+            | SyntaxKind::RightParen
+            | SyntaxKind::LeftBrace
+            | SyntaxKind::RightBrace
+            | SyntaxKind::Comma
+            | SyntaxKind::Semicolon
+            | SyntaxKind::Colon => {}
+            _ => {
+                let blank = std::mem::take(&mut blank) && !items.is_empty();
+                let leading = std::mem::take(&mut comments);
+                items.push(Item {
+                    leading,
+                    node: child,
+                    annotation: None,
+                    trailing: None,
+                    blank,
+                });
+                newline = false;
+            }
+        }
+    }
+
+    (items, comments)
+}
+
+/// Whether any of the items has comments.
+fn has_comments(items: &[Item], dangling: &[&SyntaxNode]) -> bool {
+    !dangling.is_empty()
+        || items
+            .iter()
+            .any(|item| !item.leading.is_empty() || item.trailing.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+    use super::*;
+
+    #[track_caller]
+    fn test(text: &str, expected: &str) {
+        let found = format(&parse(text), 40).unwrap();
+        assert_eq!(found, expected);
+        assert_eq!(format(&parse(&found), 40).unwrap(), found, "not idempotent");
+    }
+
+    #[test]
+    fn test_format_code() {
+        test("#f(a,b)", "#f(a, b)");
+        test("#let x=1+2", "#let x = 1 + 2");
+        test("#(1 ,)", "#(1,)");
+        test("#(a :1)", "#(a: 1)");
+        test("#(: ..a, ..b)", "#(:..a, ..b)");
+        test("#let f(x as int,y)=x", "#let f(x as int, y) = x");
+        test("#{let x = 1; x}", "#{\n  let x = 1\n  x\n}");
+        test("#{\n\n  x\n\n\n  y\n}", "#{\n  x\n\n  y\n}");
+        test(
+            "#f(first-argument, second-argument, third)",
+            "#f(\n  first-argument,\n  second-argument,\n  third,\n)",
+        );
+        test("#f(x => {\n  x\n})", "#f(x => {\n  x\n})");
+    }
+
+    #[test]
+    fn test_format_comments() {
+        test("#f(a, // one\n b)", "#f(\n  a, // one\n  b,\n)");
+        test("#{\n  // two\n  x /* three */\n}", "#{\n  // two\n  x /* three */\n}");
+    }
+
+    #[test]
+    fn test_format_markup() {
+        test("Hello   world\n  again", "Hello world\nagain");
+        test("- a\n    - b\n       c\n- d", "- a\n  - b\n    c\n- d");
+        test("#box[\n      text\n    ]", "#box[\n  text\n]");
+        test("```\n  raw\n```", "```\n  raw\n```");
+    }
+
+    #[test]
+    fn test_format_erroneous() {
+        assert_eq!(format(&parse("#f("), 40), None);
+    }
+}
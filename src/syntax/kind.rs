@@ -155,6 +155,8 @@ pub enum SyntaxKind {
     If,
     /// The `else` keyword.
     Else,
+    /// The contextual `match` keyword.
+    Match,
     /// The `for` keyword.
     For,
     /// The `in` keyword.
@@ -218,6 +220,8 @@ pub enum SyntaxKind {
     Closure,
     /// A closure's parameters: `(x, y)`.
     Params,
+    /// A type annotation on a parameter or return value: `as length or none`.
+    TypeAnnotation,
     /// A let binding: `let x = 1`.
     LetBinding,
     /// A set rule: `set text(...)`.
@@ -226,14 +230,20 @@ pub enum SyntaxKind {
     ShowRule,
     /// An if-else conditional: `if x { y } else { z }`.
     Conditional,
+    /// A match expression: `match x { 1 => y, _ => z }`.
+    MatchExpr,
+    /// An arm of a match expression: `(a, b) if a > b => z`.
+    MatchArm,
     /// A while loop: `while x { y }`.
     WhileLoop,
     /// A for loop: `for x in y { z }`.
     ForLoop,
-    /// A module import: `import a, b, c from "utils.typ"`.
+    /// A module import: `import "utils.typ": a, b, c`.
     ModuleImport,
     /// Items to import from a module: `a, b, c`.
     ImportItems,
+    /// A renamed import item: `a as d`.
+    RenamedImportItem,
     /// A module include: `include "chapter1.typ"`.
     ModuleInclude,
     /// A break from a loop: `break`.
@@ -388,6 +398,7 @@ impl SyntaxKind {
             Self::Show => "keyword `show`",
             Self::If => "keyword `if`",
             Self::Else => "keyword `else`",
+            Self::Match => "keyword `match`",
             Self::For => "keyword `for`",
             Self::In => "keyword `in`",
             Self::While => "keyword `while`",
@@ -419,14 +430,18 @@ impl SyntaxKind {
             Self::Spread => "spread",
             Self::Closure => "closure",
             Self::Params => "closure parameters",
+            Self::TypeAnnotation => "type annotation",
             Self::LetBinding => "`let` expression",
             Self::SetRule => "`set` expression",
             Self::ShowRule => "`show` expression",
             Self::Conditional => "`if` expression",
+            Self::MatchExpr => "`match` expression",
+            Self::MatchArm => "match arm",
             Self::WhileLoop => "while-loop expression",
             Self::ForLoop => "for-loop expression",
             Self::ModuleImport => "`import` expression",
             Self::ImportItems => "import items",
+            Self::RenamedImportItem => "renamed import item",
             Self::ModuleInclude => "`include` expression",
             Self::LoopBreak => "`break` expression",
             Self::LoopContinue => "`continue` expression",
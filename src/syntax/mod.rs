@@ -1,7 +1,8 @@
-//! Syntax definition, parsing, and highlighting.
+//! Syntax definition, parsing, highlighting, and formatting.
 
 pub mod ast;
 
+mod format;
 mod kind;
 mod lexer;
 mod node;
@@ -10,6 +11,7 @@ mod reparser;
 mod source;
 mod span;
 
+pub use self::format::*;
 pub use self::kind::*;
 pub use self::lexer::*;
 pub use self::node::*;
@@ -636,9 +636,10 @@ fn code_expr_prec(
 fn code_primary(p: &mut Parser, atomic: bool, allow_destructuring: bool) {
     let m = p.marker();
     match p.current() {
+        SyntaxKind::Ident if p.at_match_keyword() => match_expr(p),
         SyntaxKind::Ident => {
             p.eat();
-            if !atomic && p.at(SyntaxKind::Arrow) {
+            if !atomic && p.at_closure_arrow() {
                 p.wrap(m, SyntaxKind::Params);
                 p.assert(SyntaxKind::Arrow);
                 code_expr(p);
@@ -647,7 +648,7 @@ fn code_primary(p: &mut Parser, atomic: bool, allow_destructuring: bool) {
         }
         SyntaxKind::Underscore if !atomic => {
             p.eat();
-            if p.at(SyntaxKind::Arrow) {
+            if p.at_closure_arrow() {
                 p.wrap(m, SyntaxKind::Params);
                 p.eat();
                 code_expr(p);
@@ -665,6 +666,7 @@ fn code_primary(p: &mut Parser, atomic: bool, allow_destructuring: bool) {
         SyntaxKind::Set => set_rule(p),
         SyntaxKind::Show => show_rule(p),
         SyntaxKind::If => conditional(p),
+        SyntaxKind::Match => match_expr(p),
         SyntaxKind::While => while_loop(p),
         SyntaxKind::For => for_loop(p),
         SyntaxKind::Import => module_import(p),
@@ -728,9 +730,12 @@ fn content_block(p: &mut Parser) {
 fn with_paren(p: &mut Parser, allow_destructuring: bool) {
     let m = p.marker();
     let mut kind = collection(p, true);
-    if p.at(SyntaxKind::Arrow) {
+    if p.at_closure_arrow() || at_return_annotation(p) {
         validate_params(p, m);
         p.wrap(m, SyntaxKind::Params);
+        if p.at(SyntaxKind::As) {
+            type_annotation(p);
+        }
         p.assert(SyntaxKind::Arrow);
         code_expr(p);
         kind = SyntaxKind::Closure;
@@ -753,6 +758,11 @@ fn with_paren(p: &mut Parser, allow_destructuring: bool) {
         }
         _ => {}
     }
+
+    if kind != SyntaxKind::Closure {
+        forbid_annotations(p, m);
+    }
+
     p.wrap(m, kind);
 }
 
@@ -863,6 +873,10 @@ fn item(p: &mut Parser, keyed: bool) -> SyntaxKind {
 
     code_expr_or_pattern(p);
 
+    if p.at(SyntaxKind::As) {
+        type_annotation(p);
+    }
+
     if !p.eat_if(SyntaxKind::Colon) {
         return SyntaxKind::Int;
     }
@@ -905,6 +919,7 @@ fn args(p: &mut Parser) {
     if p.at(SyntaxKind::LeftParen) {
         collection(p, false);
         validate_args(p, m);
+        forbid_annotations(p, m);
     }
 
     while p.directly_at(SyntaxKind::LeftBracket) {
@@ -925,6 +940,7 @@ fn pattern(p: &mut Parser) -> PatternKind {
     if p.at(SyntaxKind::LeftParen) {
         let kind = collection(p, false);
         validate_destruct_pattern(p, m, true);
+        forbid_annotations(p, m);
 
         if kind == SyntaxKind::Parenthesized {
             PatternKind::Ident
@@ -955,6 +971,9 @@ fn let_binding(p: &mut Parser) {
                 collection(p, false);
                 validate_params(p, m3);
                 p.wrap(m3, SyntaxKind::Params);
+                if p.at(SyntaxKind::As) {
+                    type_annotation(p);
+                }
             }
         }
         PatternKind::Placeholder => {}
@@ -973,6 +992,51 @@ fn let_binding(p: &mut Parser) {
     p.wrap(m, SyntaxKind::LetBinding);
 }
 
+fn type_annotation(p: &mut Parser) {
+    let m = p.marker();
+    p.assert(SyntaxKind::As);
+    loop {
+        if is_type_name(p.current()) {
+            p.eat();
+        } else {
+            p.expected("type name");
+        }
+        if !p.eat_if(SyntaxKind::Or) {
+            break;
+        }
+    }
+    p.wrap(m, SyntaxKind::TypeAnnotation);
+}
+
+/// Whether the parser is at a closure's return type annotation, that is, at
+/// `as content =>`.
+fn at_return_annotation(p: &Parser) -> bool {
+    if !p.at(SyntaxKind::As) {
+        return false;
+    }
+
+    let mut lexer = p.lexer.clone();
+    let mut next =
+        || std::iter::from_fn(|| Some(lexer.next())).find(|kind| !kind.is_trivia());
+    loop {
+        if !next().map_or(false, is_type_name) {
+            return false;
+        }
+        match next() {
+            Some(SyntaxKind::Or) => {}
+            Some(SyntaxKind::Arrow) => return true,
+            _ => return false,
+        }
+    }
+}
+
+fn is_type_name(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Ident | SyntaxKind::None | SyntaxKind::Auto | SyntaxKind::Str
+    )
+}
+
 fn set_rule(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Set);
@@ -1026,6 +1090,135 @@ fn conditional(p: &mut Parser) {
     p.wrap(m, SyntaxKind::Conditional);
 }
 
+fn match_expr(p: &mut Parser) {
+    let m = p.marker();
+    p.convert(SyntaxKind::Match);
+    code_expr(p);
+    if p.at(SyntaxKind::LeftBrace) {
+        match_arms(p);
+    } else {
+        p.expected("match arms");
+    }
+    p.wrap(m, SyntaxKind::MatchExpr);
+}
+
+fn match_arms(p: &mut Parser) {
+    p.enter(LexMode::Code);
+    p.stop_at_newline(false);
+    p.assert(SyntaxKind::LeftBrace);
+
+    while !p.eof() && !p.at(SyntaxKind::RightBrace) {
+        let prev = p.prev_end();
+        let block = match_arm(p);
+        if !p.progress(prev) {
+            p.unexpected();
+            continue;
+        }
+
+        // Arms whose body is a block need not be separated by a comma.
+        if !p.at(SyntaxKind::RightBrace) && !p.eat_if(SyntaxKind::Comma) && !block {
+            p.expected("comma");
+        }
+    }
+
+    p.expect(SyntaxKind::RightBrace);
+    p.exit();
+    p.unstop();
+}
+
+fn match_arm(p: &mut Parser) -> bool {
+    let m = p.marker();
+    match_pattern(p);
+
+    if p.eat_if(SyntaxKind::If) {
+        // The guard is followed by the arm's arrow, which must not turn a
+        // trailing identifier or group into a closure.
+        let guard = guard_arrow(p);
+        let prev = std::mem::replace(&mut p.guard_arrow, guard);
+        code_expr(p);
+        p.guard_arrow = prev;
+    }
+
+    p.expect(SyntaxKind::Arrow);
+    let block = p.at(SyntaxKind::LeftBrace) || p.at(SyntaxKind::LeftBracket);
+    code_expr(p);
+    p.wrap(m, SyntaxKind::MatchArm);
+    block
+}
+
+fn match_pattern(p: &mut Parser) {
+    let m = p.marker();
+    match p.current() {
+        SyntaxKind::LeftParen => {
+            let kind = collection(p, false);
+            validate_match_pattern(p, m);
+            forbid_annotations(p, m);
+            if kind != SyntaxKind::Parenthesized {
+                p.wrap(m, SyntaxKind::Destructuring);
+            }
+        }
+        SyntaxKind::Ident
+            if p.current_text() == "type"
+                && p.lexer.clone().next() == SyntaxKind::LeftParen =>
+        {
+            p.eat();
+            args(p);
+            p.wrap(m, SyntaxKind::FuncCall);
+        }
+        SyntaxKind::Minus => {
+            p.eat();
+            if matches!(
+                p.current(),
+                SyntaxKind::Int | SyntaxKind::Float | SyntaxKind::Numeric
+            ) {
+                p.eat();
+            } else {
+                p.expected("number");
+            }
+            p.wrap(m, SyntaxKind::Unary);
+        }
+        SyntaxKind::Ident
+        | SyntaxKind::Underscore
+        | SyntaxKind::None
+        | SyntaxKind::Auto
+        | SyntaxKind::Bool
+        | SyntaxKind::Int
+        | SyntaxKind::Float
+        | SyntaxKind::Numeric
+        | SyntaxKind::Str
+        | SyntaxKind::Label => p.eat(),
+        _ => p.expected("pattern"),
+    }
+}
+
+/// Find the start of the arrow that ends a match arm's guard.
+fn guard_arrow(p: &Parser) -> Option<usize> {
+    let mut lexer = p.lexer.clone();
+    let mut kind = p.current();
+    let mut start = p.current_start();
+    let mut depth = 0;
+    loop {
+        match kind {
+            SyntaxKind::LeftParen | SyntaxKind::LeftBrace | SyntaxKind::LeftBracket => {
+                depth += 1
+            }
+            SyntaxKind::RightParen
+            | SyntaxKind::RightBrace
+            | SyntaxKind::RightBracket => {
+                if depth == 0 {
+                    return None;
+                }
+                depth -= 1;
+            }
+            SyntaxKind::Arrow if depth == 0 => return Some(start),
+            SyntaxKind::Eof => return None,
+            _ => {}
+        }
+        start = lexer.cursor();
+        kind = lexer.next();
+    }
+}
+
 fn while_loop(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::While);
@@ -1056,6 +1249,9 @@ fn module_import(p: &mut Parser) {
     let m = p.marker();
     p.assert(SyntaxKind::Import);
     code_expr(p);
+    if p.eat_if(SyntaxKind::As) {
+        p.expect(SyntaxKind::Ident);
+    }
     if p.eat_if(SyntaxKind::Colon) && !p.eat_if(SyntaxKind::Star) {
         import_items(p);
     }
@@ -1065,8 +1261,12 @@ fn module_import(p: &mut Parser) {
 fn import_items(p: &mut Parser) {
     let m = p.marker();
     while !p.eof() && !p.at(SyntaxKind::Semicolon) {
+        let item = p.marker();
         if !p.eat_if(SyntaxKind::Ident) {
             p.unexpected();
+        } else if p.eat_if(SyntaxKind::As) {
+            p.expect(SyntaxKind::Ident);
+            p.wrap(item, SyntaxKind::RenamedImportItem);
         }
         if p.current().is_terminator() {
             break;
@@ -1217,7 +1417,8 @@ fn validate_params(p: &mut Parser, m: Marker) {
             SyntaxKind::LeftParen
             | SyntaxKind::RightParen
             | SyntaxKind::Comma
-            | SyntaxKind::Underscore => {}
+            | SyntaxKind::Underscore
+            | SyntaxKind::TypeAnnotation => {}
             kind => {
                 child.convert_to_error(eco_format!(
                     "expected identifier, named pair or argument sink, found {}",
@@ -1246,6 +1447,130 @@ fn validate_args(p: &mut Parser, m: Marker) {
     }
 }
 
+fn forbid_annotations(p: &mut Parser, m: Marker) {
+    const MESSAGE: &str = "type annotations are only allowed on closure parameters";
+    for child in p.post_process(m) {
+        match child.kind() {
+            SyntaxKind::TypeAnnotation => child.convert_to_error(MESSAGE),
+            SyntaxKind::Named => {
+                if let Some(annotation) = child
+                    .children_mut()
+                    .iter_mut()
+                    .find(|node| node.kind() == SyntaxKind::TypeAnnotation)
+                {
+                    annotation.convert_to_error(MESSAGE);
+                    child.make_erroneous();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn validate_match_pattern(p: &mut Parser, m: Marker) {
+    let mut used = HashSet::new();
+    for child in p.post_process(m) {
+        match child.kind() {
+            SyntaxKind::Ident => {
+                if !used.insert(child.text().clone()) {
+                    child.convert_to_error(
+                        "at most one binding per identifier is allowed",
+                    );
+                }
+            }
+            SyntaxKind::Named => {
+                let Some(within) = child.children_mut().last_mut() else { continue };
+                match within.kind() {
+                    SyntaxKind::Ident => {
+                        if !used.insert(within.text().clone()) {
+                            within.convert_to_error(
+                                "at most one binding per identifier is allowed",
+                            );
+                            child.make_erroneous();
+                        }
+                    }
+                    SyntaxKind::Unary => {}
+                    kind if is_literal_pattern(kind) => {}
+                    kind if is_nested_pattern(kind) => {
+                        within.convert_to_error("nested patterns are not supported");
+                        child.make_erroneous();
+                    }
+                    kind => {
+                        within.convert_to_error(eco_format!(
+                            "expected identifier, literal or underscore, found {}",
+                            kind.name(),
+                        ));
+                        child.make_erroneous();
+                    }
+                }
+            }
+            SyntaxKind::Spread => {
+                let Some(within) = child.children_mut().last_mut() else { continue };
+                if within.kind() == SyntaxKind::Ident {
+                    if !used.insert(within.text().clone()) {
+                        within.convert_to_error(
+                            "at most one binding per identifier is allowed",
+                        );
+                        child.make_erroneous();
+                    }
+                } else if within.kind() != SyntaxKind::Dots {
+                    within.convert_to_error(eco_format!(
+                        "expected identifier, found {}",
+                        within.kind().name(),
+                    ));
+                    child.make_erroneous();
+                }
+            }
+            SyntaxKind::Unary => {
+                let numeric = child.children().last().map_or(false, |last| {
+                    matches!(
+                        last.kind(),
+                        SyntaxKind::Int | SyntaxKind::Float | SyntaxKind::Numeric
+                    )
+                });
+                if !numeric
+                    || child.children().next().map(SyntaxNode::kind)
+                        != Some(SyntaxKind::Minus)
+                {
+                    child.convert_to_error("expected negated number");
+                }
+            }
+            SyntaxKind::LeftParen | SyntaxKind::RightParen | SyntaxKind::Comma => {}
+            kind if is_literal_pattern(kind) => {}
+            kind if is_nested_pattern(kind) => {
+                child.convert_to_error("nested patterns are not supported");
+            }
+            kind => {
+                child.convert_to_error(eco_format!(
+                    "expected pattern, found {}",
+                    kind.name()
+                ));
+            }
+        }
+    }
+}
+
+/// Whether a node of this kind can be matched against as is.
+fn is_literal_pattern(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Underscore
+            | SyntaxKind::None
+            | SyntaxKind::Auto
+            | SyntaxKind::Bool
+            | SyntaxKind::Int
+            | SyntaxKind::Float
+            | SyntaxKind::Numeric
+            | SyntaxKind::Str
+            | SyntaxKind::Label
+    )
+}
+
+/// Whether a node of this kind would be a pattern nested in another one.
+fn is_nested_pattern(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::Array | SyntaxKind::Dict | SyntaxKind::Parenthesized)
+}
+
 fn validate_destruct_pattern(p: &mut Parser, m: Marker, forbid_expressions: bool) {
     let mut used = HashSet::new();
     validate_pattern(p.post_process(m), &mut used, forbid_expressions);
@@ -1340,6 +1665,7 @@ struct Parser<'s> {
     modes: Vec<LexMode>,
     nodes: Vec<SyntaxNode>,
     stop_at_newline: Vec<bool>,
+    guard_arrow: Option<usize>,
     balanced: bool,
 }
 
@@ -1360,6 +1686,7 @@ impl<'s> Parser<'s> {
             modes: vec![],
             nodes: vec![],
             stop_at_newline: vec![],
+            guard_arrow: None,
             balanced: true,
         }
     }
@@ -1406,6 +1733,42 @@ impl<'s> Parser<'s> {
         self.current == kind && self.prev_end == self.current_start
     }
 
+    /// Whether the current identifier is the contextual `match` keyword, that
+    /// is, whether it is followed by the value to match on the same line.
+    /// Otherwise, `match` remains usable as a normal identifier.
+    fn at_match_keyword(&self) -> bool {
+        if self.current != SyntaxKind::Ident || self.current_text() != "match" {
+            return false;
+        }
+
+        let mut lexer = self.lexer.clone();
+        if lexer.next() != SyntaxKind::Space || lexer.newline() {
+            return false;
+        }
+
+        matches!(
+            lexer.next(),
+            SyntaxKind::Ident
+                | SyntaxKind::None
+                | SyntaxKind::Auto
+                | SyntaxKind::Bool
+                | SyntaxKind::Int
+                | SyntaxKind::Float
+                | SyntaxKind::Numeric
+                | SyntaxKind::Str
+                | SyntaxKind::Label
+                | SyntaxKind::Not
+                | SyntaxKind::Dollar
+                | SyntaxKind::LeftParen
+                | SyntaxKind::LeftBrace
+                | SyntaxKind::LeftBracket
+        )
+    }
+
+    fn at_closure_arrow(&self) -> bool {
+        self.at(SyntaxKind::Arrow) && self.guard_arrow != Some(self.current_start)
+    }
+
     fn eat_if(&mut self, kind: SyntaxKind) -> bool {
         let at = self.at(kind);
         if at {
@@ -0,0 +1,5 @@
+#import "util.typ": double
+
+#let add(x, y) = x + y
+#let add-twice(x, y) = double(add(x, y))
+#let data = read("/data.txt")
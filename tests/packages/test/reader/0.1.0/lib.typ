@@ -0,0 +1,2 @@
+// Reads a file, resolving the path from within the package.
+#let read-file(..args) = read(..args)
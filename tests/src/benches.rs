@@ -1,9 +1,9 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use comemo::{Prehashed, Track, Tracked};
 use iai::{black_box, main, Iai};
-use typst::diag::{FileError, FileResult};
-use typst::eval::Library;
+use typst::diag::{FileError, FileResult, PackageError, PackageResult};
+use typst::eval::{Datetime, Library, PackageSpec, Tracer};
 use typst::font::{Font, FontBook};
 use typst::geom::Color;
 use typst::syntax::{Source, SourceId};
@@ -63,8 +63,14 @@ fn bench_eval(iai: &mut Iai) {
     let route = typst::eval::Route::default();
     let mut tracer = typst::eval::Tracer::default();
     iai.run(|| {
-        typst::eval::eval(world.track(), route.track(), tracer.track_mut(), &world.source)
-            .unwrap()
+        typst::eval::eval(
+            world.track(),
+            route.track(),
+            tracer.track_mut(),
+            None,
+            &world.source,
+        )
+        .unwrap()
     });
 }
 
@@ -76,6 +82,7 @@ fn bench_typeset(iai: &mut Iai) {
         world.track(),
         route.track(),
         tracer.track_mut(),
+        None,
         &world.source,
     )
     .unwrap();
@@ -85,12 +92,14 @@ fn bench_typeset(iai: &mut Iai) {
 
 fn bench_compile(iai: &mut Iai) {
     let world = BenchWorld::new();
-    iai.run(|| typst::compile(&world));
+    let mut tracer = Tracer::default();
+    iai.run(|| typst::compile(&world, &mut tracer));
 }
 
 fn bench_render(iai: &mut Iai) {
     let world = BenchWorld::new();
-    let document = typst::compile(&world).unwrap();
+    let mut tracer = Tracer::default();
+    let document = typst::compile(&world, &mut tracer).unwrap();
     iai.run(|| typst::export::render(&document.pages[0], 1.0, Color::WHITE))
 }
 
@@ -147,4 +156,12 @@ impl World for BenchWorld {
     fn file(&self, path: &Path) -> FileResult<Buffer> {
         Err(FileError::NotFound(path.into()))
     }
+
+    fn package(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
+        Err(PackageError::NotFound(spec.clone()))
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
 }
@@ -21,9 +21,9 @@ use tiny_skia as sk;
 use unscanny::Scanner;
 use walkdir::WalkDir;
 
-use typst::diag::{bail, FileError, FileResult};
+use typst::diag::{bail, FileError, FileResult, PackageError, PackageResult, Severity};
 use typst::doc::{Document, Frame, FrameItem, Meta};
-use typst::eval::{func, Library, Value};
+use typst::eval::{func, Datetime, Library, PackageSpec, Tracer, Value};
 use typst::font::{Font, FontBook};
 use typst::geom::{Abs, Color, RgbaColor, Sides, Smart};
 use typst::syntax::{Source, SourceId, Span, SyntaxNode};
@@ -38,6 +38,7 @@ const PNG_DIR: &str = "png";
 const PDF_DIR: &str = "pdf";
 const FONT_DIR: &str = "../assets/fonts";
 const FILE_DIR: &str = "../assets/files";
+const PACKAGE_DIR: &str = "packages";
 
 #[derive(Debug, Clone, Parser)]
 #[clap(name = "typst-test", author)]
@@ -297,6 +298,23 @@ impl World for TestWorld {
             .get_or_init(|| read(path).map(Buffer::from))
             .clone()
     }
+
+    fn package(&self, spec: &PackageSpec) -> PackageResult<PathBuf> {
+        let dir = Path::new(PACKAGE_DIR)
+            .join(spec.namespace.as_str())
+            .join(spec.name.as_str())
+            .join(spec.version.to_string());
+
+        if dir.is_dir() {
+            Ok(dir)
+        } else {
+            Err(PackageError::NotFound(spec.clone()))
+        }
+    }
+
+    fn today(&self, _: Option<i64>) -> Option<Datetime> {
+        Some(Datetime::from_ymd(1970, 1, 1).unwrap())
+    }
 }
 
 impl TestWorld {
@@ -513,33 +531,40 @@ fn test_part(
         let route = typst::eval::Route::default();
         let mut tracer = typst::eval::Tracer::default();
         let module =
-            typst::eval::eval(world, route.track(), tracer.track_mut(), source).unwrap();
+            typst::eval::eval(world, route.track(), tracer.track_mut(), None, source)
+                .unwrap();
         writeln!(output, "Model:\n{:#?}\n", module.content()).unwrap();
     }
 
-    let (mut frames, errors) = match typst::compile(world) {
+    let mut tracer = Tracer::default();
+    let result = typst::compile(world, &mut tracer);
+    let compiled = result.as_ref().ok().map(|document| document.pages.clone());
+    let (mut frames, mut errors) = match result {
         Ok(document) => (document.pages, vec![]),
         Err(errors) => (vec![], *errors),
     };
+    errors.extend(tracer.warnings().iter().cloned());
 
     // Don't retain frames if we don't wanna compare with reference images.
     if !compare_ref {
         frames.clear();
     }
 
-    // Map errors to range and message format, discard traces and errors from
-    // other files.
+    // Map errors and warnings to range, severity and message format, discard
+    // traces and diagnostics from other files.
     let mut errors: Vec<_> = errors
         .into_iter()
         .filter(|error| error.span.source() == id)
-        .map(|error| (error.range(world), error.message.replace('\\', "/")))
+        .map(|error| {
+            (error.range(world), error.severity, error.message.replace('\\', "/"))
+        })
         .collect();
 
     errors.sort_by_key(|error| error.0.start);
     ref_errors.sort_by_key(|error| error.0.start);
 
     if errors != ref_errors {
-        writeln!(output, "  Subtest {i} does not match expected errors.").unwrap();
+        writeln!(output, "  Subtest {i} does not match expected diagnostics.").unwrap();
         ok = false;
 
         let source = world.source(id);
@@ -558,10 +583,15 @@ fn test_part(
         }
     }
 
+    let root = world.source(id).root().clone();
+    ok &= test_format(output, world, src_path, &root, compiled.as_deref(), i);
+
     (ok, compare_ref, frames)
 }
 
-fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>) {
+type Annotation = (Range<usize>, Severity, String);
+
+fn parse_metadata(source: &Source) -> (Option<bool>, Vec<Annotation>) {
     let mut compare_ref = None;
     let mut errors = vec![];
 
@@ -590,13 +620,20 @@ fn parse_metadata(source: &Source) -> (Option<bool>, Vec<(Range<usize>, String)>
             source.line_column_to_byte(line, column).unwrap()
         };
 
-        let Some(rest) = line.strip_prefix("// Error: ") else { continue; };
+        let (severity, rest) = if let Some(rest) = line.strip_prefix("// Error: ") {
+            (Severity::Error, rest)
+        } else if let Some(rest) = line.strip_prefix("// Warning: ") {
+            (Severity::Warning, rest)
+        } else {
+            continue;
+        };
+
         let mut s = Scanner::new(rest);
         let start = pos(&mut s);
         let end = if s.eat_if('-') { pos(&mut s) } else { start };
         let range = start..end;
 
-        errors.push((range, s.after().trim().to_string()));
+        errors.push((range, severity, s.after().trim().to_string()));
     }
 
     (compare_ref, errors)
@@ -606,13 +643,17 @@ fn print_error(
     output: &mut String,
     source: &Source,
     line: usize,
-    (range, message): &(Range<usize>, String),
+    (range, severity, message): &Annotation,
 ) {
     let start_line = 1 + line + source.byte_to_line(range.start).unwrap();
     let start_col = 1 + source.byte_to_column(range.start).unwrap();
     let end_line = 1 + line + source.byte_to_line(range.end).unwrap();
     let end_col = 1 + source.byte_to_column(range.end).unwrap();
-    writeln!(output, "Error: {start_line}:{start_col}-{end_line}:{end_col}: {message}")
+    let kind = match severity {
+        Severity::Error => "Error",
+        Severity::Warning => "Warning",
+    };
+    writeln!(output, "{kind}: {start_line}:{start_col}-{end_line}:{end_col}: {message}")
         .unwrap();
 }
 
@@ -732,6 +773,49 @@ fn test_reparse(
     ok
 }
 
+/// Ensure that formatting the source is idempotent and doesn't change the
+/// produced frames.
+fn test_format(
+    output: &mut String,
+    world: &mut TestWorld,
+    src_path: &Path,
+    root: &SyntaxNode,
+    frames: Option<&[Frame]>,
+    i: usize,
+) -> bool {
+    let Some(formatted) = typst::syntax::format(root, 80) else { return true };
+
+    let reformatted = typst::syntax::format(&typst::syntax::parse(&formatted), 80);
+    if reformatted.as_ref() != Some(&formatted) {
+        writeln!(output, "    Subtest {i} is not formatted idempotently ❌").unwrap();
+        writeln!(output, "    Formatted once:\n{formatted}").unwrap();
+        writeln!(output, "    Formatted twice:\n{}", reformatted.unwrap_or_default())
+            .unwrap();
+        return false;
+    }
+
+    world.set(src_path, formatted.clone());
+    let mut tracer = Tracer::default();
+    let same = match (frames, typst::compile(world, &mut tracer)) {
+        (Some(frames), Ok(document)) => {
+            frames.is_empty() && document.pages.is_empty()
+                || !frames.is_empty()
+                    && !document.pages.is_empty()
+                    && render(frames).data() == render(&document.pages).data()
+        }
+        (None, Err(_)) => true,
+        _ => false,
+    };
+
+    if !same {
+        writeln!(output, "    Subtest {i} produces different output when formatted ❌")
+            .unwrap();
+        writeln!(output, "    Formatted source:\n{formatted}").unwrap();
+    }
+
+    same
+}
+
 /// Returns all leaf descendants of a node (may include itself).
 fn leafs(node: &SyntaxNode) -> Vec<SyntaxNode> {
     if node.children().len() == 0 {
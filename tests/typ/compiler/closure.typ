@@ -197,3 +197,94 @@
 ---
 // Error: 11 expected comma
 #let foo(_: 3) = none
+
+---
+// Type annotations on parameters and return values.
+#let scale(x as length or ratio, by as float: 1.0) as length or ratio = x * by
+#test(scale(2pt, by: 2), 4pt)
+#test(scale(50%), 50%)
+
+#let wrap(body as content) as content = [(#body)]
+#test(type(wrap("hi")), "content")
+#test(type(wrap(none)), "content")
+
+#let sum((a, b) as array) = a + b
+#test(sum((1, 2)), 3)
+
+#test(((x as "relative length") => x)(1pt + 10%), 1pt + 10%)
+#test(((x as none or auto) => repr(x))(auto), "auto")
+
+#let first(..args) as integer = {
+  return args.pos().first()
+}
+#test(first(1, 2), 1)
+
+---
+// Values are converted to the annotated type.
+#let f(x as float) = x
+#test(type(f(1)), "float")
+#test(f(2) / 4, 0.5)
+#let g(body as content or none) = body
+#test(type(g("hi")), "content")
+#test(g(none), none)
+#let h(x as "relative length") as "relative length" = x
+#test(type(h(1pt)), "relative length")
+#test(type(h(50%)), "relative length")
+
+---
+#let f(size as length) = size
+
+// Error: 4-9 expected length, found string
+#f("big")
+
+---
+#let f(gap as length or ratio: 1pt) = gap
+
+// Error: 9-11 expected length or ratio, found integer
+#f(gap: 12)
+
+---
+// Error: 23-27 expected length, found string
+#let f(gap as length: "no") = gap
+
+---
+// Error: 23-24 expected content, found integer
+#let f() as content = 1
+#f()
+
+---
+#let f(x) as string = {
+  // Error: 3-11 expected string, found integer
+  return x
+}
+#f(1)
+
+---
+// Error: 12 expected type name
+#let f(x as) = x
+
+---
+// Error: 8-16 type annotations are only allowed on closure parameters
+#(1, 2 as array)
+
+---
+// Error: 6-15 type annotations are only allowed on closure parameters
+#f(x as length: 1pt)
+
+---
+// Error: 13-19 unknown type `lenght`
+#let f(x as lenght) = x
+
+---
+// Error: 13-18 unknown type `strin`
+#let f() as strin = ""
+
+---
+// Error: 13-30 unknown type `relative lenght`
+#let f(x as "relative lenght") = x
+
+---
+// Types of the standard library's values can be named, too.
+#let f(c as counter or state) = type(c)
+#test(f(counter("x")), "counter")
+#test(f(state("x")), "state")
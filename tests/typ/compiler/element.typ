@@ -0,0 +1,102 @@
+// Test user-defined elements.
+// Ref: false
+
+---
+// Test construction and fields.
+#let note = element("note", (body, tone: "info") => [#tone: #body])
+#let it = note[Hi]
+#test(it.func(), note)
+#test(it.body, [Hi])
+#test(it.has("tone"), false)
+#test(note("Hey", tone: "warn").tone, "warn")
+#test(repr(note), "note")
+
+---
+// Test that defining the same element twice yields equal elements.
+#let make(tone) = element("note", (body, tone: tone) => body)
+#test(make("info"), make("info"))
+#test(make("info") == make("warn"), false)
+
+---
+// Test that the defining function displays the element.
+#let boxed = element("boxed", (body, inset: 2pt) => {
+  test(body, [A])
+  test(inset, 4pt)
+  box(inset: inset, body)
+})
+
+#boxed(inset: 4pt)[A]
+
+---
+// Test set and show rules.
+#let note = element("note", (body, tone: "info") => [#tone: #body])
+#set note(tone: "warn")
+#show note: it => {
+  test(it.tone, "warn")
+  test(it.body, [Careful])
+}
+
+#note[Careful]
+
+---
+// Test selecting elements by their fields, including defaults.
+#let note = element("note", (body, tone: "info") => body)
+#show note.where(tone: "warn"): it => test(it.body, [B])
+#show note.where(tone: "info"): it => test(it.body, [A])
+
+#note[A]
+#note(tone: "warn")[B]
+
+---
+// Test querying and counting elements.
+#let theorem = element("theorem", body => body)
+
+#theorem[A]
+#theorem[B]
+
+#locate(loc => {
+  test(query(theorem, loc).map(it => it.body), ([A], [B]))
+  test(counter(theorem).at(loc), (2,))
+})
+
+---
+// Test type annotations on fields.
+#let note = element("note", (body as content, tone as string: "info") => body)
+#note("Hi")
+
+// Error: 17-19 expected string, found integer
+#set note(tone: 12)
+
+---
+#let note = element("note", (body as content, tone as string: "info") => body)
+
+// Error: 7-9 expected content, found integer
+#note(12)
+
+---
+#let note = element("note", (body, tone: "info") => body)
+
+// Error: 6-8 missing argument: body
+#note()
+
+---
+#let note = element("note", (body, tone: "info") => body)
+
+// Error: 11-15 unexpected argument
+#set note([Hi])
+
+---
+// Error: 23-31 expected a closure defining the element's fields
+#let x = element("x", calc.abs)
+
+---
+// Error: 23-36 element fields must be plain identifiers
+#let x = element("x", ((a, b)) => a)
+
+---
+// Error: 23-39 elements cannot have an argument sink
+#let x = element("x", (..args) => none)
+
+---
+// Error: 18-20 element name must not be empty
+#let x = element("", body => body)
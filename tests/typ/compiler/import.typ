@@ -41,6 +41,56 @@
 #test(module.item(1, 2), 3)
 #test(module.push(2), 3)
 
+---
+// Renamed items.
+#import "module.typ": item as add, b as one, c
+#test(add(1, 2), 3)
+#test(one, 1)
+#test(c, 2)
+
+// A renamed module.
+#import "module.typ" as mod
+#test(mod.b, 1)
+
+// A renamed module with items.
+#import "module.typ" as other: d as three
+#test(other.d, 3)
+#test(three, 3)
+
+---
+// Private definitions are usable within the module, but wildcard imports
+// don't bring them into scope.
+#let _secret = "mine"
+#import "module.typ": *
+#test(reveal(), 4)
+#test(_secret, "mine")
+
+---
+// Error: 23-30 `_secret` is private to module `module`
+#import "module.typ": _secret
+
+---
+// Error: 23-30 `_secret` is private to module `module`
+#import "module.typ": _secret as secret
+
+---
+#import "module.typ"
+
+// Error: 9-16 `_secret` is private to module `module`
+#module._secret
+
+---
+// Error: 23-35 unresolved import
+#import "module.typ": non_existing as other
+
+---
+// Error: 24 expected identifier
+#import "module.typ" as
+
+---
+// Error: 27 expected identifier
+#import "module.typ": a as
+
 ---
 // Edge case for module access that isn't fixed.
 #import "module.typ"
@@ -0,0 +1,163 @@
+// Test match expressions.
+// Ref: false
+
+---
+// Test literal, type and placeholder patterns.
+#let describe(x) = match x {
+  none => "nothing",
+  0 => "zero",
+  -1 => "minus one",
+  "hi" => "greeting",
+  true => "yes",
+  type("integer") => "some integer",
+  type("string") => "some string",
+  _ => "something else",
+}
+
+#test(describe(none), "nothing")
+#test(describe(0), "zero")
+#test(describe(-1), "minus one")
+#test(describe(5), "some integer")
+#test(describe("hi"), "greeting")
+#test(describe("ho"), "some string")
+#test(describe(true), "yes")
+#test(describe(false), "something else")
+#test(describe(1pt), "something else")
+
+---
+// Test binding patterns and guards.
+#let sign(x) = match x {
+  n if n > 0 => "positive",
+  n if n < 0 => "negative",
+  zero => zero,
+}
+
+#test(sign(3), "positive")
+#test(sign(-3), "negative")
+#test(sign(0), 0)
+
+---
+// Test array patterns.
+#let shape(x) = match x {
+  () => "empty",
+  (a,) => "one: " + str(a),
+  (1, b) => "starts with one, then " + str(b),
+  (a, _, c) => str(a + c),
+  (first, ..rest) if rest.len() > 3 => "long",
+  (first, ..rest) => "more: " + str(rest.len()),
+}
+
+#test(shape(()), "empty")
+#test(shape((5,)), "one: 5")
+#test(shape((1, 2)), "starts with one, then 2")
+#test(shape((1, 2, 3)), "4")
+#test(shape((2, 2)), "more: 1")
+#test(shape((1, 2, 3, 4, 5)), "long")
+#test(shape((1, 2, 3, 4)), "more: 3")
+
+---
+// Test dictionary patterns.
+#let render(x) = match x {
+  (kind: "heading", body: b) => "= " + b,
+  (kind: "text", ..rest) => rest.at("body", default: ""),
+  (title,) => title,
+  type("dictionary") => "unknown",
+  type("array") => x.map(render).join(" "),
+  s => s,
+}
+
+#test(render((kind: "heading", body: "Intro")), "= Intro")
+#test(render((kind: "text", body: "Hello")), "Hello")
+#test(render((kind: "text")), "")
+#test(render((kind: "list")), "unknown")
+#test(render((
+  (kind: "heading", body: "A"),
+  (kind: "text", body: "b"),
+)), "= A b")
+#test(render("plain"), "plain")
+
+---
+// Test that arms with block bodies need no commas and that bindings don't
+// leak out of their arm.
+#let x = "outer"
+#let y = match (1, 2) {
+  (x, y) if x == y => { "same" }
+  (x, y) => {
+    x + y
+  }
+}
+#test(y, 3)
+#test(x, "outer")
+
+---
+// Test that a guard can end in an identifier or group.
+#let yes = true
+#test(match 1 { x if yes => "a", _ => "b" }, "a")
+#test(match 1 { x if (x > 2) => "a", _ => "b" }, "b")
+#test(match 1 { x if (x => x)(x) == 1 => "a", _ => "b" }, "a")
+
+---
+// Test that only calls to `type` are type patterns.
+#let kind(x) = x
+#test(match "dictionary" { kind("dictionary") => "equal", _ => "other" }, "equal")
+#test(match (:) { kind("dictionary") => "equal", _ => "other" }, "other")
+#test(match counter("x") { type("counter") => "counter", _ => "other" }, "counter")
+
+---
+// Test match in markup.
+#match 1 { 1 => [One], _ => [Other] }
+
+---
+// Test that `match` is only a keyword when followed by a value.
+#let match = 1
+#test(match, 1)
+#test(match + 1, 2)
+#let f(match) = match * 2
+#test(f(3), 6)
+#for match in (1, 2) { test(type(match), "integer") }
+#let d = (match: 3)
+#test(d.match, 3)
+
+---
+// Test closures capturing match bindings.
+#let f = x => match x {
+  (a, b) => () => a + b,
+  _ => () => x,
+}
+#test(f((1, 2))(), 3)
+#test(f(5)(), 5)
+
+---
+// Error: 8-9 no pattern matches the value
+#match 3 {
+  1 => "one",
+  2 => "two",
+}
+
+---
+// Error: 17-18 expected boolean, found integer
+#match 1 { x if 1 => x }
+
+---
+// Error: 9 expected match arms
+#match 1
+
+---
+// Error: 21-26 expected pattern, found binary expression
+#match (1, 2) { (a, 1 + 1) => a }
+
+---
+// Error: 18-19 at most one binding per identifier is allowed
+#match (1, 1) { (a, a) => a }
+
+---
+// Error: 26-32 nested patterns are not supported
+#match (1, (2, 3)) { (a, (b, c)) => a }
+
+---
+// Error: 26-32 nested patterns are not supported
+#match (a: (b: 1)) { (a: (b: x)) => x }
+
+---
+// Error: 17-25 unknown type `integr`
+#match 1 { type("integr") => 1 }
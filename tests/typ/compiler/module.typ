@@ -9,5 +9,7 @@
 #let item(a, b) = a + b
 #let push(a) = a + 1
 #let fn = rect.with(fill: conifer, inset: 5pt)
+#let _secret = 4
+#let reveal() = _secret
 
 Some _includable_ text.
@@ -0,0 +1,63 @@
+// Test package imports.
+// Ref: false
+
+---
+// Test import with items.
+#import "@test/adder:0.1.0": add, add-twice
+#test(add(2, 3), 5)
+#test(add-twice(2, 3), 10)
+
+---
+// Test import without items.
+#import "@test/adder:0.1.0"
+#test(adder.add(1, 1), 2)
+
+---
+// Absolute paths are relative to the package root.
+#import "@test/adder:0.1.0": data
+#test(data, "Hello from the package!\n")
+
+---
+// Paths given to a package are resolved within the package.
+#import "@test/reader:0.1.0": read-file
+#test(read-file("data.txt"), "Hello from the reader!\n")
+#test(read-file("/data.txt"), "Hello from the reader!\n")
+
+---
+// Error: 2:12-2:40 cannot access file outside of package
+#import "@test/reader:0.1.0": read-file
+#read-file("../../adder/0.1.0/data.txt")
+
+---
+// Error: 2:12-2:41 cannot access file outside of package
+#import "@test/reader:0.1.0": read-file
+#read-file("/../../adder/0.1.0/data.txt")
+
+---
+// Error: 2:12-2:52 cannot access file outside of package
+#import "@test/reader:0.1.0": read-file
+#read-file("/../../../../typ/compiler/packages.typ")
+
+---
+// Error: 9-30 package not found (searched for @test/missing:0.1.0)
+#import "@test/missing:0.1.0": x
+
+---
+// Error: 9-22 package specification is missing version
+#import "@test/adder": add
+
+---
+// Error: 9-26 version number is missing patch version
+#import "@test/adder:0.1": add
+
+---
+// Error: 9-29 `0a` is not a valid patch version
+#import "@test/adder:0.1.0a": add
+
+---
+// Error: 9-28 `Adder` is not a valid package name
+#import "@test/Adder:0.1.0": add
+
+---
+// Error: 9-31 package manifest contains mismatched version 0.2.0
+#import "@test/mismatch:0.1.0": x
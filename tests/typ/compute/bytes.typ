@@ -0,0 +1,41 @@
+// Test the bytes type.
+// Ref: false
+
+---
+// Test conversion from and to strings and arrays.
+#let data = bytes("Hello")
+#test(data.len(), 5)
+#test(data.at(0), 72)
+#test(data.at(-1), 111)
+#test(str(data), "Hello")
+#test(array(data), (72, 101, 108, 108, 111))
+#test(bytes((72, 105)), bytes("Hi"))
+#test(str(data.slice(1, 3)), "el")
+#test(str(data.slice(-3)), "llo")
+#test(str(data.slice(1, count: 2)), "el")
+#test(bytes("Hel") + bytes("lo"), data)
+#test(bytes(()).len(), 0)
+#test(type(data), "bytes")
+#test(repr(data), "bytes(5)")
+
+---
+// Test reading a file in binary mode.
+#let data = read("/hello.txt", encoding: none)
+#test(type(data), "bytes")
+#test(str(data), "Hello, world!")
+
+---
+// Error: 2-22 byte index out of bounds (index: 5, len: 5)
+#bytes("Hello").at(5)
+
+---
+// Error: 8-14 byte must be between 0 and 255, found 256
+#bytes((256,))
+
+---
+// Error: 8-14 expected integer, found string
+#bytes(("a",))
+
+---
+// Error: 6-38 bytes are not valid utf-8
+#str(read("/bad.txt", encoding: none))
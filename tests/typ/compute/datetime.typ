@@ -0,0 +1,93 @@
+// Test datetimes and durations.
+// Ref: false
+
+---
+// Test date display.
+#let date = datetime(year: 2023, month: 5, day: 1)
+#test(date.display(), "2023-05-01")
+#test(date.display("[day].[month].[year]"), "01.05.2023")
+#test(date.display("[month repr:long] [day padding:none]"), "May 1")
+#test(date.display("[weekday]"), "Monday")
+#test(date.year(), 2023)
+#test(date.month(), 5)
+#test(date.day(), 1)
+#test(date.weekday(), 1)
+#test(date.ordinal(), 121)
+#test(date.hour(), none)
+
+---
+// Test time and datetime display.
+#let time = datetime(hour: 18, minute: 2, second: 23)
+#test(time.display(), "18:02:23")
+#test(time.display("[hour repr:12][period]"), "06PM")
+#test(time.year(), none)
+#test(time.minute(), 2)
+#test(
+  datetime(year: 2000, month: 2, day: 29, hour: 1, minute: 2, second: 3).display(),
+  "2000-02-29 01:02:03",
+)
+
+---
+// Test comparison and arithmetic.
+#let a = datetime(year: 2023, month: 5, day: 1)
+#let b = datetime(year: 2023, month: 5, day: 11)
+#test(a < b, true)
+#test(a == datetime(year: 2023, month: 5, day: 1), true)
+#test((b - a).days(), 10.0)
+#test(a + duration(days: 10), b)
+#test(b - duration(weeks: 1, days: 3), a)
+#test(duration(hours: 2, minutes: 30).minutes(), 150.0)
+#test(duration(days: 1) * 7, duration(weeks: 1))
+#test(duration(weeks: 1) / duration(days: 1), 7.0)
+#test(-duration(seconds: 5) + duration(seconds: 5), duration())
+#test(repr(duration(days: 8, seconds: 3)), "duration(weeks: 1, days: 1, seconds: 3)")
+#test(repr(a), "datetime(year: 2023, month: 5, day: 1)")
+
+---
+// Test today.
+#test(datetime.today().display(), "1970-01-01")
+#test(type(datetime.today(offset: 2)), "datetime")
+
+---
+// Error: 10-41 date is invalid
+#datetime(year: 2023, month: 2, day: 30)
+
+---
+// Error: 10-31 time or date is incomplete
+#datetime(year: 2023, month: 2)
+
+---
+// Error: 10-12 at least one of date or time must be fully specified
+#datetime()
+
+---
+// Error: 2-58 missing closing bracket for bracket at index 0
+#datetime(hour: 1, minute: 2, second: 3).display("[hour")
+
+---
+// Error: 3-61 cannot subtract datetimes of different kinds
+#(datetime(hour: 1, minute: 2, second: 3) - datetime.today())
+
+---
+// Error: 3-31 duration is out of range
+#(duration(seconds: 1) * 1e300)
+
+---
+// Error: 3-32 duration is out of range
+#(duration(seconds: 1) / 1e-300)
+
+---
+// Error: 3-34 duration is out of range
+#(calc.nan * duration(seconds: 1))
+
+---
+// Error: 3-68 duration is out of range
+#(duration(weeks: 10000000000000) + duration(weeks: 10000000000000))
+
+---
+// Error: 3-68 duration is out of range
+#(-(duration(seconds: -9223372036854775807) - duration(seconds: 1)))
+
+---
+// Error: 25-27 offset must be between -24 and 24 hours
+#datetime.today(offset: 25)
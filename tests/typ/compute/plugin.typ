@@ -0,0 +1,39 @@
+// Test WebAssembly plugins.
+// Ref: false
+
+---
+#let p = plugin("/hello.wasm")
+#test(type(p), "module")
+#test(str(p.hello()), "Hello")
+#test(str(p.echo(bytes("Hi"))), "Hi")
+#test(p.echo(bytes(())), bytes(()))
+
+---
+// Test importing from a plugin.
+#import plugin("/hello.wasm"): hello
+#test(str(hello()), "Hello")
+
+---
+#let p = plugin("/hello.wasm")
+// Error: 2-8 plugin errored with: oops
+#p.fail()
+
+---
+#let p = plugin("/hello.wasm")
+// Error: 9-13 expected bytes, found string
+#p.echo("Hi")
+
+---
+#let p = plugin("/hello.wasm")
+// Error: 2-9 wrong number of arguments for plugin function `hello` (expected 0, found 1)
+#p.hello(bytes("Hi"))
+
+---
+// A plugin that doesn't terminate runs out of fuel.
+#let p = plugin("/loop.wasm")
+// Error: 2-8 plugin ran out of fuel
+#p.loop()
+
+---
+// Error: 17-32 file not found (searched at /missing.wasm)
+#let p = plugin("/missing.wasm")
@@ -0,0 +1,58 @@
+// Test configurable widow and orphan prevention and keeping headings with
+// the content after them.
+// Ref: false
+
+---
+// The heading moves along with the first lines of the paragraph.
+#set page(height: 100pt)
+#set par(orphans: 3)
+#v(36pt)
+= Introduction <intro>
+#lorem(20)
+
+#locate(loc => {
+  let intro = query(<intro>, loc).first()
+  test(counter(page).at(intro.location()), (2,))
+})
+
+---
+// Widows and orphans can be allowed. Four lines fit on a page.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt, orphans: 1, widows: 1)
+#let on-page(n) = locate(loc => test(loc.position().page, n))
+A #on-page(1) \
+B \
+C \
+D #on-page(1) \
+E #on-page(2)
+
+---
+// More lines than needed move to the next page to keep the last four lines
+// together.
+#set page(height: 100pt, margin: 10pt)
+#set text(top-edge: 10pt, bottom-edge: 0pt)
+#set par(leading: 10pt, widows: 4)
+#let on-page(n) = locate(loc => test(loc.position().page, n))
+A \
+B #on-page(1) \
+C #on-page(2) \
+D \
+E \
+F #on-page(2)
+
+---
+// The heading moves along with a table that doesn't fit at all.
+#set page(height: 100pt)
+#v(24pt)
+= Results <results>
+#table(rect(height: 40pt))
+
+#locate(loc => {
+  let results = query(<results>, loc).first()
+  test(counter(page).at(results.location()), (2,))
+})
+
+---
+// Error: 19-20 number must be positive
+#set par(orphans: 0)
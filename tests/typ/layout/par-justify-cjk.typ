@@ -25,6 +25,7 @@
 ---
 // Test punctuation whitespace adjustment
 #set page(width: auto)
+// Warning: 29-48 unknown font family: noto serif cjk sc
 #set text(lang: "zh", font: "Noto Serif CJK SC", overhang: false)
 #set par(justify: true)
 #rect(inset: 0pt, width: 80pt, fill: rgb("eee"))[
@@ -0,0 +1,76 @@
+// Test floating placement.
+// Ref: false
+
+---
+#set page(height: 150pt)
+#let note(where, body) = place(
+  center + where,
+  float: true,
+  clearance: 6pt,
+  rect(body),
+)
+
+#let at-bottom = locate(loc => {
+  let pos = loc.position()
+  test(pos.page, 1)
+  test(pos.y > 75pt, true)
+})
+
+#lorem(10)
+#note(bottom)[Bottom 1 #at-bottom]
+#note(bottom)[Bottom 2 #at-bottom]
+#lorem(40)
+#note(top)[Top #locate(loc => test(loc.position().y < 40pt, true))]
+#lorem(10)
+
+---
+// Floats without a vertical alignment go to the closer end of the page.
+#set page(width: 150pt, height: 120pt)
+#let note(body) = place(float: true, clearance: 4pt, rect(height: 10pt, body))
+#note[Top #locate(loc => test(loc.position().y < 40pt, true))]
+#lorem(8)
+#note[Bottom #locate(loc => test(loc.position().y > 60pt, true))]
+
+---
+// Floats that don't fit move to the next page and keep their order.
+#set page(height: 120pt)
+#let on-page(n) = locate(loc => test(loc.position().page, n))
+#block(height: 50pt)
+#place(top, float: true, clearance: 5pt, rect(height: 50pt)[A #on-page(2)])
+#place(bottom, float: true, clearance: 5pt, rect(height: 10pt)[B #on-page(2)])
+#lorem(10) #on-page(1)
+
+---
+// A float that is taller than the page gets a page of its own.
+#set page(height: 80pt)
+#let on-page(n) = locate(loc => test(loc.position().page, n))
+#lorem(5) #on-page(1)
+#place(top, float: true, rect(height: 100pt)[A #on-page(2)])
+
+---
+// Floating figures can still be referenced and outlined.
+#set page(height: 150pt, numbering: "1")
+#outline(target: figure)
+@big shows something.
+
+#figure(
+  placement: auto,
+  rect(height: 60pt),
+  caption: [A big figure],
+) <big>
+
+#figure(
+  placement: top,
+  rect(height: 20pt),
+  caption: [A small figure],
+)
+
+#lorem(20)
+#locate(loc => {
+  let page = counter(page).at(query(<big>, loc).first().location())
+  test(page, (2,))
+})
+
+---
+// Error: 2-32 floating placement must be `top` or `bottom`
+#place(horizon, float: true)[A]
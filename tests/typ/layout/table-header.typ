@@ -0,0 +1,52 @@
+// Test repeated table headers and footers.
+// Ref: false
+
+---
+#set page(height: 100pt)
+#table(
+  columns: 2,
+  table.header[*Name*][*Value*],
+  ..range(12).map(n => ([Item #n], [#(n * n)])).flatten(),
+  table.footer[*Sum*][506],
+)
+
+---
+// A header that doesn't repeat only appears once.
+#set page(height: 100pt)
+#table(
+  columns: 2,
+  gutter: 2pt,
+  table.header(repeat: false, table.cell(colspan: 2)[*Squares*]),
+  ..range(12).map(n => ([#n], [#(n * n)])).flatten(),
+)
+
+---
+// The header moves to the next page if no other row fits below it.
+#set page(height: 100pt)
+#let on-page(n) = locate(loc => test(loc.position().page, n))
+#v(65pt)
+#table(
+  table.header[*Header* #on-page(2)],
+  [A #on-page(2)], [B],
+)
+
+---
+// A header with a cell spanning multiple rows.
+#set page(height: 120pt)
+#table(
+  columns: 3,
+  table.header(
+    table.cell(rowspan: 2)[*Name*],
+    table.cell(colspan: 2)[*Values*],
+    [Min], [Max],
+  ),
+  ..range(15).map(n => ([#n], [#(n - 1)], [#(n + 1)])).flatten(),
+)
+
+---
+// Error: 13-28 table header must be the first child
+#table([A], table.header[B])
+
+---
+// Error: 8-23 table footer must be the last child
+#table(table.footer[A], [B])
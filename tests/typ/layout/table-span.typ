@@ -0,0 +1,56 @@
+// Test table cells that span multiple columns and rows.
+// Ref: false
+
+---
+#table(
+  columns: 3,
+  fill: (x, y) => if y == 0 { luma(230) },
+  table.cell(colspan: 3, align: center)[*Results*],
+  table.cell(rowspan: 2)[Runs], [Fast], [Slow],
+  [12], [7],
+  [Total], table.cell(colspan: 2, fill: yellow)[19],
+)
+
+---
+// Spanning cells grow the auto columns they cover, but not fractional ones.
+#table(
+  columns: (auto, auto, 1fr),
+  table.cell(colspan: 2)[A rather wide cell], [B],
+  [C], [D], [E],
+  table.cell(colspan: 3)[Wider than a single column],
+)
+
+---
+// Cells that don't fit into the remaining columns move to the next row.
+#let first-column = locate(loc => test(loc.position().x < 20pt, true))
+#table(
+  columns: 2,
+  gutter: 3pt,
+  [#first-column A], table.cell(colspan: 2)[#first-column B],
+  table.cell(colspan: 5, inset: 2pt)[#first-column C],
+)
+
+---
+// A merged cell spanning rows that break across pages.
+#set page(height: 4cm)
+#table(
+  columns: 2,
+  table.cell(rowspan: 4)[#lorem(40)],
+  [A], [B], [C], [D],
+)
+
+---
+#set text(dir: rtl)
+#table(
+  columns: 3,
+  table.cell(colspan: 2)[A], [B],
+  [C], table.cell(rowspan: 2)[D], [E],
+  [F], [G],
+)
+
+---
+#test(table.cell(rowspan: 2)[A].rowspan, 2)
+
+---
+// Error: 22-23 number must be positive
+#table.cell(colspan: 0)[A]
@@ -0,0 +1,69 @@
+// Test per-cell strokes and explicit table lines.
+// Ref: false
+
+---
+// A booktabs-like table with explicit lines.
+#table(
+  columns: 3,
+  stroke: none,
+  table.hline(stroke: 1pt),
+  [*Name*], [*Min*], [*Max*],
+  table.hline(stroke: 0.5pt),
+  [A], [1], [3],
+  [B], [2], [4],
+  table.hline(stroke: 1pt),
+)
+
+---
+// Strokes as a function of the cell position.
+#table(
+  columns: 3,
+  stroke: (x, y) => (
+    top: if y == 0 { 1pt } else if y == 1 { 0.5pt },
+    bottom: if y == 2 { 1pt },
+  ),
+  [*Name*], [*Min*], [*Max*],
+  [A], [1], [3],
+  [B], [2], [4],
+)
+
+---
+// Strokes set on a cell take precedence over the table's stroke.
+#table(
+  columns: 3,
+  gutter: 2pt,
+  stroke: (x: none, y: 0.5pt),
+  [A], table.cell(stroke: (bottom: 2pt + red, left: blue))[B], [C],
+  table.cell(colspan: 2, stroke: none)[D], [E],
+  [F], [G], [H],
+)
+
+---
+// Partial lines and vertical lines.
+#set text(dir: rtl)
+#table(
+  columns: 4,
+  stroke: none,
+  [A], table.vline(stroke: red), [B], [C], [D],
+  table.hline(start: 1, end: 3),
+  [E], [F], table.cell(rowspan: 2)[G], [H],
+  table.hline(y: 2, stroke: 2pt),
+  [I], [J], [K],
+  table.vline(x: 3, start: 1),
+)
+
+---
+// Lines may follow the footer.
+#table(
+  [A],
+  table.footer[B],
+  table.hline(),
+)
+
+---
+// Error: 16-21 expected length, color, dictionary, stroke, none, or function, found string
+#table(stroke: "red")
+
+---
+// Error: 27-33 expected length, color, dictionary, stroke, or none, found function
+#table(table.cell(stroke: x => x)[A])
@@ -0,0 +1,46 @@
+// Test footnotes.
+// Ref: false
+
+---
+// Entries are placed at the bottom of the page the footnote is on.
+#set page(height: 120pt)
+#let at-bottom = locate(loc => {
+  let pos = loc.position()
+  test(pos.page, 1)
+  test(pos.y > 70pt, true)
+})
+
+Hello#footnote[First #at-bottom]
+and world#footnote[Second #at-bottom].
+#locate(loc => test(loc.position().y < 40pt, true))
+
+---
+// Footnotes are counted throughout the document.
+#set page(height: 120pt)
+Hello#footnote[First]
+and world#footnote[Second].
+#locate(loc => test(counter(footnote).at(loc), (2,)))
+#locate(loc => test(query(footnote, loc).len(), 2))
+
+---
+// The counter can be reset, e.g. per chapter.
+#set page(height: 120pt)
+#footnote[A]
+#counter(footnote).update(0)
+#footnote[B]
+#locate(loc => test(counter(footnote).at(loc), (1,)))
+
+---
+// Long footnotes break across pages.
+#set page(height: 100pt)
+#set footnote(separator: line(length: 50%), gap: 0.8em)
+Text#footnote[
+  #locate(loc => test(loc.position().page, 1))
+  #lorem(80)
+  #locate(loc => test(loc.position().page > 1, true))
+]
+#locate(loc => test(counter(page).final(loc).first() > 1, true))
+
+---
+// Error: 10-12 missing argument: body
+#footnote()
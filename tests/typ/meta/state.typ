@@ -37,3 +37,12 @@ Was: #locate(location => {
 #trait[Adventure]
 #trait[Fear]
 #trait[Anger]
+
+---
+// Test that layout which does not converge is reported.
+// Ref: false
+#let s = state("grow", 1)
+// Warning: 2-43 layout did not converge within 5 attempts: the result of this call kept changing
+#locate(loc => s.update(s.final(loc) + 1))
+// Warning: 2-13 layout did not converge within 5 attempts: the result of this call kept changing
+#s.display()
@@ -5,6 +5,7 @@
 #set text(size: 8pt)
 
 #let try(top, bottom) = rect(inset: 0pt, fill: conifer)[
+  // Warning: 19-34 unknown font family: ibm plex mono
   #set text(font: "IBM Plex Mono", top-edge: top, bottom-edge: bottom)
   From #top to #bottom
 ]
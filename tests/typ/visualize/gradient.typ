@@ -0,0 +1,74 @@
+// Test gradients.
+// Ref: false
+
+---
+// Test gradient construction and methods.
+#let g = gradient.linear(red, blue)
+#test(type(g), "gradient")
+#test(g.kind(), "linear")
+#test(g.stops(), ((red, 0%), (blue, 100%)))
+#test(g.angle(), 0deg)
+#test(g.relative(), auto)
+#test(g.sample(0%), red)
+#test(g.sample(100%), blue)
+#test(gradient.linear(black, white).sample(50%), rgb(128, 128, 128))
+#test(gradient.radial(red, blue).angle(), none)
+#test(gradient.conic(red, blue, angle: 90deg).angle(), 90deg)
+#test(gradient.conic(red, blue, relative: "parent").relative(), "parent")
+
+---
+// Test stop distribution and padding.
+#test(
+  gradient.linear(red, green, blue).stops(),
+  ((red, 0%), (green, 50%), (blue, 100%)),
+)
+#test(
+  gradient.radial((red, 20%), (blue, 80%)).stops(),
+  ((red, 0%), (red, 20%), (blue, 80%), (blue, 100%)),
+)
+
+---
+// Test representation.
+#test(
+  repr(gradient.linear(red, blue, angle: 45deg)),
+  "gradient.linear((rgb(\"#ff4136\"), 0%), (rgb(\"#0074d9\"), 100%), angle: 45deg)",
+)
+#test(
+  repr(gradient.radial(red, blue, center: (20%, 30%), relative: "self")),
+  "gradient.radial((rgb(\"#ff4136\"), 0%), (rgb(\"#0074d9\"), 100%), center: (20%, 30%), relative: \"self\")",
+)
+
+---
+// Test gradients as paints.
+#set page(width: 140pt)
+#rect(width: 100%, height: 20pt, fill: gradient.linear(red, yellow, blue))
+#rect(width: 100%, height: 20pt, stroke: 4pt + gradient.linear(red, blue, angle: 90deg))
+#stack(
+  dir: ltr,
+  spacing: 5pt,
+  circle(radius: 15pt, fill: gradient.radial(white, blue)),
+  circle(radius: 15pt, fill: gradient.conic(red, yellow, green, blue, red)),
+  square(size: 30pt, fill: gradient.linear(red, blue, relative: "parent")),
+)
+#text(fill: gradient.linear(red, blue))[Colorful text]
+#table(
+  columns: 3,
+  fill: gradient.linear(white, aqua),
+  [A], [B], [C],
+)
+
+---
+// Error: 17-22 a gradient must have at least two stops
+#gradient.linear(red)
+
+---
+// Error: 29-33 either all or no stops must have an offset
+#gradient.linear((red, 0%), blue)
+
+---
+// Error: 30-41 offsets must be in ascending order
+#gradient.linear((red, 50%), (blue, 20%))
+
+---
+// Error: 18-29 offset must be between 0% and 100%
+#gradient.linear((red, 150%), blue)
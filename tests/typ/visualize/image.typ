@@ -60,3 +60,12 @@ A #box(image("/tiger.jpg", height: 1cm, width: 80%)) B
 ---
 // Error: 8-18 failed to parse svg: found closing tag 'g' instead of 'style' in line 4
 #image("/bad.svg")
+
+---
+// Test loading an image from bytes.
+// Ref: false
+#image(read("/rhino.png", encoding: none), format: "png", width: 1cm)
+
+---
+// Error: 2-43 format must be specified when loading from bytes
+#image(read("/rhino.png", encoding: none))